@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Decodes arbitrary bytes as a Sandboxed Execution (`{"chain": [...]}`) under
+//! the default limits and runs enforced acceptance on its outer chain, against
+//! the fixture registry and sandbox origin. Any outcome but a panic is a pass.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pic::{
+    accept_guarded_crossing, fixtureset, from_untrusted_bytes, Limits, SandboxedExecution, Verifier,
+};
+use std::sync::OnceLock;
+
+/// The fixture cast and its authorized sandbox origin, loaded (keystore KDF
+/// included) once per process, not per input.
+fn fixtures() -> &'static (&'static fixtureset::Set, [String; 1]) {
+    static FIXTURES: OnceLock<(&'static fixtureset::Set, [String; 1])> = OnceLock::new();
+    FIXTURES.get_or_init(|| {
+        let set = fixtureset::load().expect("fixtures");
+        (set, [set.identity("enforcement-origin").id.clone()])
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (set, origins) = fixtures();
+    let Ok(sx) = from_untrusted_bytes::<SandboxedExecution>(data, &Limits::default()) else {
+        return;
+    };
+    // Wall-clock time, so seeds from `picdemo dump` are within their windows.
+    let mut verifier = Verifier::new(&set.registry, None);
+    let _ = accept_guarded_crossing(&mut verifier, origins, &sx.chain, chrono::Utc::now());
+});
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Feeds arbitrary bytes to `Verifier::verify_envelope_bytes` (JSON or
+//! COSE_Sign1) under the default limits, against the fixture registry. Any
+//! outcome but a panic is a pass.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pic::{fixtureset, Verifier};
+use std::sync::OnceLock;
+
+/// The fixture cast, loaded (keystore KDF included) once per process, not per
+/// input.
+fn fixtures() -> &'static fixtureset::Set {
+    static SET: OnceLock<&'static fixtureset::Set> = OnceLock::new();
+    SET.get_or_init(|| fixtureset::load().expect("fixtures"))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let set = fixtures();
+    // Wall-clock time, so seeds from `picdemo dump` are within their windows.
+    let _ = Verifier::new(&set.registry, None).verify_envelope_bytes(data, chrono::Utc::now());
+});
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Attestation-by-reference: a PoR may carry only the content digest of the
+//! executor attestation (`executorAttestationDigest`) instead of embedding it.
+//!
+//! The PCA signature covers the digest and the digest pins the whole signed
+//! attestation, so a referenced attestation is bound exactly as an embedded
+//! one; the Verifier resolves it and then runs the same attestation and
+//! conformance checks. Referenced attestations travel once per envelope in its
+//! `attestations` side table, or are resolved from an [`AttestationCache`].
+
+use crate::types::Attestation;
+use std::collections::HashMap;
+
+/// The signed attestations a Verifier may resolve references against, keyed
+/// by content digest. Entries are not trusted: a resolved attestation is
+/// verified like an embedded one.
+#[derive(Default)]
+pub struct AttestationCache {
+    by_digest: HashMap<String, Attestation>,
+}
+
+impl AttestationCache {
+    pub fn new() -> AttestationCache {
+        AttestationCache::default()
+    }
+
+    /// Adds an attestation and returns the digest a PoR references it by.
+    pub fn insert(&mut self, att: Attestation) -> String {
+        let digest = att.digest();
+        self.by_digest.insert(digest.clone(), att);
+        digest
+    }
+
+    /// The attestation whose content digest is `digest`, if known.
+    pub fn get(&self, digest: &str) -> Option<&Attestation> {
+        self.by_digest.get(digest)
+    }
+
+    /// The cached attestations, e.g. to ship them once as a side table.
+    pub fn iter(&self) -> impl Iterator<Item = &Attestation> {
+        self.by_digest.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_digest.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_digest.is_empty()
+    }
+}
+
+/// Builds a cache from a chain's side table.
+impl FromIterator<Attestation> for AttestationCache {
+    fn from_iter<I: IntoIterator<Item = Attestation>>(iter: I) -> AttestationCache {
+        let mut cache = AttestationCache::new();
+        for att in iter {
+            cache.insert(att);
+        }
+        cache
+    }
+}
@@ -4,111 +4,420 @@
 // Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
 
 //! The reference authority profile (Prover/Verifier spec §4): operations as a set
-//! with subset inclusion, an execution-contract order, a conformance function,
-//! and the executed-vs-signed / PDP authorization check.
+//! of patterns ordered by subsumption (see [`crate::operation`]), an
+//! execution-contract order, quantity constraints kept or tightened hop by hop,
+//! a conformance function, and the executed-vs-signed / PDP authorization
+//! check.
+//!
+//! The three checks together are an [`AuthorityProfile`]. The Prover, the
+//! Verifier, snapshot issuance and the guardrail run whichever profile they
+//! are given, [`ReferenceProfile`] unless set. A PCA0 records the profile it
+//! was minted under, and a Verifier refuses a lineage minted under a profile
+//! other than its own: two profiles may order the same invariants differently,
+//! so a chain is only sound under the profile that checked its hops.
 
-use crate::types::{ContractAttributes, ExecutionContract, Invariants, Request};
-use crate::PicResult;
+use crate::diff::{diff_under, InvariantDiff};
+use crate::execution_model::ModelLattice;
+use crate::operation::Operation;
+use crate::types::{ContractAttributes, ExecutionContract, Invariants, Pca, Request};
+use crate::REFERENCE_AUTHORITY_PROFILE;
+use std::fmt;
 
-/// Reports the error, if any, that makes `current` an invalid (expansive)
-/// continuation of `predecessor`. `Ok(())` means current ≤ predecessor under the
-/// reference attenuation order (non-expansion, §2.4 / §3.3 check 6).
-pub fn attenuates(current: &Invariants, predecessor: &Invariants) -> PicResult<()> {
-    for op in &current.operations {
-        if !predecessor.operations.contains(op) {
-            return Err(format!(
-                "non-expansion: operation {op:?} not present in predecessor"
-            ));
+/// A reference-profile rule broken by a successor, an executor, or a request,
+/// carrying the offending values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    // Non-expansion (§2.4).
+    OperationAdded {
+        operation: String,
+    },
+    RoleRelaxed {
+        current: String,
+        predecessor: String,
+    },
+    ComplianceDropped {
+        compliance: String,
+    },
+    ExecutionModelRelaxed {
+        current: String,
+        predecessor: String,
+    },
+    // Either check: a model the execution-model lattice does not declare.
+    UnknownExecutionModel {
+        model: String,
+    },
+    AllowedRelaxed {
+        field: &'static str,
+        current: Vec<String>,
+        predecessor: Vec<String>,
+    },
+    ConstraintDropped {
+        name: String,
+    },
+    ConstraintRelaxed {
+        name: String,
+        current: String,
+        predecessor: String,
+    },
+    // Conformance (§4.2).
+    RoleNotSatisfied {
+        attested: String,
+        required: String,
+    },
+    ComplianceMissing {
+        compliance: String,
+    },
+    ExecutionModelNotPermitted {
+        attested: String,
+        permitted: String,
+    },
+    NotAllowed {
+        field: &'static str,
+        attested: String,
+        allowed: Vec<String>,
+    },
+    // Authorization (§4.3).
+    NotAuthorized {
+        requested: String,
+        granted: Vec<String>,
+    },
+    QuantityUndeclared {
+        name: String,
+        constraint: String,
+    },
+    QuantityExceeded {
+        name: String,
+        declared: String,
+        constraint: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::OperationAdded { operation } => write!(
+                f,
+                "non-expansion: operation {operation:?} not present in predecessor"
+            ),
+            Violation::RoleRelaxed {
+                current,
+                predecessor,
+            } => write!(
+                f,
+                "non-expansion: role {current:?} relaxes predecessor role {predecessor:?}"
+            ),
+            Violation::ComplianceDropped { compliance } => {
+                write!(f, "non-expansion: compliance {compliance:?} dropped")
+            }
+            Violation::ExecutionModelRelaxed {
+                current,
+                predecessor,
+            } => write!(
+                f,
+                "non-expansion: executionModel {current:?} relaxes predecessor {predecessor:?}"
+            ),
+            Violation::UnknownExecutionModel { model } => {
+                write!(f, "unknown executionModel {model:?}")
+            }
+            Violation::AllowedRelaxed {
+                field,
+                current,
+                predecessor,
+            } => write!(
+                f,
+                "non-expansion: {field} {} relaxes predecessor {}",
+                go_slice(current),
+                go_slice(predecessor)
+            ),
+            Violation::ConstraintDropped { name } => {
+                write!(f, "non-expansion: constraint {name:?} dropped")
+            }
+            Violation::ConstraintRelaxed {
+                name,
+                current,
+                predecessor,
+            } => write!(
+                f,
+                "non-expansion: constraint {name:?} ({current}) relaxes predecessor ({predecessor})"
+            ),
+            Violation::RoleNotSatisfied { attested, required } => write!(
+                f,
+                "conformance: executor role {attested:?} does not satisfy required role {required:?}"
+            ),
+            Violation::ComplianceMissing { compliance } => write!(
+                f,
+                "conformance: executor lacks required compliance {compliance:?}"
+            ),
+            Violation::ExecutionModelNotPermitted {
+                attested,
+                permitted,
+            } => write!(
+                f,
+                "conformance: executionModel {attested:?} not permitted by contract {permitted:?}"
+            ),
+            Violation::NotAllowed {
+                field,
+                attested,
+                allowed,
+            } => write!(
+                f,
+                "conformance: executor {field} {attested:?} not in {}",
+                go_slice(allowed)
+            ),
+            Violation::NotAuthorized { requested, granted } => write!(
+                f,
+                "not authorized: {requested:?} not covered by {}",
+                go_slice(granted)
+            ),
+            Violation::QuantityUndeclared { name, constraint } => write!(
+                f,
+                "not authorized: quantity {name:?} ({constraint}) not declared"
+            ),
+            Violation::QuantityExceeded {
+                name,
+                declared,
+                constraint,
+            } => write!(
+                f,
+                "not authorized: quantity {name:?} = {declared} not within {constraint}"
+            ),
         }
     }
-    attenuates_contract(&current.execution_contract, &predecessor.execution_contract)
 }
 
-/// Checks that every predecessor contract constraint is preserved or strengthened
-/// by `current` (§2.4).
-fn attenuates_contract(
-    current: &ExecutionContract,
-    predecessor: &ExecutionContract,
-) -> PicResult<()> {
-    if !predecessor.role.is_empty() && current.role != predecessor.role {
-        return Err(format!(
-            "non-expansion: role {:?} relaxes predecessor role {:?}",
-            current.role, predecessor.role
-        ));
+impl std::error::Error for Violation {}
+
+/// The semantics of authority along a lineage: the attenuation order on
+/// invariants, the conformance of an executor to an execution contract, and
+/// the authorization of a concrete request. Implementations are identified by
+/// [`AuthorityProfile::id`], which a PCA0 records (as `authorityProfile`).
+pub trait AuthorityProfile: Send + Sync {
+    /// The identifier PCA0s minted under this profile carry.
+    fn id(&self) -> &str;
+
+    /// Reports the violation, if any, that makes `current` an expansive
+    /// continuation of `predecessor` (§3.3 check 6).
+    fn attenuates(&self, current: &Invariants, predecessor: &Invariants) -> Result<(), Violation>;
+
+    /// Reports the violation, if any, that makes an executor's attested
+    /// attributes fail `contract` (§3.3 check 5).
+    fn conforms(
+        &self,
+        att: &ContractAttributes,
+        contract: &ExecutionContract,
+    ) -> Result<(), Violation>;
+
+    /// Reports the violation, if any, that keeps `inv` from permitting `req`
+    /// (§4.3).
+    fn authorize(&self, inv: &Invariants, req: &Request) -> Result<(), Violation>;
+}
+
+/// The reference profile: [`attenuates`], [`conforms`] and [`authorize`],
+/// with execution models ordered by `models`, [`ModelLattice::reference`]
+/// unless set.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceProfile {
+    models: Option<ModelLattice>,
+}
+
+impl ReferenceProfile {
+    /// The reference profile over the reference execution-model lattice.
+    pub const DEFAULT: ReferenceProfile = ReferenceProfile { models: None };
+
+    /// The reference profile with execution models ordered by `models`.
+    pub fn with_models(models: ModelLattice) -> ReferenceProfile {
+        ReferenceProfile {
+            models: Some(models),
+        }
     }
-    for c in &predecessor.compliance {
-        if !current.compliance.contains(c) {
-            return Err(format!("non-expansion: compliance {c:?} dropped"));
+
+    /// The execution-model order this profile checks contracts under.
+    pub fn models(&self) -> &ModelLattice {
+        match &self.models {
+            Some(models) => models,
+            None => ModelLattice::reference(),
         }
     }
-    if model_rank(&current.execution_model) > model_rank(&predecessor.execution_model) {
-        return Err(format!(
-            "non-expansion: executionModel {:?} relaxes predecessor {:?}",
-            current.execution_model, predecessor.execution_model
-        ));
+
+    /// Explains `current` as a continuation of `predecessor` under this
+    /// profile (see [`crate::diff`]).
+    pub fn diff(&self, current: &Invariants, predecessor: &Invariants) -> InvariantDiff {
+        diff_under(self.models(), current, predecessor)
+    }
+}
+
+impl AuthorityProfile for ReferenceProfile {
+    fn id(&self) -> &str {
+        REFERENCE_AUTHORITY_PROFILE
+    }
+
+    fn attenuates(&self, current: &Invariants, predecessor: &Invariants) -> Result<(), Violation> {
+        attenuates_under(self.models(), current, predecessor)
+    }
+
+    fn conforms(
+        &self,
+        att: &ContractAttributes,
+        contract: &ExecutionContract,
+    ) -> Result<(), Violation> {
+        conforms_under(self.models(), att, contract)
+    }
+
+    fn authorize(&self, inv: &Invariants, req: &Request) -> Result<(), Violation> {
+        authorize(inv, req)
+    }
+}
+
+/// The authority profile `p` was minted under: its recorded `authorityProfile`,
+/// or the reference profile's when none is recorded.
+pub fn profile_of(p: &Pca) -> &str {
+    if p.authority_profile.is_empty() {
+        REFERENCE_AUTHORITY_PROFILE
+    } else {
+        &p.authority_profile
+    }
+}
+
+/// Reports the violation, if any, that makes `current` an invalid (expansive)
+/// continuation of `predecessor`. `Ok(())` means current ≤ predecessor under the
+/// reference attenuation order (non-expansion, §2.4 / §3.3 check 6): every
+/// current operation is covered by some predecessor operation.
+pub fn attenuates(current: &Invariants, predecessor: &Invariants) -> Result<(), Violation> {
+    attenuates_under(ModelLattice::reference(), current, predecessor)
+}
+
+/// [`attenuates`] with execution models ordered by `models`: the first
+/// violation the hop's [`diff_under`] reports.
+fn attenuates_under(
+    models: &ModelLattice,
+    current: &Invariants,
+    predecessor: &Invariants,
+) -> Result<(), Violation> {
+    let d = diff_under(models, current, predecessor);
+    match d.violations.into_iter().next() {
+        Some(v) => Err(v),
+        None => Ok(()),
+    }
+}
+
+/// Checks that an allow-list keeps or narrows the predecessor's: once set,
+/// it may lose members but never gain any or become empty (unconstrained).
+pub(crate) fn narrows(
+    field: &'static str,
+    current: &[String],
+    predecessor: &[String],
+) -> Result<(), Violation> {
+    if predecessor.is_empty() {
+        return Ok(());
+    }
+    if current.is_empty() || current.iter().any(|v| !predecessor.contains(v)) {
+        return Err(Violation::AllowedRelaxed {
+            field,
+            current: current.to_vec(),
+            predecessor: predecessor.to_vec(),
+        });
     }
     Ok(())
 }
 
-/// Reports the error, if any, that makes an executor's attested attributes fail
-/// the execution contract (§3.3 check 5). `Ok(())` means conforming.
-pub fn conforms(att: &ContractAttributes, contract: &ExecutionContract) -> PicResult<()> {
+/// Reports the violation, if any, that makes an executor's attested attributes
+/// fail the execution contract (§3.3 check 5). `Ok(())` means conforming.
+pub fn conforms(att: &ContractAttributes, contract: &ExecutionContract) -> Result<(), Violation> {
+    conforms_under(ModelLattice::reference(), att, contract)
+}
+
+/// [`conforms`] with execution models ordered by `models`.
+fn conforms_under(
+    models: &ModelLattice,
+    att: &ContractAttributes,
+    contract: &ExecutionContract,
+) -> Result<(), Violation> {
     if !contract.role.is_empty() && att.role != contract.role {
-        return Err(format!(
-            "conformance: executor role {:?} does not satisfy required role {:?}",
-            att.role, contract.role
-        ));
+        return Err(Violation::RoleNotSatisfied {
+            attested: att.role.clone(),
+            required: contract.role.clone(),
+        });
     }
     for c in &contract.compliance {
         if !att.compliance.contains(c) {
-            return Err(format!(
-                "conformance: executor lacks required compliance {c:?}"
-            ));
+            return Err(Violation::ComplianceMissing {
+                compliance: c.clone(),
+            });
         }
     }
-    if model_rank(&att.execution_model) > model_rank(&contract.execution_model) {
-        return Err(format!(
-            "conformance: executionModel {:?} not permitted by contract {:?}",
-            att.execution_model, contract.execution_model
-        ));
+    if !restricts(models, &att.execution_model, &contract.execution_model)? {
+        return Err(Violation::ExecutionModelNotPermitted {
+            attested: att.execution_model.clone(),
+            permitted: contract.execution_model.clone(),
+        });
     }
-    Ok(())
+    allows("region", &att.region, &contract.allowed_regions)?;
+    allows(
+        "environment",
+        &att.environment,
+        &contract.allowed_environments,
+    )
 }
 
-/// The PDP / reference-monitor decision (§4.3): is the concrete request permitted
-/// by the authority carried in these invariants?
-pub fn authorize(inv: &Invariants, req: &Request) -> PicResult<()> {
-    let want = format!("{}:{}", req.operation, req.target);
-    for granted in &inv.operations {
-        if match_op(granted, &want) {
-            return Ok(());
-        }
+/// Checks an attested attribute against a contract allow-list; an empty list
+/// allows any value, a set one requires the attribute to be attested.
+fn allows(field: &'static str, attested: &str, allowed: &[String]) -> Result<(), Violation> {
+    if allowed.is_empty() || allowed.iter().any(|a| a == attested) {
+        return Ok(());
     }
-    Err(format!(
-        "not authorized: {:?} not covered by {}",
-        want,
-        go_slice(&inv.operations)
-    ))
+    Err(Violation::NotAllowed {
+        field,
+        attested: attested.to_string(),
+        allowed: allowed.to_vec(),
+    })
 }
 
-/// Reports whether a granted operation pattern covers a concrete op. A pattern
-/// ending in `*` matches by prefix; otherwise it must match exactly.
-fn match_op(pattern: &str, concrete: &str) -> bool {
-    if let Some(prefix) = pattern.strip_suffix('*') {
-        concrete.starts_with(prefix)
-    } else {
-        pattern == concrete
+/// The PDP / reference-monitor decision (§4.3): is the concrete request permitted
+/// by the authority carried in these invariants? The request, read literally,
+/// must be covered by a granted operation pattern, and must declare every
+/// constrained quantity within its bound.
+pub fn authorize(inv: &Invariants, req: &Request) -> Result<(), Violation> {
+    let want = Operation::concrete(&req.operation, &req.target);
+    if !inv
+        .operations
+        .iter()
+        .any(|granted| Operation::parse(granted).covers(&want))
+    {
+        return Err(Violation::NotAuthorized {
+            requested: format!("{}:{}", req.operation, req.target),
+            granted: inv.operations.clone(),
+        });
+    }
+    for (name, constraint) in &inv.constraints {
+        let Some(declared) = req.quantities.get(name) else {
+            return Err(Violation::QuantityUndeclared {
+                name: name.clone(),
+                constraint: constraint.to_string(),
+            });
+        };
+        if !constraint.admits(declared) {
+            return Err(Violation::QuantityExceeded {
+                name: name.clone(),
+                declared: declared.to_string(),
+                constraint: constraint.to_string(),
+            });
+        }
     }
+    Ok(())
 }
 
-/// Orders execution models by restrictiveness: lower is more restrictive. An
-/// unset/unknown model is the least restrictive (unconstrained).
-fn model_rank(m: &str) -> i32 {
-    match m {
-        "deterministic" => 0,
-        "agentic" => 1,
-        _ => 2,
+/// Reports whether execution model `a` is at least as restrictive as `b` in
+/// `models` (unset is the least restrictive). A model `models` does not
+/// declare is a violation of its own, never unconstrained.
+pub(crate) fn restricts(models: &ModelLattice, a: &str, b: &str) -> Result<bool, Violation> {
+    for model in [a, b] {
+        if !models.contains(model) {
+            return Err(Violation::UnknownExecutionModel {
+                model: model.to_string(),
+            });
+        }
     }
+    Ok(models.restricts(a, b) == Ok(true))
 }
 
 /// Formats a slice the way Go's `%v` does: `[a b c]`, space-separated, unquoted.
@@ -5,13 +5,22 @@
 
 //! Command genfixtures deterministically (re)generates the v0.2 fixtures: a DID
 //! document and Ed25519 key per actor, plus signed attestations for the executor
-//! hops. Output goes to v0.2/fixtures. Keys are throwaway, for demos and tests.
+//! hops and the grants org-authority issues to the guardrail demo's origins.
+//! Executors run in `eu-1` production unless placed elsewhere to exercise
+//! data-residency contracts.
+//! Output goes to v0.2/fixtures. Keys are throwaway, for demos and tests.
 //!
-//!   cargo run --bin genfixtures [output-dir]
+//! The keys go to the encrypted `keystore.json` (under
+//! [`fixtureset::FIXTURE_PASSPHRASE`]); an existing keystore is kept, so
+//! regenerating leaves it unchanged. `--jwk` also writes each key as a
+//! plaintext `identities/<actor>/private.jwk`, the form other tracks read.
+//!
+//!   cargo run --bin genfixtures [--jwk] [output-dir]
 
-use pic::crypto::Identity;
-use pic::types::{Attestation, ContractAttributes};
-use pic::{sign_attestation, PicResult};
+use pic::crypto::{b64_encode, Identity};
+use pic::fixtureset;
+use pic::types::{Attestation, ContractAttributes, Grant, Invariants};
+use pic::{sign_attestation, sign_grant, Keystore, PicError, PicResult};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -27,10 +36,14 @@ struct Actor {
     exec_model: &'static str,
     compliance: &'static [&'static str],
     is_executor: bool,
+    region: &'static str,
+    environment: &'static str,
 }
 
 // The fixture cast: the origin principal (alice), the attestation issuer
-// (org-authority), the snapshot validator, and the executor hops.
+// (org-authority), the snapshot validator, the sandbox enforcement origin and
+// its guardrail, and the executor hops (two storage replicas outside eu-1
+// production among them).
 fn actors() -> Vec<Actor> {
     vec![
         Actor {
@@ -40,6 +53,8 @@ fn actors() -> Vec<Actor> {
             exec_model: "",
             compliance: &[],
             is_executor: false,
+            region: "",
+            environment: "",
         },
         Actor {
             name: "org-authority",
@@ -48,6 +63,8 @@ fn actors() -> Vec<Actor> {
             exec_model: "",
             compliance: &[],
             is_executor: false,
+            region: "",
+            environment: "",
         },
         Actor {
             name: "snapshot-issuer",
@@ -56,6 +73,28 @@ fn actors() -> Vec<Actor> {
             exec_model: "",
             compliance: &[],
             is_executor: false,
+            region: "",
+            environment: "",
+        },
+        Actor {
+            name: "enforcement-origin",
+            did: "did:web:enforcement.example",
+            role: "",
+            exec_model: "",
+            compliance: &[],
+            is_executor: false,
+            region: "",
+            environment: "",
+        },
+        Actor {
+            name: "guardrail",
+            did: "did:web:guardrail.example",
+            role: "guardrail",
+            exec_model: "deterministic",
+            compliance: &[],
+            is_executor: true,
+            region: "eu-1",
+            environment: "production",
         },
         Actor {
             name: "gateway",
@@ -64,6 +103,8 @@ fn actors() -> Vec<Actor> {
             exec_model: "deterministic",
             compliance: &[],
             is_executor: true,
+            region: "eu-1",
+            environment: "production",
         },
         Actor {
             name: "backup-service",
@@ -72,6 +113,8 @@ fn actors() -> Vec<Actor> {
             exec_model: "deterministic",
             compliance: &["GDPR"],
             is_executor: true,
+            region: "eu-1",
+            environment: "production",
         },
         Actor {
             name: "summary-service",
@@ -80,6 +123,8 @@ fn actors() -> Vec<Actor> {
             exec_model: "agentic",
             compliance: &[],
             is_executor: true,
+            region: "eu-1",
+            environment: "production",
         },
         Actor {
             name: "archive-service",
@@ -88,6 +133,8 @@ fn actors() -> Vec<Actor> {
             exec_model: "deterministic",
             compliance: &["GDPR"],
             is_executor: true,
+            region: "eu-1",
+            environment: "production",
         },
         Actor {
             name: "storage-service",
@@ -96,27 +143,91 @@ fn actors() -> Vec<Actor> {
             exec_model: "deterministic",
             compliance: &["GDPR"],
             is_executor: true,
+            region: "eu-1",
+            environment: "production",
+        },
+        Actor {
+            name: "storage-us",
+            did: "did:web:storage-us.example",
+            role: "storage-service",
+            exec_model: "deterministic",
+            compliance: &["GDPR"],
+            is_executor: true,
+            region: "us-1",
+            environment: "production",
+        },
+        Actor {
+            name: "storage-staging",
+            did: "did:web:storage-staging.example",
+            role: "storage-service",
+            exec_model: "deterministic",
+            compliance: &["GDPR"],
+            is_executor: true,
+            region: "eu-1",
+            environment: "staging",
+        },
+    ]
+}
+
+/// Describes one fixture grant, signed by org-authority.
+struct GrantSpec {
+    file: &'static str,
+    id: &'static str,
+    grantee: &'static str,
+    operations: &'static [&'static str],
+    scopes: &'static [&'static str],
+}
+
+// The grants behind the guardrail demo's carried lineages.
+fn grants() -> Vec<GrantSpec> {
+    vec![
+        GrantSpec {
+            file: "user-backup",
+            id: "urn:pic:grant:user-backup",
+            grantee: "did:web:alice.example",
+            operations: &["read-all", "backup"],
+            scopes: &["data-protection"],
+        },
+        GrantSpec {
+            file: "agent-s3-writer",
+            id: "urn:pic:grant:agent-s3-writer",
+            grantee: "did:web:summary.example",
+            operations: &["write:s3/backups/*"],
+            scopes: &["data-protection", "ai-compliance"],
+        },
+        GrantSpec {
+            file: "external-sharing",
+            id: "urn:pic:grant:external-sharing",
+            grantee: "did:web:summary.example",
+            operations: &["share-public"],
+            scopes: &["external-sharing"],
         },
     ]
 }
 
-// Fixed, wide validity window so attestations verify regardless of run date.
+// Fixed, wide validity window so attestations and grants verify regardless of
+// run date.
 const FX_ISSUED: &str = "2026-01-01T00:00:00Z";
 const FX_EXPIRES: &str = "2035-01-01T00:00:00Z";
 
 fn main() {
-    let out_dir = std::env::args()
-        .nth(1)
-        .map(PathBuf::from)
-        .unwrap_or_else(default_fixtures_dir);
-    if let Err(e) = run(&out_dir) {
+    let mut jwk = false;
+    let mut out_dir = None;
+    for a in std::env::args().skip(1) {
+        match a.as_str() {
+            "--jwk" => jwk = true,
+            _ => out_dir = Some(PathBuf::from(a)),
+        }
+    }
+    let out_dir = out_dir.unwrap_or_else(default_fixtures_dir);
+    if let Err(e) = run(&out_dir, jwk) {
         eprintln!("genfixtures: {e}");
         exit(1);
     }
     println!("fixtures written to {}", out_dir.display());
 }
 
-fn run(out_dir: &Path) -> PicResult<()> {
+fn run(out_dir: &Path, jwk: bool) -> PicResult<()> {
     // deterministic identities, built first so the issuer key is available.
     let mut ids: HashMap<&str, Identity> = HashMap::new();
     for a in actors() {
@@ -124,13 +235,24 @@ fn run(out_dir: &Path) -> PicResult<()> {
         ids.insert(a.name, id);
     }
     let org = ids.get("org-authority").expect("org-authority identity");
+    fs::create_dir_all(out_dir).map_err(|e| PicError::Fixture(e.to_string()))?;
+    let mut keystore = Keystore::open_or_create(
+        out_dir.join(fixtureset::KEYSTORE_FILE),
+        fixtureset::FIXTURE_PASSPHRASE,
+    )?;
 
     for a in actors() {
         let id = ids.get(a.name).expect("identity");
         let dir = out_dir.join("identities").join(a.name);
-        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&dir).map_err(|e| PicError::Fixture(e.to_string()))?;
         write_json(&dir.join("did.json"), &did_document(id))?;
-        write_json(&dir.join("private.jwk"), &private_jwk(id))?;
+        keystore.import(a.name, id)?;
+        if jwk {
+            write_json(
+                &dir.join("private.jwk"),
+                &private_jwk(id, &seed_for(a.name)),
+            )?;
+        }
         if a.is_executor {
             let att = sign_attestation(
                 Attestation {
@@ -139,20 +261,41 @@ fn run(out_dir: &Path) -> PicResult<()> {
                         role: a.role.to_string(),
                         compliance: a.compliance.iter().map(|s| s.to_string()).collect(),
                         execution_model: a.exec_model.to_string(),
-                        environment: "production".to_string(),
-                        region: "eu-1".to_string(),
+                        environment: a.environment.to_string(),
+                        region: a.region.to_string(),
                     },
                     issued_at: FX_ISSUED.to_string(),
                     expires_at: FX_EXPIRES.to_string(),
                     ..Default::default()
                 },
                 org,
-            );
+            )?;
             let adir = out_dir.join("attestations");
-            fs::create_dir_all(&adir).map_err(|e| e.to_string())?;
+            fs::create_dir_all(&adir).map_err(|e| PicError::Fixture(e.to_string()))?;
             write_json(&adir.join(format!("{}.json", a.name)), &att)?;
         }
     }
+
+    let gdir = out_dir.join("grants");
+    fs::create_dir_all(&gdir).map_err(|e| PicError::Fixture(e.to_string()))?;
+    for g in grants() {
+        let grant = sign_grant(
+            Grant {
+                id: g.id.to_string(),
+                grantee: g.grantee.to_string(),
+                invariants: Invariants {
+                    operations: g.operations.iter().map(|s| s.to_string()).collect(),
+                    ..Default::default()
+                },
+                issued_at: FX_ISSUED.to_string(),
+                expires_at: FX_EXPIRES.to_string(),
+                scopes: g.scopes.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            },
+            org,
+        )?;
+        write_json(&gdir.join(format!("{}.json", g.file)), &grant)?;
+    }
     Ok(())
 }
 
@@ -174,13 +317,14 @@ struct Jwk {
     d: String,
 }
 
-fn private_jwk(id: &Identity) -> Jwk {
+/// The private JWK of `id`, loaded from `seed`.
+fn private_jwk(id: &Identity, seed: &[u8]) -> Jwk {
     Jwk {
         kty: "OKP",
         crv: "Ed25519",
         kid: id.verification_method.clone(),
         x: id.encode_public(),
-        d: id.seed(),
+        d: b64_encode(seed),
     }
 }
 
@@ -233,9 +377,9 @@ fn did_document(id: &Identity) -> DidDoc {
 /// Writes `v` as 2-space pretty JSON plus a trailing newline, matching Go's
 /// `json.MarshalIndent(v, "", "  ")` + `\n`.
 fn write_json<T: Serialize>(path: &Path, v: &T) -> PicResult<()> {
-    let mut b = serde_json::to_string_pretty(v).map_err(|e| e.to_string())?;
+    let mut b = serde_json::to_string_pretty(v).map_err(|e| PicError::Fixture(e.to_string()))?;
     b.push('\n');
-    fs::write(path, b).map_err(|e| e.to_string())
+    fs::write(path, b).map_err(|e| PicError::Fixture(e.to_string()))
 }
 
 /// Resolves v0.2/fixtures relative to the crate manifest.
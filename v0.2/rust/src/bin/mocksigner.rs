@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Command mocksigner is a local stand-in for a remote signing service: it
+//! holds the fixture keys and answers the line protocol of `pic::signer` on a
+//! Unix socket, so executors can sign through a [`pic::RemoteSigner`] without
+//! the key in their own process. Keys are addressed by DID. It prints `ready`
+//! once listening and serves until killed.
+//!
+//! `--keystore` serves the identities of another encrypted keystore, opened
+//! with the passphrase in `PIC_KEYSTORE_PASSPHRASE`.
+//!
+//!   cargo run --bin mocksigner -- <socket-path> [--keystore <path>]
+
+#[cfg(unix)]
+fn main() {
+    if let Err(e) = serve() {
+        eprintln!("mocksigner: {e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("mocksigner: Unix sockets are not available on this platform");
+    std::process::exit(1);
+}
+
+#[cfg(unix)]
+fn serve() -> Result<(), String> {
+    use pic::crypto::{b64_decode, b64_encode};
+    use pic::signer::{SignerRequest, SignerResponse};
+    use pic::{Identity, Keystore};
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+
+    const USAGE: &str = "usage: mocksigner <socket-path> [--keystore <path>]";
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, keystore) = match args.as_slice() {
+        [path] => (path, None),
+        [path, flag, keystore] if flag == "--keystore" => (path, Some(keystore)),
+        _ => return Err(USAGE.to_string()),
+    };
+    let owned;
+    let identities: Vec<&Identity> = match keystore {
+        None => {
+            let set = pic::fixtureset::load().map_err(|e| e.to_string())?;
+            set.identities.values().collect()
+        }
+        Some(keystore) => {
+            let passphrase = std::env::var("PIC_KEYSTORE_PASSPHRASE")
+                .map_err(|_| "PIC_KEYSTORE_PASSPHRASE is not set")?;
+            owned = Keystore::open(keystore, &passphrase)
+                .and_then(|k| k.load())
+                .map_err(|e| e.to_string())?;
+            owned.identities.values().collect()
+        }
+    };
+    let keys: HashMap<&str, &Identity> = identities
+        .into_iter()
+        .map(|id| (id.id.as_str(), id))
+        .collect();
+
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path).map_err(|e| format!("bind {path}: {e}"))?;
+    println!("ready");
+    std::io::stdout().flush().map_err(|e| e.to_string())?;
+
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { continue };
+        let mut line = String::new();
+        if BufReader::new(&stream).read_line(&mut line).is_err() {
+            continue;
+        }
+        let resp = match serde_json::from_str::<SignerRequest>(&line) {
+            Err(e) => SignerResponse {
+                error: format!("bad request: {e}"),
+                ..Default::default()
+            },
+            Ok(req) => match (req.op.as_str(), keys.get(req.key_id.as_str())) {
+                (_, None) => SignerResponse {
+                    error: format!("unknown key {:?}", req.key_id),
+                    ..Default::default()
+                },
+                ("describe", Some(id)) => SignerResponse {
+                    id: id.id.clone(),
+                    verification_method: id.verification_method.clone(),
+                    type_: id.proof_type().to_string(),
+                    public_key: id.encode_public(),
+                    ..Default::default()
+                },
+                ("sign", Some(id)) => match b64_decode(&req.message) {
+                    Ok(msg) => SignerResponse {
+                        signature: b64_encode(&id.sign_raw(&msg)),
+                        ..Default::default()
+                    },
+                    Err(e) => SignerResponse {
+                        error: format!("bad message: {e}"),
+                        ..Default::default()
+                    },
+                },
+                (op, Some(_)) => SignerResponse {
+                    error: format!("unknown op {op:?}"),
+                    ..Default::default()
+                },
+            },
+        };
+        let mut out = serde_json::to_vec(&resp).expect("response to_vec");
+        out.push(b'\n');
+        let _ = stream.write_all(&out);
+    }
+    Ok(())
+}
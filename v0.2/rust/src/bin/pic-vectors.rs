@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Command pic-vectors writes the cross-implementation test-vector corpus
+//! (see `pic::vectors`) or replays one and reports every divergence. The
+//! default corpus is v0.2/fixtures/vectors/corpus.json.
+//!
+//!   cargo run --bin pic-vectors -- generate [corpus.json]
+//!   cargo run --bin pic-vectors -- check [corpus.json]
+
+use pic::vectors::{self, Corpus};
+use pic::{fixtureset, PicError, PicResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let path = args
+        .get(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(default_corpus);
+    let res = match args.first().map(String::as_str) {
+        Some("generate") => generate(&path),
+        Some("check") => check(&path),
+        _ => {
+            eprintln!("usage: pic-vectors generate|check [corpus.json]");
+            exit(2);
+        }
+    };
+    match res {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(e) => {
+            eprintln!("pic-vectors: {e}");
+            exit(1);
+        }
+    }
+}
+
+fn generate(path: &Path) -> PicResult<bool> {
+    let corpus = vectors::generate(fixtureset::load()?)?;
+    let mut raw = serde_json::to_string_pretty(&corpus).expect("corpus to_string");
+    raw.push('\n');
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| PicError::Fixture(e.to_string()))?;
+    }
+    fs::write(path, raw).map_err(|e| PicError::Fixture(e.to_string()))?;
+    println!(
+        "{} vectors written to {}",
+        corpus.vectors.len(),
+        path.display()
+    );
+    Ok(true)
+}
+
+fn check(path: &Path) -> PicResult<bool> {
+    let raw = fs::read(path).map_err(|e| PicError::Fixture(format!("{}: {e}", path.display())))?;
+    let corpus: Corpus = serde_json::from_slice(&raw)
+        .map_err(|e| PicError::Fixture(format!("{}: {e}", path.display())))?;
+    let divergences = vectors::replay(&corpus, fixtureset::load()?);
+    for d in &divergences {
+        println!("DIVERGES {d}");
+    }
+    println!(
+        "{} vectors, {} divergences",
+        corpus.vectors.len(),
+        divergences.len()
+    );
+    Ok(divergences.is_empty())
+}
+
+/// Resolves v0.2/fixtures/vectors/corpus.json relative to the crate manifest.
+fn default_corpus() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("fixtures")
+        .join("vectors")
+        .join("corpus.json")
+}
@@ -10,18 +10,27 @@
 
 use crate::{fmt_dur, header, paint, print_json, C_BOLD, C_CYAN, C_DIM, C_GREEN, C_RED, C_YELLOW};
 use chrono::{DateTime, Utc};
+use pic::codec::encode_cose;
 use pic::prover::mint_pca0;
 use pic::scenario::World;
 use pic::types::{Invariants, Request};
-use pic::{issue_snapshot, wrap_envelope, Prover, Verifier};
+use pic::{issue_snapshot, wrap_envelope, Prover, ReferenceProfile, Verifier};
 use serde::Serialize;
 use std::time::{Duration, Instant};
 
-/// Serialized (compact JSON) byte sizes of representative artifacts.
+/// Serialized byte sizes of representative artifacts, in compact JSON and as
+/// a COSE_Sign1 over deterministic CBOR.
 struct PcaSizes {
     pca0: usize,
     successor: usize,
     envelope: usize,
+    pca0_cose: usize,
+    successor_cose: usize,
+    envelope_cose: usize,
+    /// The 64-hop chain with every attestation embedded.
+    chain: usize,
+    /// The same chain in the reference form, plus its side table.
+    chain_by_reference: usize,
 }
 
 /// Byte length of the compact JSON serialization of `v`.
@@ -59,19 +68,33 @@ pub(crate) fn run_bench(now: DateTime<Utc>, o: &crate::Opts) -> Result<(), Strin
     };
 
     // Shared setup, done once (not timed).
-    let pca0 = mint_pca0(w.set.identity("alice"), inv.clone(), "", now);
+    let pca0 = mint_pca0(w.set.identity("alice"), inv.clone(), None, now)?;
     let prover = Prover::new(w.set.identity("gateway"), w.set.attestation("gateway"));
     let pca1 = prover.continue_(&pca0, inv.clone(), req.clone(), now)?;
     let chain = w.build_chain(64, now)?;
+    let (ref_chain, atts) = w.build_chain_by_reference(64, now)?;
+    let side: Vec<_> = atts.iter().collect();
     let through = chain.len() - 1 - 8;
-    let snap = issue_snapshot(w.set.identity("snapshot-issuer"), reg, &chain, through, now)?;
+    let snap = issue_snapshot(
+        w.set.identity("snapshot-issuer"),
+        reg,
+        &chain,
+        through,
+        now,
+        &ReferenceProfile::DEFAULT,
+    )?;
     let tail = &chain[through..];
 
-    let env = wrap_envelope(w.set.identity("gateway"), &pca0, &pca1);
+    let env = wrap_envelope(w.set.identity("gateway"), &pca0, &pca1)?;
     let sizes = PcaSizes {
         pca0: json_len(&pca0),
         successor: json_len(&pca1),
         envelope: json_len(&env),
+        pca0_cose: encode_cose(&pca0, w.set.identity("alice"))?.len(),
+        successor_cose: encode_cose(&pca1, w.set.identity("gateway"))?.len(),
+        envelope_cose: encode_cose(&env, w.set.identity("gateway"))?.len(),
+        chain: json_len(&chain),
+        chain_by_reference: json_len(&ref_chain) + json_len(&side),
     };
 
     type Case<'a> = (&'a str, Box<dyn Fn() + 'a>);
@@ -79,7 +102,7 @@ pub(crate) fn run_bench(now: DateTime<Utc>, o: &crate::Opts) -> Result<(), Strin
         (
             "sign PCA0 (Ed25519)",
             Box::new(|| {
-                let _ = mint_pca0(w.set.identity("alice"), inv.clone(), "", now);
+                let _ = mint_pca0(w.set.identity("alice"), inv.clone(), None, now);
             }),
         ),
         (
@@ -106,6 +129,14 @@ pub(crate) fn run_bench(now: DateTime<Utc>, o: &crate::Opts) -> Result<(), Strin
                 let _ = Verifier::new(reg, None).verify_full_chain(&chain, now);
             }),
         ),
+        (
+            "verify full chain (64 hops, by reference)",
+            Box::new(|| {
+                let mut v = Verifier::new(reg, None);
+                v.attestations = Some(&atts);
+                let _ = v.verify_full_chain(&ref_chain, now);
+            }),
+        ),
         (
             "verify from snapshot (tail 8)",
             Box::new(|| {
@@ -172,7 +203,8 @@ fn bench_guardrail(w: &World, now: DateTime<Utc>) -> Result<(Vec<BenchRow>, usiz
         &pdp,
         w.set.policy.clone(),
         &w.set.scopes,
-    );
+    )
+    .with_grants(&w.set.grants);
     let origin = w.set.identity("enforcement-origin");
     let origins = w.accepted_origins();
     let agent = w.set.identity("summary-service");
@@ -184,15 +216,17 @@ fn bench_guardrail(w: &World, now: DateTime<Utc>) -> Result<(Vec<BenchRow>, usiz
         Invariants {
             operations: vec!["read-all".into(), "backup".into()],
             execution_contract: contract.clone(),
+            ..Default::default()
         },
-        GRANT_USER_BACKUP,
+        Some(w.set.grant(GRANT_USER_BACKUP)),
         now,
-    );
+    )?;
     let pca1_a = Prover::new(agent, agent_att.clone()).continue_(
         &pca0_a,
         Invariants {
             operations: vec!["backup".into()],
             execution_contract: contract.clone(),
+            ..Default::default()
         },
         Request {
             operation: "backup".into(),
@@ -205,8 +239,14 @@ fn bench_guardrail(w: &World, now: DateTime<Utc>) -> Result<(Vec<BenchRow>, usiz
     let inv_b = Invariants {
         operations: vec!["write:s3/backups/*".into()],
         execution_contract: contract.clone(),
+        ..Default::default()
     };
-    let pca0_b = mint_pca0(agent, inv_b.clone(), GRANT_AGENT_S3_WRITER, now);
+    let pca0_b = mint_pca0(
+        agent,
+        inv_b.clone(),
+        Some(w.set.grant(GRANT_AGENT_S3_WRITER)),
+        now,
+    )?;
     let pca1_b = Prover::new(agent, agent_att.clone()).continue_(
         &pca0_b,
         inv_b,
@@ -236,7 +276,7 @@ fn bench_guardrail(w: &World, now: DateTime<Utc>) -> Result<(Vec<BenchRow>, usiz
     };
 
     // One reference crossing for the accept benchmark and the size figure.
-    let mut se_ref = SandboxedExecution::originate(origin, now);
+    let mut se_ref = SandboxedExecution::originate(origin, now)?;
     let (outer, trace) = guard.enforce(&mut se_ref, &mle, now);
     let outer = outer.ok_or("bench: sandboxed crossing unexpectedly denied")?;
     let ref_chain = se_ref.chain.clone();
@@ -267,22 +307,24 @@ fn bench_guardrail(w: &World, now: DateTime<Utc>) -> Result<(Vec<BenchRow>, usiz
         (
             "guardrail: enforce (prove PCA1-G)",
             Box::new(|| {
-                let mut se = SandboxedExecution::originate(origin, now);
+                let mut se = SandboxedExecution::originate(origin, now).expect("originate");
                 let _ = guard.enforce(&mut se, &mle, now);
             }),
         ),
         (
             "receiver: accept outer PCA",
             Box::new(|| {
-                let _ = accept_guarded_crossing(reg, None, &origins, &ref_chain, now);
+                let mut verifier = Verifier::new(reg, None);
+                let _ = accept_guarded_crossing(&mut verifier, &origins, &ref_chain, now);
             }),
         ),
         (
             "sandboxed crossing end to end",
             Box::new(|| {
-                let mut se = SandboxedExecution::originate(origin, now);
+                let mut se = SandboxedExecution::originate(origin, now).expect("originate");
                 let _ = guard.enforce(&mut se, &mle, now);
-                let _ = accept_guarded_crossing(reg, None, &origins, &se.chain, now);
+                let mut verifier = Verifier::new(reg, None);
+                let _ = accept_guarded_crossing(&mut verifier, &origins, &se.chain, now);
             }),
         ),
     ];
@@ -513,11 +555,38 @@ fn render_bench(rows: &[BenchRow], sizes: &PcaSizes) {
 
     println!();
     println!(
-        "  {}  PCA0 {} · successor {} · envelope {}",
-        paint(C_BOLD, "serialized size"),
-        paint(C_YELLOW, &size_str(sizes.pca0)),
-        paint(C_YELLOW, &size_str(sizes.successor)),
-        paint(C_YELLOW, &size_str(sizes.envelope)),
+        "  {}  {} {} {}",
+        pad(&paint(C_BOLD, "serialized size"), 16),
+        pad_left(&paint(C_BOLD, "PCA0"), 20),
+        pad_left(&paint(C_BOLD, "successor"), 20),
+        pad_left(&paint(C_BOLD, "envelope"), 20),
+    );
+    let size_row = |name: &str, pca0: usize, successor: usize, envelope: usize| {
+        println!(
+            "    {}  {} {} {}",
+            pad(name, 14),
+            pad_left(&paint(C_YELLOW, &size_str(pca0)), 20),
+            pad_left(&paint(C_YELLOW, &size_str(successor)), 20),
+            pad_left(&paint(C_YELLOW, &size_str(envelope)), 20),
+        );
+    };
+    size_row("JSON", sizes.pca0, sizes.successor, sizes.envelope);
+    size_row(
+        "CBOR/COSE",
+        sizes.pca0_cose,
+        sizes.successor_cose,
+        sizes.envelope_cose,
+    );
+    let saved = sizes.chain.saturating_sub(sizes.chain_by_reference);
+    println!(
+        "    {}  {} embedded, {} by reference + side table ({})",
+        pad("chain (64)", 14),
+        paint(C_YELLOW, &size_str(sizes.chain)),
+        paint(C_YELLOW, &size_str(sizes.chain_by_reference)),
+        paint(
+            C_GREEN,
+            &format!("-{:.0}%", 100.0 * saved as f64 / sizes.chain.max(1) as f64)
+        ),
     );
 
     println!(
@@ -98,6 +98,8 @@ pub(crate) fn run_exec(now: DateTime<Utc>, o: &Opts) -> Result<(), String> {
             scopes: &'a [String],
             authority: &'a [String],
             chain: &'a [Pca],
+            /// Each successor's invariants against its predecessor's.
+            diffs: Vec<pic::InvariantDiff>,
         }
         let cl: Vec<CarriedJson> = carried
             .iter()
@@ -107,6 +109,11 @@ pub(crate) fn run_exec(now: DateTime<Utc>, o: &Opts) -> Result<(), String> {
                 scopes: &l.scopes,
                 authority: &l.authority,
                 chain: &l.chain,
+                diffs: l
+                    .chain
+                    .windows(2)
+                    .map(|w| pic::diff(&w[1].invariants, &w[0].invariants))
+                    .collect(),
             })
             .collect();
         let mut out = serde_json::json!({
@@ -353,6 +360,8 @@ fn render_lineage_chain(l: &Lin, with_pca: bool) {
                     )
                 )
             );
+            let d = pic::diff(&p.invariants, &l.chain[idx - 1].invariants);
+            println!("      {}", paint(C_DIM, &d.to_string()));
         }
         if with_pca {
             println!("      {}", paint(C_DIM, "signed PCA ▾"));
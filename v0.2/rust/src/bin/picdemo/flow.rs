@@ -64,11 +64,8 @@ fn render_hop(h: &FlowHop) {
     );
 
     let mut line = format!("    authority: {}", paint(C_GREEN, &go_vec(&h.authority)));
-    if !h.dropped.is_empty() {
-        line += &format!(
-            "   {}",
-            paint(C_RED, &format!("dropped: {}", h.dropped.join(", ")))
-        );
+    if let Some(d) = h.diff.as_ref().filter(|d| !d.is_unchanged()) {
+        line += &format!("   {}", paint(C_RED, &d.to_string()));
     }
     println!("{line}");
 
@@ -123,6 +120,13 @@ fn render_rogue(r: &RogueAttempt) {
         )
     );
     println!("    {}", paint(C_DIM, &format!("reason: {}", r.reason)));
+    for v in &r.diff.violations {
+        println!(
+            "      {} {}",
+            paint(C_REJECT, "✗"),
+            paint(C_DIM, &v.to_string())
+        );
+    }
 }
 
 fn print_indented_json<T: Serialize>(v: &T, prefix: &str) {
@@ -12,6 +12,7 @@
 //!                 simulated PDP) and route each scenario's tip crossing through
 //!                 them; without it, everything behaves exactly as before.
 //!   --only-json   emit a single JSON document (for jq) instead of the report.
+//!   --at=<time>   run at a fixed RFC 3339 instant instead of the system clock.
 //!
 //! Dump selectors (with `dump`): pca0|hop0, pca1|hop1, envelope, and with
 //! --guardrail also policy, scopes, mle, pdp, trace, guard, denytrace.
@@ -28,7 +29,10 @@ use chrono::{DateTime, Utc};
 use guarded::{render_receiver, render_tip_guard, run_guardrail, wrap};
 use pic::authority::go_slice;
 use pic::scenario::World;
-use pic::{issue_snapshot, wrap_envelope, Verifier};
+use pic::{
+    issue_snapshot, wrap_envelope, Clock, FixedClock, PicResult, ReferenceProfile, SystemClock,
+    Verifier,
+};
 use serde::Serialize;
 use serde_json::Value;
 use std::io::IsTerminal;
@@ -46,10 +50,18 @@ pub(crate) struct Opts {
 fn main() {
     let mut o = Opts::default();
     let mut positional: Vec<String> = Vec::new();
+    let mut clock: Box<dyn Clock> = Box::new(SystemClock);
     for a in std::env::args().skip(1) {
         match a.as_str() {
             "--guardrail" | "-g" => o.guardrail = true,
             "--only-json" | "--json" | "-j" => o.only_json = true,
+            _ if a.starts_with("--at=") => match pic::parse_rfc3339(&a["--at=".len()..]) {
+                Ok(at) => clock = Box::new(FixedClock::new(at)),
+                Err(e) => {
+                    eprintln!("picdemo: --at: {e}");
+                    exit(2);
+                }
+            },
             _ => positional.push(a),
         }
     }
@@ -58,7 +70,7 @@ fn main() {
         .cloned()
         .unwrap_or_else(|| "all".to_string());
     o.selectors = positional.get(1..).unwrap_or_default().to_vec();
-    let now = Utc::now();
+    let now = clock.now();
 
     let order = ["why-pic", "confused-deputy", "snapshot", "revocation"];
     let known = ["why-pic", "confused-deputy", "snapshot", "revocation", "guardrail"];
@@ -170,8 +182,9 @@ pub(crate) fn verdict(ok: bool, yes: &str, no: &str) -> String {
     }
 }
 
-fn reason(e: &Option<String>) -> String {
-    e.clone().unwrap_or_else(|| "<nil>".to_string())
+fn reason<E: std::fmt::Display>(e: &Option<E>) -> String {
+    e.as_ref()
+        .map_or_else(|| "<nil>".to_string(), |e| e.to_string())
 }
 
 // --- dump -------------------------------------------------------------------
@@ -214,7 +227,7 @@ fn run_dump(now: DateTime<Utc>, o: &Opts) -> Result<(), String> {
     let pca0 = chain[0].clone();
     let pca1 = chain[1].clone();
     let d0 = pca0.digest();
-    let env = wrap_envelope(w.set.identity("gateway"), &pca0, &pca1);
+    let env = wrap_envelope(w.set.identity("gateway"), &pca0, &pca1)?;
     let (inv, verify_err) =
         match Verifier::new(&w.set.registry, None).verify_full_chain(&chain[..2], now) {
             Ok(inv) => (inv.operations, None),
@@ -391,7 +404,7 @@ fn run_dump(now: DateTime<Utc>, o: &Opts) -> Result<(), String> {
                 "explanation": "Editing one signed field (invariants.operations) and re-verifying: the Ed25519 signature no longer verifies, so the edit is rejected.",
                 "editedSignedField": "invariants.operations",
                 "rejected": tamper_err.is_some(),
-                "reason": tamper_err.clone().unwrap_or_default(),
+                "reason": tamper_err.as_ref().map(|e| e.to_string()).unwrap_or_default(),
             },
         });
         if let Some(g) = &guarded_res {
@@ -538,6 +551,7 @@ fn run_snapshot(now: DateTime<Utc>, o: &Opts) -> Result<(), String> {
         &chain,
         through_index,
         now,
+        &ReferenceProfile::DEFAULT,
     )?;
     let tail_chain = &chain[through_index..];
 
@@ -627,7 +641,7 @@ fn run_revocation(now: DateTime<Utc>, o: &Opts) -> Result<(), String> {
 // --- timing / formatting helpers --------------------------------------------
 
 /// Runs `fn` `iters` times and returns the average duration.
-fn time_it<F: FnMut() -> Result<(), String>>(iters: u32, mut f: F) -> Duration {
+fn time_it<F: FnMut() -> PicResult<()>>(iters: u32, mut f: F) -> Duration {
     let start = Instant::now();
     for _ in 0..iters {
         f().expect("timed function failed");
@@ -0,0 +1,457 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! The compact wire encoding: deterministic CBOR (RFC 8949 §4.2.1, core
+//! deterministic) wrapped in a COSE_Sign1 (RFC 9052) sealed in the sender's
+//! signature suite.
+//!
+//! The CBOR payload carries the same data model as the JSON form, proof
+//! included, only denser: field names become small integer labels and
+//! digests, signatures, and nonces travel as raw bytes. The mapping is lossless,
+//! so a decoded document is identical to its JSON twin: digests,
+//! `previousPcaHash` bindings and document signatures do not depend on how a
+//! PCA travelled. The COSE seal authenticates the transport; its `kid` is the
+//! sealer's verification method and is resolved through the [`Registry`] at
+//! the decoding instant, so a seal checks against the key version valid then.
+
+use crate::crypto::{b64_decode, b64_encode, hex_lower, Registry};
+use crate::error::CodecError;
+use crate::jcs;
+use crate::signer::Signer;
+use crate::suite::SignatureSuite;
+use crate::types::{Attestation, Envelope, Pca, Revocation, Snapshot};
+use crate::untrusted::{self, Limits};
+use crate::{PicError, PicResult, DIGEST_PREFIX};
+use chrono::{DateTime, Utc};
+use ciborium::value::Value as Cbor;
+use coset::{
+    CborSerializable, CoseSign1, CoseSign1Builder, HeaderBuilder, RegisteredLabel,
+    RegisteredLabelWithPrivate, TaggedCborSerializable,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value as Json;
+
+/// A signed PIC document that travels in either wire encoding.
+pub trait Document: Serialize + DeserializeOwned {
+    /// The COSE content type (header label 3) naming the payload.
+    const CONTENT_TYPE: &'static str;
+}
+
+impl Document for Pca {
+    const CONTENT_TYPE: &'static str = "application/pic-pca+cbor";
+}
+
+impl Document for Envelope {
+    const CONTENT_TYPE: &'static str = "application/pic-envelope+cbor";
+}
+
+impl Document for Snapshot {
+    const CONTENT_TYPE: &'static str = "application/pic-snapshot+cbor";
+}
+
+impl Document for Attestation {
+    const CONTENT_TYPE: &'static str = "application/pic-attestation+cbor";
+}
+
+impl Document for Revocation {
+    const CONTENT_TYPE: &'static str = "application/pic-revocation+cbor";
+}
+
+/// The two wire encodings a Verifier accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    Cose,
+}
+
+impl Encoding {
+    /// Sniffs the encoding from the leading byte: a JSON object, or a
+    /// COSE_Sign1 either tagged (18) or bare (a 4-element array).
+    pub fn detect(bytes: &[u8]) -> Option<Encoding> {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Some(Encoding::Json),
+            Some(0xd2) | Some(0x84) => Some(Encoding::Cose),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the core deterministic CBOR encoding of `v`: definite lengths,
+/// shortest-form integers, and map keys sorted by their encoded bytes. Known
+/// field names are replaced by integer labels and binary values (digests,
+/// signatures, nonces) travel as raw bytes; the mapping is lossless.
+pub fn to_cbor<T: Serialize>(v: &T) -> Vec<u8> {
+    let value = serde_json::to_value(v).expect("cbor to_value");
+    let mut out = Vec::new();
+    ciborium::into_writer(&json_to_cbor(&value), &mut out).expect("cbor into_writer");
+    out
+}
+
+/// Decodes a deterministic CBOR payload. Items outside the profile's mapping
+/// (unknown labels or tags, non-text keys), any encoding other than the one
+/// [`to_cbor`] would produce for the same document, and numbers the canonical
+/// JSON form would round are rejected.
+pub fn from_cbor<T: DeserializeOwned>(b: &[u8]) -> Result<T, CodecError> {
+    let json = cbor_payload(b)?;
+    jcs::exact_numbers(&json)?;
+    serde_json::from_value(json).map_err(|e| CodecError::Cbor {
+        reason: e.to_string(),
+    })
+}
+
+/// Transcodes a deterministic CBOR payload back to the JSON data model.
+fn cbor_payload(b: &[u8]) -> Result<Json, CodecError> {
+    let value: Cbor = ciborium::from_reader(b).map_err(|e| CodecError::Cbor {
+        reason: e.to_string(),
+    })?;
+    let json = cbor_to_json(value)?;
+    let mut again = Vec::new();
+    ciborium::into_writer(&json_to_cbor(&json), &mut again).expect("cbor into_writer");
+    if again != b {
+        return Err(CodecError::NonDeterministic);
+    }
+    Ok(json)
+}
+
+/// Seals `doc` as a tagged COSE_Sign1: protected header `{alg, kid, content
+/// type}`, deterministic CBOR payload, signature by `sealer` in its suite.
+pub fn encode_cose<T: Document>(doc: &T, sealer: &dyn Signer) -> PicResult<Vec<u8>> {
+    let mut cose = unsealed(doc, sealer);
+    cose.signature = sealer
+        .try_sign(&cose.tbs_data(b""))
+        .map_err(PicError::Signer)?;
+    Ok(tagged(cose))
+}
+
+/// [`encode_cose`] through [`Signer::sign_async`].
+pub async fn encode_cose_async<T: Document>(doc: &T, sealer: &dyn Signer) -> PicResult<Vec<u8>> {
+    let mut cose = unsealed(doc, sealer);
+    cose.signature = sealer
+        .sign_async(&cose.tbs_data(b""))
+        .await
+        .map_err(PicError::Signer)?;
+    Ok(tagged(cose))
+}
+
+/// The COSE_Sign1 of `doc` before `sealer` signs it.
+fn unsealed<T: Document>(doc: &T, sealer: &dyn Signer) -> CoseSign1 {
+    let protected = HeaderBuilder::new()
+        .algorithm(sealer.suite().cose_algorithm())
+        .key_id(sealer.verification_method().as_bytes().to_vec())
+        .content_type(T::CONTENT_TYPE.to_string())
+        .build();
+    CoseSign1Builder::new()
+        .protected(protected)
+        .payload(to_cbor(doc))
+        .build()
+}
+
+fn tagged(cose: CoseSign1) -> Vec<u8> {
+    cose.to_tagged_vec().expect("cose to_tagged_vec")
+}
+
+/// Opens a COSE_Sign1 produced by [`encode_cose`]: checks the content type and
+/// that the algorithm is the suite of the `kid` resolved through `reg` at
+/// `now`, verifies the seal under that key, and
+/// decodes the payload under the default [`Limits`].
+pub fn decode_cose<T: Document>(reg: &Registry, bytes: &[u8], now: DateTime<Utc>) -> PicResult<T> {
+    decode_cose_with(reg, bytes, now, &Limits::default())
+}
+
+fn decode_cose_with<T: Document>(
+    reg: &Registry,
+    bytes: &[u8],
+    now: DateTime<Utc>,
+    limits: &Limits,
+) -> PicResult<T> {
+    let codec = |e| PicError::Codec(e);
+    untrusted::check_size(bytes.len(), limits).map_err(codec)?;
+    let cose = CoseSign1::from_tagged_slice(bytes)
+        .or_else(|_| CoseSign1::from_slice(bytes))
+        .map_err(|e| {
+            codec(CodecError::Cose {
+                reason: e.to_string(),
+            })
+        })?;
+    let header = &cose.protected.header;
+    let alg = match &header.alg {
+        Some(RegisteredLabelWithPrivate::Assigned(alg))
+            if SignatureSuite::ALL
+                .iter()
+                .any(|s| s.cose_algorithm() == *alg) =>
+        {
+            *alg
+        }
+        other => {
+            return Err(codec(CodecError::UnsupportedAlgorithm {
+                alg: format!("{other:?}"),
+            }))
+        }
+    };
+    match &header.content_type {
+        Some(RegisteredLabel::Text(ct)) if ct == T::CONTENT_TYPE => {}
+        other => {
+            return Err(codec(CodecError::ContentType {
+                expected: T::CONTENT_TYPE.to_string(),
+                found: match other {
+                    Some(RegisteredLabel::Text(ct)) => ct.clone(),
+                    Some(label) => format!("{label:?}"),
+                    None => String::new(),
+                },
+            }))
+        }
+    }
+    let kid = std::str::from_utf8(&header.key_id)
+        .ok()
+        .filter(|k| !k.is_empty())
+        .ok_or(codec(CodecError::MissingKid))?;
+    let payload = cose
+        .payload
+        .as_deref()
+        .ok_or(codec(CodecError::MissingPayload))?;
+    let keys = reg.keys_at(kid, now);
+    if let Some(key) = keys.first().map(|e| &e.key) {
+        if keys.iter().all(|e| e.key.suite().cose_algorithm() != alg) {
+            return Err(codec(CodecError::UnsupportedAlgorithm {
+                alg: format!("{alg:?} for a {} key", key.suite().jwk_curve()),
+            }));
+        }
+    }
+    cose.verify_signature(b"", |sig, tbs| reg.verify_raw_at(kid, tbs, sig, now))
+        .map_err(|e| codec(CodecError::Seal(e)))?;
+    let payload = cbor_payload(payload).map_err(codec)?;
+    untrusted::admit(payload, limits, |reason| CodecError::Cbor { reason }).map_err(codec)
+}
+
+/// Decodes a document in whichever encoding it arrived in, under the default
+/// [`Limits`]; a COSE seal is checked at `now`.
+pub fn decode<T: Document>(reg: &Registry, bytes: &[u8], now: DateTime<Utc>) -> PicResult<T> {
+    decode_with(reg, bytes, now, &Limits::default())
+}
+
+/// Decodes a document in whichever encoding it arrived in, enforcing `limits`
+/// (see [`crate::untrusted`]); a COSE seal is checked at `now`.
+pub fn decode_with<T: Document>(
+    reg: &Registry,
+    bytes: &[u8],
+    now: DateTime<Utc>,
+    limits: &Limits,
+) -> PicResult<T> {
+    match Encoding::detect(bytes) {
+        Some(Encoding::Json) => untrusted::from_untrusted_bytes(bytes, limits),
+        Some(Encoding::Cose) => decode_cose_with(reg, bytes, now, limits),
+        None => Err(PicError::Codec(CodecError::UnknownEncoding)),
+    }
+}
+
+/// The integer map labels standing in for the profile's field names, indexed
+/// by label. Append-only: a label, once assigned, is part of the wire format.
+/// Names not listed travel as text keys.
+const LABELS: &[&str] = &[
+    "type",
+    "proof",
+    "signature",
+    "verificationMethod",
+    "issuer",
+    "issuedAt",
+    "expiresAt",
+    "invariants",
+    "operations",
+    "executionContract",
+    "compliance",
+    "executionModel",
+    "role",
+    "continuation",
+    "challenge",
+    "mode",
+    "profile",
+    "lineageId",
+    "lineageCounter",
+    "branchId",
+    "grantId",
+    "originIssuer",
+    "originNonce",
+    "proofOfRelationship",
+    "previousPcaHash",
+    "continuationResponse",
+    "predecessorChallenge",
+    "executorNonce",
+    "executor",
+    "request",
+    "operation",
+    "target",
+    "securityDomain",
+    "executorAttestation",
+    "subject",
+    "attributes",
+    "environment",
+    "region",
+    "maxUses",
+    "requestDigest",
+    "payloadDigest",
+    "multiLineage",
+    "multiLineageDigest",
+    "policyCommitment",
+    "inputsCommitment",
+    "semanticProfile",
+    "enforcementResult",
+    "envelope",
+    "forwardedBy",
+    "predecessor",
+    "predecessorDigest",
+    "current",
+    "currentDigest",
+    "throughCounter",
+    "throughPcaHash",
+    "strategy",
+    "fromCounter",
+    "executorAttestationDigest",
+    "attestations",
+    "branchNonce",
+    "temporalProfile",
+    "maxHopWindowSeconds",
+    "maxChallengeLifetimeSeconds",
+    "lineageLifetimeSeconds",
+    "audience",
+    "executors",
+    "roles",
+    "constraints",
+    "quantities",
+    "max",
+    "oneOf",
+    "timeOfDay",
+    "from",
+    "to",
+    "allowedRegions",
+    "allowedEnvironments",
+    "authorityProfile",
+];
+
+/// The CBOR tag carrying a `sha256:<hex>` digest as its 32 raw bytes. A
+/// profile-local tag from the first-come-first-served range.
+pub const DIGEST_TAG: u64 = 0x5049_4301;
+
+/// Base64url values at least this long (decoded) travel as byte strings.
+const MIN_BINARY_LEN: usize = 16;
+
+fn json_to_cbor(v: &Json) -> Cbor {
+    match v {
+        Json::Null => Cbor::Null,
+        Json::Bool(b) => Cbor::Bool(*b),
+        Json::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                Cbor::Integer(u.into())
+            } else if let Some(i) = n.as_i64() {
+                Cbor::Integer(i.into())
+            } else {
+                Cbor::Float(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        Json::String(s) => text_to_cbor(s),
+        Json::Array(a) => Cbor::Array(a.iter().map(json_to_cbor).collect()),
+        Json::Object(o) => {
+            // §4.2.1: map keys sort by their encoded bytes (integer labels
+            // before text keys, shorter before longer).
+            let mut entries: Vec<(Vec<u8>, Cbor, Cbor)> = o
+                .iter()
+                .map(|(k, v)| {
+                    let key = match LABELS.iter().position(|l| l == k) {
+                        Some(label) => Cbor::Integer(label.into()),
+                        None => Cbor::Text(k.clone()),
+                    };
+                    let mut encoded = Vec::new();
+                    ciborium::into_writer(&key, &mut encoded).expect("cbor key");
+                    (encoded, key, json_to_cbor(v))
+                })
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Cbor::Map(entries.into_iter().map(|(_, k, v)| (k, v)).collect())
+        }
+    }
+}
+
+/// Digests become tagged raw bytes and canonical base64url values become byte
+/// strings; both map back to exactly the original text.
+fn text_to_cbor(s: &str) -> Cbor {
+    if let Some(hex) = s.strip_prefix(DIGEST_PREFIX) {
+        if let Some(raw) = hex_decode_lower(hex).filter(|r| r.len() == 32) {
+            return Cbor::Tag(DIGEST_TAG, Box::new(Cbor::Bytes(raw)));
+        }
+    }
+    if let Ok(raw) = b64_decode(s) {
+        if raw.len() >= MIN_BINARY_LEN && b64_encode(&raw) == s {
+            return Cbor::Bytes(raw);
+        }
+    }
+    Cbor::Text(s.to_string())
+}
+
+fn hex_decode_lower(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    let nibble = |c: u8| match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        _ => None,
+    };
+    hex.as_bytes()
+        .chunks(2)
+        .map(|p| Some(nibble(p[0])? << 4 | nibble(p[1])?))
+        .collect()
+}
+
+fn cbor_to_json(v: Cbor) -> Result<Json, CodecError> {
+    let unsupported = |what: &str| CodecError::Cbor {
+        reason: format!("unsupported item: {what}"),
+    };
+    Ok(match v {
+        Cbor::Null => Json::Null,
+        Cbor::Bool(b) => Json::Bool(b),
+        Cbor::Integer(i) => {
+            let i = i128::from(i);
+            if let Ok(u) = u64::try_from(i) {
+                Json::from(u)
+            } else if let Ok(s) = i64::try_from(i) {
+                Json::from(s)
+            } else {
+                return Err(unsupported("integer out of range"));
+            }
+        }
+        Cbor::Float(f) => serde_json::Number::from_f64(f)
+            .map(Json::Number)
+            .ok_or_else(|| unsupported("non-finite float"))?,
+        Cbor::Text(s) => Json::String(s),
+        Cbor::Bytes(b) => Json::String(b64_encode(&b)),
+        Cbor::Tag(DIGEST_TAG, inner) => match *inner {
+            Cbor::Bytes(b) if b.len() == 32 => {
+                Json::String(format!("{DIGEST_PREFIX}{}", hex_lower(&b)))
+            }
+            _ => return Err(unsupported("malformed digest")),
+        },
+        Cbor::Tag(..) => return Err(unsupported("tag")),
+        Cbor::Array(a) => Json::Array(a.into_iter().map(cbor_to_json).collect::<Result<_, _>>()?),
+        Cbor::Map(m) => {
+            let mut o = serde_json::Map::new();
+            for (k, v) in m {
+                let k = match k {
+                    Cbor::Text(k) => k,
+                    Cbor::Integer(label) => usize::try_from(i128::from(label))
+                        .ok()
+                        .and_then(|l| LABELS.get(l))
+                        .ok_or_else(|| unsupported("unknown map label"))?
+                        .to_string(),
+                    _ => return Err(unsupported("non-text map key")),
+                };
+                if o.insert(k, cbor_to_json(v)?).is_some() {
+                    return Err(unsupported("duplicate map key"));
+                }
+            }
+            Json::Object(o)
+        }
+        _ => return Err(unsupported("simple value")),
+    })
+}
@@ -3,16 +3,25 @@
 // Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
 // Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
 
-//! Ed25519 keys, the key registry, canonical JSON, and SHA-256 digests.
+//! Signing identities and the key registry (any [`SignatureSuite`], versioned
+//! keys with validity windows), canonical JSON, and SHA-256 digests.
 
-use crate::{PicResult, DIGEST_PREFIX};
+use crate::did::DidResolver;
+use crate::error::{ResolveError, SignatureError};
+use crate::jcs;
+use crate::signer::Signer;
+use crate::suite::{self, PublicKey, SecretKey, SignatureSuite};
+use crate::types::Proof;
+use crate::{rfc3339, PicError, PicResult, DIGEST_PREFIX};
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
-use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
-use rand::RngCore;
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
 
 /// Encodes bytes as URL-safe base64 without padding (the profile's encoding).
 pub fn b64_encode(b: &[u8]) -> String {
@@ -24,24 +33,28 @@ pub fn b64_decode(s: &str) -> Result<Vec<u8>, base64::DecodeError> {
     URL_SAFE_NO_PAD.decode(s)
 }
 
-/// An executor, issuer, or principal: an Ed25519 key pair addressed by an
-/// identifier (a DID in the examples) and a verification method.
+/// An executor, issuer, or principal: a key pair of some signature suite
+/// addressed by an identifier (a DID in the examples) and a verification method.
+/// The private key is wiped from memory when the identity is dropped.
 pub struct Identity {
     pub id: String,
     pub verification_method: String,
-    signing: SigningKey,
+    signing: SecretKey,
 }
 
 impl Identity {
     /// Generates a fresh Ed25519 identity. The verification method is the
     /// identifier with a `#key-1` fragment, matching the spec examples.
     pub fn new(id: &str) -> Identity {
-        let mut seed = [0u8; 32];
-        rand::rngs::OsRng.fill_bytes(&mut seed);
+        Identity::new_with(id, SignatureSuite::Ed25519)
+    }
+
+    /// [`Identity::new`] with a key of `suite`.
+    pub fn new_with(id: &str, suite: SignatureSuite) -> Identity {
         Identity {
             id: id.to_string(),
             verification_method: format!("{id}#key-1"),
-            signing: SigningKey::from_bytes(&seed),
+            signing: SecretKey::generate(suite),
         }
     }
 
@@ -49,11 +62,18 @@ impl Identity {
     /// deterministic identities from fixtures. An empty verification method
     /// defaults to `id + "#key-1"`.
     pub fn load(id: &str, verification_method: &str, seed: &[u8]) -> PicResult<Identity> {
-        if seed.len() != 32 {
-            return Err(format!("seed must be 32 bytes, got {}", seed.len()));
-        }
-        let mut arr = [0u8; 32];
-        arr.copy_from_slice(seed);
+        Identity::load_with(SignatureSuite::Ed25519, id, verification_method, seed)
+    }
+
+    /// [`Identity::load`] for a key of `suite`; `secret` is the JWK "d"
+    /// parameter (the Edwards seed or the P-256 scalar).
+    pub fn load_with(
+        suite: SignatureSuite,
+        id: &str,
+        verification_method: &str,
+        secret: &[u8],
+    ) -> PicResult<Identity> {
+        let signing = SecretKey::from_bytes(suite, secret).map_err(PicError::Key)?;
         let vm = if verification_method.is_empty() {
             format!("{id}#key-1")
         } else {
@@ -62,81 +82,483 @@ impl Identity {
         Ok(Identity {
             id: id.to_string(),
             verification_method: vm,
-            signing: SigningKey::from_bytes(&arr),
+            signing,
         })
     }
 
-    /// The raw 32-byte Ed25519 public key.
-    pub fn public_bytes(&self) -> [u8; 32] {
-        self.signing.verifying_key().to_bytes()
+    /// The signature suite of this identity's key.
+    pub fn suite(&self) -> SignatureSuite {
+        self.signing.suite()
+    }
+
+    /// The `Proof.type_` of this identity's signatures.
+    pub fn proof_type(&self) -> &'static str {
+        self.suite().proof_type()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.signing.public_key()
+    }
+
+    /// The raw public key (see [`PublicKey::to_bytes`]).
+    pub fn public_bytes(&self) -> Vec<u8> {
+        self.public_key().to_bytes()
     }
 
     /// A detached signature over `msg` (base64url, no padding).
     pub fn sign(&self, msg: &[u8]) -> String {
-        b64_encode(&self.signing.sign(msg).to_bytes())
+        b64_encode(&self.sign_raw(msg))
     }
 
-    /// The base64url of the raw public key (the JWK "x" parameter).
+    /// The raw signature over `msg`, for binary envelopes such as COSE_Sign1.
+    pub fn sign_raw(&self, msg: &[u8]) -> Vec<u8> {
+        self.signing.sign(msg)
+    }
+
+    /// The base64url of the raw public key (the JWK "x" parameter of the
+    /// Edwards curves).
     pub fn encode_public(&self) -> String {
         b64_encode(&self.public_bytes())
     }
 
-    /// The base64url of the 32-byte Ed25519 seed (the JWK "d" parameter). For
-    /// fixtures/testing only.
-    pub fn seed(&self) -> String {
-        b64_encode(&self.signing.to_bytes())
+    /// The raw secret, in a buffer wiped on drop.
+    pub(crate) fn secret(&self) -> Zeroizing<Vec<u8>> {
+        self.signing.to_bytes()
     }
 }
 
-/// Resolves a verification method (or bare issuer id) to a public key. It stands
-/// in for a DID resolver / key distribution mechanism.
+/// One version of a controller's key (`did#key-1`, `did#key-2`, …) and the
+/// window in which it may sign: `[notBefore, notAfter)`, cut short at
+/// `revokedAt` if the key is marked compromised. Open bounds are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEntry {
+    /// The identifier (DID) the key belongs to.
+    pub controller: String,
+    pub verification_method: String,
+    pub key: PublicKey,
+    pub not_before: Option<DateTime<Utc>>,
+    pub not_after: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl KeyEntry {
+    /// An entry for `signer`'s key with an unbounded window.
+    pub fn new(signer: &dyn Signer) -> KeyEntry {
+        KeyEntry {
+            controller: signer.id().to_string(),
+            verification_method: signer.verification_method().to_string(),
+            key: signer.public_key(),
+            not_before: None,
+            not_after: None,
+            revoked_at: None,
+        }
+    }
+
+    /// Whether the key may sign at `at`.
+    pub fn valid_at(&self, at: DateTime<Utc>) -> bool {
+        self.check_at(at).is_ok()
+    }
+
+    fn check_at(&self, at: DateTime<Utc>) -> Result<(), SignatureError> {
+        if let Some(revoked_at) = self.revoked_at.filter(|r| at >= *r) {
+            return Err(SignatureError::KeyCompromised {
+                reference: self.verification_method.clone(),
+                revoked_at: rfc3339(revoked_at),
+            });
+        }
+        if self.not_before.is_some_and(|t| at < t) || self.not_after.is_some_and(|t| at >= t) {
+            return Err(SignatureError::KeyNotValid {
+                reference: self.verification_method.clone(),
+                at: rfc3339(at),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Resolves a verification method, or a bare controller id, to the keys valid
+/// at some instant. Keys are registered directly or, with a [`DidResolver`],
+/// resolved the first time an unknown DID is looked up and cached from then
+/// on. A DID that fails to resolve is refused from the cache for a while
+/// too, so documents naming unknown DIDs cannot make every lookup reach the
+/// resolver (see [`Registry::with_failure_ttl`]).
+///
+/// A controller may hold several key versions over time; each signature is
+/// checked under the version valid when the document claims to have been
+/// signed, so rotating a key leaves earlier chains verifiable.
+///
+/// A compromised key can still be used to backdate a document to before its
+/// `revokedAt`; the temporal containment of each hop in its predecessor's
+/// window (§6.3) is what bounds how far back.
 #[derive(Default)]
 pub struct Registry {
-    keys: HashMap<String, VerifyingKey>,
+    store: RwLock<Store>,
+    resolver: Option<Box<dyn DidResolver>>,
+    failure_ttl: Duration,
+}
+
+#[derive(Default)]
+struct Store {
+    methods: HashMap<String, Arc<KeyEntry>>,
+    /// The verification methods of each controller, in registration order.
+    controllers: HashMap<String, Vec<String>>,
+    /// DIDs the resolver failed on, with when and why.
+    failed: HashMap<String, (Instant, ResolveError)>,
+}
+
+/// How long a failed resolution is cached by default.
+const FAILURE_TTL: Duration = Duration::from_secs(60);
+
+impl Store {
+    fn insert(&mut self, entry: KeyEntry) {
+        let vms = self
+            .controllers
+            .entry(entry.controller.clone())
+            .or_default();
+        if !vms.contains(&entry.verification_method) {
+            vms.push(entry.verification_method.clone());
+        }
+        self.methods
+            .insert(entry.verification_method.clone(), Arc::new(entry));
+    }
 }
 
 impl Registry {
     pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// A registry that resolves DIDs it has no key for through `resolver`.
+    /// A failed resolution is cached for a minute: a document published
+    /// later is found once that has passed.
+    pub fn with_resolver(resolver: impl DidResolver + 'static) -> Registry {
         Registry {
-            keys: HashMap::new(),
+            store: RwLock::default(),
+            resolver: Some(Box::new(resolver)),
+            failure_ttl: FAILURE_TTL,
         }
     }
 
-    /// Registers an identity under both its verification method and its id, so a
-    /// Verifier can resolve either form.
-    pub fn add(&mut self, id: &Identity) {
-        let vk = id.signing.verifying_key();
-        self.keys.insert(id.verification_method.clone(), vk);
-        self.keys.insert(id.id.clone(), vk);
+    /// Caches failed resolutions for `ttl` instead; zero disables the cache.
+    pub fn with_failure_ttl(mut self, ttl: Duration) -> Registry {
+        self.failure_ttl = ttl;
+        self
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Store> {
+        self.store.read().unwrap_or_else(PoisonError::into_inner)
     }
 
-    /// Checks `sig` (base64url) over `msg` for the key registered under `reference`.
-    pub fn verify(&self, reference: &str, msg: &[u8], sig: &str) -> PicResult<()> {
-        let vk = self
-            .keys
-            .get(reference)
-            .ok_or_else(|| format!("unknown verification method {reference:?}"))?;
-        let raw = b64_decode(sig).map_err(|e| format!("malformed signature: {e}"))?;
-        let bytes: [u8; 64] = raw
-            .as_slice()
-            .try_into()
-            .map_err(|_| "malformed signature: wrong length".to_string())?;
-        let signature = Signature::from_bytes(&bytes);
-        vk.verify(msg, &signature)
-            .map_err(|_| format!("signature does not verify under {reference:?}"))
+    fn store_mut(&mut self) -> &mut Store {
+        self.store.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers a signer's key, valid at any time, so a Verifier can resolve
+    /// it by its verification method or its id.
+    pub fn add(&mut self, signer: &dyn Signer) {
+        self.add_key(KeyEntry::new(signer));
+    }
+
+    /// Registers one key version. Re-registering a verification method
+    /// replaces that version; other versions of the controller are kept.
+    pub fn add_key(&mut self, entry: KeyEntry) {
+        self.store_mut().insert(entry);
+    }
+
+    /// Rotates `next`'s controller to `next`'s key as of `at`: every other
+    /// version of the controller stops being valid at `at` and the new one
+    /// starts there. `next` must be a new verification method (`#key-2`).
+    pub fn rotate(&mut self, next: &dyn Signer, at: DateTime<Utc>) -> PicResult<()> {
+        // Versions only a resolver knows of are retired too.
+        let _ = self.ensure(next.id());
+        let store = self.store_mut();
+        if store.methods.contains_key(next.verification_method()) {
+            return Err(PicError::Key(format!(
+                "verification method {:?} is already registered",
+                next.verification_method()
+            )));
+        }
+        for vm in store.controllers.get(next.id()).into_iter().flatten() {
+            if let Some(e) = store.methods.get_mut(vm) {
+                let e = Arc::make_mut(e);
+                e.not_after = Some(e.not_after.map_or(at, |t| t.min(at)));
+            }
+        }
+        store.insert(KeyEntry {
+            not_before: Some(at),
+            ..KeyEntry::new(next)
+        });
+        Ok(())
+    }
+
+    /// Marks the key of `verification_method` compromised as of `at`: it no
+    /// longer verifies anything claiming to be signed at or after `at`.
+    pub fn mark_compromised(
+        &mut self,
+        verification_method: &str,
+        at: DateTime<Utc>,
+    ) -> PicResult<()> {
+        let _ = self.ensure(controller_of(verification_method));
+        let e = self
+            .store_mut()
+            .methods
+            .get_mut(verification_method)
+            .ok_or_else(|| {
+                PicError::Key(format!(
+                    "unknown verification method {verification_method:?}"
+                ))
+            })?;
+        let e = Arc::make_mut(e);
+        e.revoked_at = Some(e.revoked_at.map_or(at, |t| t.min(at)));
+        Ok(())
+    }
+
+    /// The key version registered under `verification_method`, whatever its
+    /// window.
+    pub fn entry(&self, verification_method: &str) -> Option<Arc<KeyEntry>> {
+        let _ = self.ensure(controller_of(verification_method));
+        self.read().methods.get(verification_method).cloned()
+    }
+
+    /// The keys `reference` resolves to at `at`: the version it names if it
+    /// is a verification method valid then, or every version of a
+    /// controller valid then.
+    pub fn keys_at(&self, reference: &str, at: DateTime<Utc>) -> Vec<Arc<KeyEntry>> {
+        self.resolve(reference, "", at).unwrap_or_default()
+    }
+
+    /// [`Registry::keys_at`] the current instant.
+    pub fn current_keys(&self, reference: &str) -> Vec<Arc<KeyEntry>> {
+        self.keys_at(reference, Utc::now())
+    }
+
+    /// Checks `proof` over `msg`, as signed at `at`, under the keys
+    /// `reference` resolves to then. A controller reference uses the version
+    /// the proof names, if it is one of the controller's; otherwise any
+    /// version valid at `at`. The proof type selects the suite, which must be
+    /// the suite of the key.
+    pub fn verify_at(
+        &self,
+        reference: &str,
+        msg: &[u8],
+        proof: &Proof,
+        at: DateTime<Utc>,
+    ) -> Result<(), SignatureError> {
+        let suite = SignatureSuite::from_proof_type(&proof.type_)?;
+        let keys = self.resolve(reference, &proof.verification_method, at)?;
+        let candidates: Vec<_> = keys.iter().filter(|e| e.key.suite() == suite).collect();
+        if candidates.is_empty() {
+            return Err(SignatureError::SuiteMismatch {
+                reference: reference.to_string(),
+                proof_type: proof.type_.clone(),
+                key_suite: keys[0].key.suite().proof_type().to_string(),
+            });
+        }
+        let raw = b64_decode(&proof.signature).map_err(|e| SignatureError::Malformed {
+            reason: e.to_string(),
+        })?;
+        first_verifying(candidates.into_iter(), reference, msg, &raw)
+    }
+
+    /// [`Registry::verify_at`] the current instant.
+    pub fn verify(&self, reference: &str, msg: &[u8], proof: &Proof) -> Result<(), SignatureError> {
+        self.verify_at(reference, msg, proof, Utc::now())
+    }
+
+    /// Checks a raw signature over `msg`, as made at `at`, under the keys
+    /// `reference` resolves to then, each in its own suite.
+    pub fn verify_raw_at(
+        &self,
+        reference: &str,
+        msg: &[u8],
+        raw: &[u8],
+        at: DateTime<Utc>,
+    ) -> Result<(), SignatureError> {
+        let keys = self.resolve(reference, "", at)?;
+        first_verifying(keys.iter(), reference, msg, raw)
+    }
+
+    /// [`Registry::verify_raw_at`] the current instant.
+    pub fn verify_raw(
+        &self,
+        reference: &str,
+        msg: &[u8],
+        raw: &[u8],
+    ) -> Result<(), SignatureError> {
+        self.verify_raw_at(reference, msg, raw, Utc::now())
+    }
+
+    /// Checks every `(reference, msg, proof, at)` in `checks` with a single
+    /// Ed25519 batch equation. Returns false if the batch fails or any check
+    /// does not resolve to exactly one Ed25519 key valid at `at`, or is not an
+    /// Ed25519 proof; the caller then checks one by one to find the culprit.
+    ///
+    /// The batch equation is the cofactored one [`Registry::verify`] uses for
+    /// a single Ed25519 signature, and the same small-order keys and `R`s are
+    /// refused, so a batch passes only if every signature in it would.
+    pub fn verify_batch(&self, checks: &[(&str, &[u8], &Proof, DateTime<Utc>)]) -> bool {
+        let mut msgs = Vec::with_capacity(checks.len());
+        let mut sigs = Vec::with_capacity(checks.len());
+        let mut keys = Vec::with_capacity(checks.len());
+        for (reference, msg, proof, at) in checks {
+            let Some(key) = self.batch_key(reference, proof, *at) else {
+                return false;
+            };
+            if proof.type_ != SignatureSuite::Ed25519.proof_type() {
+                return false;
+            }
+            let Some(raw) = b64_decode(&proof.signature)
+                .ok()
+                .and_then(|raw| <[u8; 64]>::try_from(raw).ok())
+            else {
+                return false;
+            };
+            msgs.push(*msg);
+            sigs.push(ed25519_dalek::Signature::from_bytes(&raw));
+            keys.push(key);
+        }
+        suite::ed25519_verify(&msgs, &sigs, &keys)
+    }
+
+    /// The single Ed25519 key `proof` would be checked under at `at`, if it
+    /// can take part in a batch.
+    pub(crate) fn batch_key(
+        &self,
+        reference: &str,
+        proof: &Proof,
+        at: DateTime<Utc>,
+    ) -> Option<ed25519_dalek::VerifyingKey> {
+        let keys = self
+            .resolve(reference, &proof.verification_method, at)
+            .ok()?;
+        match keys.as_slice() {
+            [e] => match e.key {
+                PublicKey::Ed25519(key) => Some(key),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Resolves `did` through the resolver unless its keys are already known.
+    fn ensure(&self, did: &str) -> Result<(), SignatureError> {
+        let Some(resolver) = &self.resolver else {
+            return Ok(());
+        };
+        {
+            let store = self.read();
+            if store.controllers.contains_key(did) {
+                return Ok(());
+            }
+            if let Some((at, e)) = store.failed.get(did) {
+                if at.elapsed() < self.failure_ttl {
+                    return Err(SignatureError::Resolution(e.clone()));
+                }
+            }
+        }
+        let resolved = resolver.resolve(did);
+        let mut store = self.store.write().unwrap_or_else(PoisonError::into_inner);
+        let entries = match resolved {
+            Ok(entries) => entries,
+            Err(e) => {
+                // Expired failures are dropped as new ones come in, so the
+                // cache holds at most what fails within one TTL.
+                let ttl = self.failure_ttl;
+                store.failed.retain(|_, (at, _)| at.elapsed() < ttl);
+                store
+                    .failed
+                    .insert(did.to_string(), (Instant::now(), e.clone()));
+                return Err(SignatureError::Resolution(e));
+            }
+        };
+        store.failed.remove(did);
+        if !store.controllers.contains_key(did) {
+            for e in entries {
+                store.insert(KeyEntry {
+                    controller: did.to_string(),
+                    ..e
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The keys `reference` resolves to at `at`; `hint` is the verification
+    /// method a proof names, preferred among a controller's versions.
+    fn resolve(
+        &self,
+        reference: &str,
+        hint: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<Arc<KeyEntry>>, SignatureError> {
+        self.ensure(controller_of(reference))?;
+        let store = self.read();
+        if let Some(e) = store.methods.get(reference) {
+            return e.check_at(at).map(|()| vec![Arc::clone(e)]);
+        }
+        let vms =
+            store
+                .controllers
+                .get(reference)
+                .ok_or_else(|| SignatureError::UnknownMethod {
+                    reference: reference.to_string(),
+                })?;
+        if let Some(e) = store
+            .methods
+            .get(hint)
+            .filter(|e| e.controller == reference)
+        {
+            return e.check_at(at).map(|()| vec![Arc::clone(e)]);
+        }
+        let valid: Vec<_> = vms
+            .iter()
+            .filter_map(|vm| store.methods.get(vm))
+            .filter(|e| e.valid_at(at))
+            .cloned()
+            .collect();
+        if valid.is_empty() {
+            return Err(SignatureError::KeyNotValid {
+                reference: reference.to_string(),
+                at: rfc3339(at),
+            });
+        }
+        Ok(valid)
     }
 }
 
-/// Returns a deterministic JSON encoding of `v`: object keys sorted
-/// lexicographically, no insignificant whitespace. This is the reproducible byte
-/// representation a hash or signature covers (spec §6.4).
-///
-/// `serde_json::Value` maps are `BTreeMap` (sorted) when `preserve_order` is off,
-/// so `to_value` then `to_vec` yields sorted-keys compact bytes — matching Go's
-/// canonicalization for our (ASCII, no `<>&`) data.
+/// The DID part of a verification method (or a bare DID, unchanged).
+fn controller_of(reference: &str) -> &str {
+    reference.split_once('#').map_or(reference, |(did, _)| did)
+}
+
+/// Succeeds if any of `keys` verifies `raw`; otherwise the first key's error.
+fn first_verifying<'a>(
+    keys: impl Iterator<Item = &'a Arc<KeyEntry>>,
+    reference: &str,
+    msg: &[u8],
+    raw: &[u8],
+) -> Result<(), SignatureError> {
+    let mut first = None;
+    for e in keys {
+        match e.key.verify(reference, msg, raw) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                first.get_or_insert(err);
+            }
+        }
+    }
+    Err(first.unwrap_or_else(|| SignatureError::UnknownMethod {
+        reference: reference.to_string(),
+    }))
+}
+
+/// Returns the RFC 8785 (JCS) canonical encoding of `v`: members sorted by
+/// UTF-16 code units, ECMAScript string escaping and number formatting, no
+/// insignificant whitespace. This is the reproducible byte representation a
+/// hash or signature covers (spec §6.4); see [`crate::jcs`].
 pub fn canonical_json<T: Serialize>(v: &T) -> Vec<u8> {
     let value = serde_json::to_value(v).expect("canonical to_value");
-    serde_json::to_vec(&value).expect("canonical to_vec")
+    jcs::to_vec(&value)
 }
 
 /// Returns "sha256:<hex>" over the canonical encoding of `v`.
@@ -170,7 +592,5 @@ pub fn hex_lower(b: &[u8]) -> String {
 
 /// Returns `n` random bytes encoded as base64url (challenges, nonces).
 pub fn random_b64(n: usize) -> String {
-    let mut buf = vec![0u8; n];
-    rand::rngs::OsRng.fill_bytes(&mut buf);
-    b64_encode(&buf)
+    crate::sources::random_b64_from(&crate::sources::OsEntropy, n)
 }
@@ -0,0 +1,372 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! DID resolution: the public keys of a DID, for a [`Registry`] to consult
+//! the first time it meets an identifier it has no key for. Nothing here
+//! needs a private key, so a verifier-only deployment builds its registry
+//! from public material alone.
+//!
+//! - `did:key` — a multibase (base58btc) multicodec public key: Ed25519
+//!   (`0xed`), P-256 (`0x1200`, compressed) and, with the `ed448` feature,
+//!   Ed448 (`0x1203`). The verification method is `did#<multibase>`.
+//! - `did:jwk` — a base64url public JWK (`OKP`/`Ed25519`, `EC`/`P-256`); the
+//!   verification method is `did#0`.
+//! - `did:web` — the `did.json` documents of a local directory, one per host,
+//!   in place of `https://<host>/.well-known/did.json`.
+//!
+//! [`Registry`]: crate::Registry
+
+use crate::crypto::{b64_decode, b64_encode, KeyEntry};
+use crate::error::ResolveError;
+use crate::parse_rfc3339;
+use crate::suite::{PublicKey, SignatureSuite};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resolves a DID to the key versions its document lists.
+pub trait DidResolver: Send + Sync {
+    /// The assertion keys of `did`, each with `did` as controller.
+    fn resolve(&self, did: &str) -> Result<Vec<KeyEntry>, ResolveError>;
+}
+
+fn invalid(did: &str, reason: impl Into<String>) -> ResolveError {
+    ResolveError::Invalid {
+        did: did.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn entry(did: &str, verification_method: String, key: PublicKey) -> KeyEntry {
+    KeyEntry {
+        controller: did.to_string(),
+        verification_method,
+        key,
+        not_before: None,
+        not_after: None,
+        revoked_at: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// did:key
+// ---------------------------------------------------------------------------
+
+/// The multicodec varint prefix of each suite's public keys.
+fn multicodec(suite: SignatureSuite) -> &'static [u8] {
+    match suite {
+        SignatureSuite::Ed25519 => &[0xed, 0x01],
+        SignatureSuite::EcdsaP256 => &[0x80, 0x24],
+        #[cfg(feature = "ed448")]
+        SignatureSuite::Ed448 => &[0x83, 0x24],
+    }
+}
+
+/// The `did:key` of `key` (P-256 keys in compressed form).
+pub fn did_key(key: &PublicKey) -> String {
+    let mut raw = multicodec(key.suite()).to_vec();
+    raw.extend(key.to_bytes());
+    format!("did:key:z{}", bs58::encode(raw).into_string())
+}
+
+/// Resolves `did:key` identifiers; no document is fetched.
+pub struct DidKey;
+
+impl DidResolver for DidKey {
+    fn resolve(&self, did: &str) -> Result<Vec<KeyEntry>, ResolveError> {
+        let mb = did
+            .strip_prefix("did:key:")
+            .ok_or_else(|| invalid(did, "not a did:key"))?;
+        let key = multibase_key(mb).ok_or_else(|| invalid(did, "unsupported multikey"))?;
+        Ok(vec![entry(did, format!("{did}#{mb}"), key)])
+    }
+}
+
+/// Decodes a base58btc multibase multicodec public key.
+fn multibase_key(mb: &str) -> Option<PublicKey> {
+    let raw = bs58::decode(mb.strip_prefix('z')?).into_vec().ok()?;
+    SignatureSuite::ALL.iter().find_map(|suite| {
+        let key = raw.strip_prefix(multicodec(*suite))?;
+        PublicKey::from_bytes(*suite, key)
+    })
+}
+
+// ---------------------------------------------------------------------------
+// did:jwk
+// ---------------------------------------------------------------------------
+
+/// The public JWK of `key`.
+pub fn public_key_jwk(key: &PublicKey) -> Value {
+    let crv = key.suite().jwk_curve();
+    match key {
+        PublicKey::EcdsaP256(k) => {
+            let point = k.to_encoded_point(false);
+            json!({
+                "kty": "EC",
+                "crv": crv,
+                "x": b64_encode(point.x().expect("uncompressed point")),
+                "y": b64_encode(point.y().expect("uncompressed point")),
+            })
+        }
+        _ => json!({ "kty": "OKP", "crv": crv, "x": b64_encode(&key.to_bytes()) }),
+    }
+}
+
+/// Decodes a public JWK of a supported suite. A JWK carrying a private
+/// part (`d`) is refused.
+pub fn public_key_from_jwk(jwk: &Value) -> Option<PublicKey> {
+    if jwk.get("d").is_some() {
+        return None;
+    }
+    let suite = SignatureSuite::from_jwk_curve(jwk.get("crv")?.as_str()?)?;
+    let coordinate = |name: &str| b64_decode(jwk.get(name)?.as_str()?).ok();
+    match (jwk.get("kty")?.as_str()?, suite) {
+        ("EC", SignatureSuite::EcdsaP256) => {
+            let mut sec1 = vec![0x04];
+            sec1.extend(coordinate("x")?);
+            sec1.extend(coordinate("y")?);
+            PublicKey::from_bytes(suite, &sec1)
+        }
+        ("OKP", SignatureSuite::EcdsaP256) | ("EC", _) => None,
+        ("OKP", _) => PublicKey::from_bytes(suite, &coordinate("x")?),
+        _ => None,
+    }
+}
+
+/// The `did:jwk` of `key`.
+pub fn did_jwk(key: &PublicKey) -> String {
+    let jwk = serde_json::to_vec(&public_key_jwk(key)).expect("jwk to_vec");
+    format!("did:jwk:{}", b64_encode(&jwk))
+}
+
+/// Resolves `did:jwk` identifiers; no document is fetched.
+pub struct DidJwk;
+
+impl DidResolver for DidJwk {
+    fn resolve(&self, did: &str) -> Result<Vec<KeyEntry>, ResolveError> {
+        let encoded = did
+            .strip_prefix("did:jwk:")
+            .ok_or_else(|| invalid(did, "not a did:jwk"))?;
+        let jwk: Value = b64_decode(encoded)
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .ok_or_else(|| invalid(did, "not a base64url JSON JWK"))?;
+        let key = public_key_from_jwk(&jwk).ok_or_else(|| invalid(did, "unsupported JWK"))?;
+        Ok(vec![entry(did, format!("{did}#0"), key)])
+    }
+}
+
+// ---------------------------------------------------------------------------
+// did:web
+// ---------------------------------------------------------------------------
+
+/// Resolves `did:web` identifiers from local `did.json` files:
+/// `did:web:<host>[:<path>…]` reads `<dir>/<path>…/did.json`, where `<dir>`
+/// is the directory mapped to the host, or `<root>/<host>`.
+pub struct DidWeb {
+    root: PathBuf,
+    hosts: HashMap<String, PathBuf>,
+}
+
+impl DidWeb {
+    pub fn new(root: impl Into<PathBuf>) -> DidWeb {
+        DidWeb {
+            root: root.into(),
+            hosts: HashMap::new(),
+        }
+    }
+
+    /// Serves `host` from `dir` instead of `<root>/<host>`.
+    pub fn map_host(mut self, host: &str, dir: impl Into<PathBuf>) -> DidWeb {
+        self.hosts.insert(host.to_string(), dir.into());
+        self
+    }
+
+    /// A resolver over a directory of per-identity subdirectories (the
+    /// `v0.2/fixtures/identities` layout): each `<sub>/did.json` whose id is
+    /// `did:web:<host>` serves that host.
+    pub fn index(dir: &Path) -> Result<DidWeb, String> {
+        let mut web = DidWeb::new(dir);
+        let entries = fs::read_dir(dir).map_err(|e| format!("read {}: {e}", dir.display()))?;
+        for entry in entries {
+            let sub = entry.map_err(|e| e.to_string())?.path();
+            let Ok(raw) = fs::read(sub.join("did.json")) else {
+                continue;
+            };
+            let doc: Value = serde_json::from_slice(&raw)
+                .map_err(|e| format!("{}: {e}", sub.join("did.json").display()))?;
+            if let Some(host) = doc["id"]
+                .as_str()
+                .and_then(|id| id.strip_prefix("did:web:"))
+            {
+                if !host.contains(':') {
+                    web.hosts.insert(percent_decode(host), sub);
+                }
+            }
+        }
+        Ok(web)
+    }
+
+    fn document_path(&self, did: &str) -> Result<PathBuf, ResolveError> {
+        let rest = did
+            .strip_prefix("did:web:")
+            .ok_or_else(|| invalid(did, "not a did:web"))?;
+        let mut parts = rest.split(':').map(percent_decode);
+        let host = parts.next().unwrap_or_default();
+        let mut path = match self.hosts.get(&host) {
+            Some(dir) => dir.clone(),
+            None => self.root.join(safe_segment(did, &host)?),
+        };
+        for seg in parts {
+            path.push(safe_segment(did, &seg)?);
+        }
+        path.push("did.json");
+        Ok(path)
+    }
+}
+
+impl DidResolver for DidWeb {
+    fn resolve(&self, did: &str) -> Result<Vec<KeyEntry>, ResolveError> {
+        let path = self.document_path(did)?;
+        let raw = fs::read(&path).map_err(|e| ResolveError::NotFound {
+            did: did.to_string(),
+            reason: format!("{}: {e}", path.display()),
+        })?;
+        let doc: Value =
+            serde_json::from_slice(&raw).map_err(|e| invalid(did, format!("did.json: {e}")))?;
+        document_keys(did, &doc)
+    }
+}
+
+/// A host or path segment that stays inside its directory.
+fn safe_segment<'s>(did: &str, seg: &'s str) -> Result<&'s str, ResolveError> {
+    if seg.is_empty() || seg == "." || seg == ".." || seg.contains(['/', '\\']) {
+        return Err(invalid(did, format!("unsafe did:web segment {seg:?}")));
+    }
+    Ok(seg)
+}
+
+/// Decodes the `%XX` escapes of a did:web segment (a port is `%3A`).
+fn percent_decode(seg: &str) -> String {
+    let bytes = seg.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (c, _) => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// The assertion keys a DID document lists: every verification method named
+/// by `assertionMethod` (all of them if the document has none), from its
+/// `publicKeyJwk` or `publicKeyMultibase`. A method's `expires` and `revoked`
+/// bound its window. The document must be the one of `did`.
+pub fn document_keys(did: &str, doc: &Value) -> Result<Vec<KeyEntry>, ResolveError> {
+    if doc["id"].as_str() != Some(did) {
+        return Err(invalid(did, format!("document id is {}", doc["id"])));
+    }
+    let absolute = |id: &str| match id.strip_prefix('#') {
+        Some(fragment) => format!("{did}#{fragment}"),
+        None => id.to_string(),
+    };
+    let assertion: Option<Vec<String>> = doc["assertionMethod"].as_array().map(|refs| {
+        refs.iter()
+            .filter_map(|r| r.as_str().or_else(|| r["id"].as_str()))
+            .map(absolute)
+            .collect()
+    });
+    let mut keys = Vec::new();
+    for method in doc["verificationMethod"].as_array().into_iter().flatten() {
+        let vm = absolute(method["id"].as_str().unwrap_or_default());
+        if !vm.starts_with(&format!("{did}#")) {
+            return Err(invalid(did, format!("foreign verification method {vm:?}")));
+        }
+        if assertion.as_ref().is_some_and(|a| !a.contains(&vm)) {
+            continue;
+        }
+        let key = match (
+            method.get("publicKeyJwk"),
+            method["publicKeyMultibase"].as_str(),
+        ) {
+            (Some(jwk), _) => public_key_from_jwk(jwk),
+            (None, Some(mb)) => multibase_key(mb),
+            (None, None) => None,
+        }
+        .ok_or_else(|| invalid(did, format!("unsupported key in {vm:?}")))?;
+        let time = |name: &str| -> Result<_, ResolveError> {
+            method[name]
+                .as_str()
+                .map(|t| parse_rfc3339(t).map_err(|e| invalid(did, e.to_string())))
+                .transpose()
+        };
+        keys.push(KeyEntry {
+            not_after: time("expires")?,
+            revoked_at: time("revoked")?,
+            ..entry(did, vm, key)
+        });
+    }
+    if keys.is_empty() {
+        return Err(invalid(did, "no assertion key"));
+    }
+    Ok(keys)
+}
+
+// ---------------------------------------------------------------------------
+// Dispatch by method
+// ---------------------------------------------------------------------------
+
+/// Dispatches on the DID method. `did:key` and `did:jwk` are built in;
+/// `did:web` (or any other method) is opt-in.
+pub struct Resolvers {
+    methods: HashMap<String, Box<dyn DidResolver>>,
+}
+
+impl Default for Resolvers {
+    fn default() -> Resolvers {
+        Resolvers::new()
+    }
+}
+
+impl Resolvers {
+    pub fn new() -> Resolvers {
+        Resolvers {
+            methods: HashMap::new(),
+        }
+        .with("key", DidKey)
+        .with("jwk", DidJwk)
+    }
+
+    /// Resolves `did:<method>:…` identifiers with `resolver`.
+    pub fn with(mut self, method: &str, resolver: impl DidResolver + 'static) -> Resolvers {
+        self.methods.insert(method.to_string(), Box::new(resolver));
+        self
+    }
+}
+
+impl DidResolver for Resolvers {
+    fn resolve(&self, did: &str) -> Result<Vec<KeyEntry>, ResolveError> {
+        did.strip_prefix("did:")
+            .and_then(|rest| rest.split(':').next())
+            .and_then(|method| self.methods.get(method))
+            .ok_or_else(|| ResolveError::UnsupportedMethod {
+                did: did.to_string(),
+            })?
+            .resolve(did)
+    }
+}
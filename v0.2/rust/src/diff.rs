@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Structured explanation of one hop: how a successor's invariants differ
+//! from its predecessor's under the reference profile.
+//!
+//! [`diff`] classifies every operation (preserved, narrowed, removed, added or
+//! expanded, see [`crate::operation`]) and every set contract field and
+//! constraint (preserved, strengthened or relaxed), and reports every
+//! violation. [`crate::attenuates`] is the first of them, so the two cannot
+//! disagree.
+
+use crate::authority::{go_slice, narrows, restricts, Violation};
+use crate::execution_model::ModelLattice;
+use crate::operation::Operation;
+use crate::types::{Constraint, ExecutionContract, Invariants};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// What became of one operation across the hop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationChange {
+    /// Carried unchanged.
+    Preserved,
+    /// A current operation strictly covered by predecessor ones.
+    Narrowed,
+    /// A predecessor operation nothing current falls under or expands.
+    Removed,
+    /// A current operation unrelated to any predecessor one.
+    Added,
+    /// A current operation strictly covering predecessor ones.
+    Expanded,
+}
+
+/// One operation of either side and what became of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OperationDiff {
+    pub operation: String,
+    pub change: OperationChange,
+    /// The predecessor operations that cover it (narrowed) or that it covers
+    /// (expanded).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub predecessor: Vec<String>,
+}
+
+/// What became of one contract field or constraint across the hop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContractChange {
+    Preserved,
+    Strengthened,
+    Relaxed,
+}
+
+/// One execution-contract field (`role`, `compliance`, `executionModel`,
+/// `allowedRegions`, `allowedEnvironments`) or constraint
+/// (`constraints.<name>`) set on either side, with both values as shown in
+/// violations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractDiff {
+    pub field: String,
+    pub change: ContractChange,
+    pub current: String,
+    pub predecessor: String,
+}
+
+/// How a successor's invariants differ from its predecessor's. It attenuates
+/// exactly when `violations` is empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvariantDiff {
+    /// Current operations in order, then the removed predecessor ones.
+    pub operations: Vec<OperationDiff>,
+    /// Contract fields in check order, then constraints by name.
+    pub contract: Vec<ContractDiff>,
+    /// Every violation; the first is the one [`crate::attenuates`] returns.
+    #[serde(serialize_with = "display_all")]
+    pub violations: Vec<Violation>,
+}
+
+impl InvariantDiff {
+    /// Reports whether the successor attenuates its predecessor.
+    pub fn attenuates(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Reports whether nothing changed across the hop.
+    pub fn is_unchanged(&self) -> bool {
+        self.operations
+            .iter()
+            .all(|o| o.change == OperationChange::Preserved)
+            && self
+                .contract
+                .iter()
+                .all(|c| c.change == ContractChange::Preserved)
+    }
+
+    /// The operations with the given change, in diff order.
+    pub fn operations_with(&self, change: OperationChange) -> Vec<String> {
+        self.operations
+            .iter()
+            .filter(|o| o.change == change)
+            .map(|o| o.operation.clone())
+            .collect()
+    }
+}
+
+/// One line: the changed operations by kind, then each changed contract
+/// field, e.g. `removed [backup]; executionModel strengthened (agentic →
+/// deterministic)`; `unchanged` when nothing changed.
+impl fmt::Display for InvariantDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        for (change, label) in [
+            (OperationChange::Narrowed, "narrowed"),
+            (OperationChange::Removed, "removed"),
+            (OperationChange::Added, "added"),
+            (OperationChange::Expanded, "expanded"),
+        ] {
+            let ops = self.operations_with(change);
+            if !ops.is_empty() {
+                parts.push(format!("{label} {}", go_slice(&ops)));
+            }
+        }
+        for c in &self.contract {
+            let label = match c.change {
+                ContractChange::Preserved => continue,
+                ContractChange::Strengthened => "strengthened",
+                ContractChange::Relaxed => "relaxed",
+            };
+            parts.push(format!(
+                "{} {label} ({} → {})",
+                c.field,
+                unset(&c.predecessor),
+                unset(&c.current)
+            ));
+        }
+        if parts.is_empty() {
+            return f.write_str("unchanged");
+        }
+        f.write_str(&parts.join("; "))
+    }
+}
+
+fn unset(v: &str) -> &str {
+    if v.is_empty() {
+        "unset"
+    } else {
+        v
+    }
+}
+
+fn display_all<S: Serializer>(violations: &[Violation], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(violations.iter().map(ToString::to_string))
+}
+
+/// Explains `current` as a continuation of `predecessor` under the reference
+/// profile (see [`crate::ReferenceProfile::diff`] for a configured one).
+pub fn diff(current: &Invariants, predecessor: &Invariants) -> InvariantDiff {
+    diff_under(ModelLattice::reference(), current, predecessor)
+}
+
+/// [`diff`] with execution models ordered by `models`.
+pub(crate) fn diff_under(
+    models: &ModelLattice,
+    current: &Invariants,
+    predecessor: &Invariants,
+) -> InvariantDiff {
+    let mut d = InvariantDiff {
+        operations: Vec::new(),
+        contract: Vec::new(),
+        violations: Vec::new(),
+    };
+    diff_operations(&mut d, &current.operations, &predecessor.operations);
+    diff_contract(
+        &mut d,
+        models,
+        &current.execution_contract,
+        &predecessor.execution_contract,
+    );
+    diff_constraints(&mut d, &current.constraints, &predecessor.constraints);
+    d
+}
+
+fn diff_operations(d: &mut InvariantDiff, current: &[String], predecessor: &[String]) {
+    let cur: Vec<Operation> = current.iter().map(|op| Operation::parse(op)).collect();
+    let pred: Vec<Operation> = predecessor.iter().map(|op| Operation::parse(op)).collect();
+    let related = |keep: &dyn Fn(&Operation) -> bool| -> Vec<String> {
+        predecessor
+            .iter()
+            .zip(&pred)
+            .filter(|(_, p)| keep(p))
+            .map(|(s, _)| s.clone())
+            .collect()
+    };
+    for (op, want) in current.iter().zip(&cur) {
+        let (change, predecessors) = if predecessor.contains(op) {
+            (OperationChange::Preserved, Vec::new())
+        } else {
+            let covering = related(&|p| p.covers(want));
+            if !covering.is_empty() {
+                (OperationChange::Narrowed, covering)
+            } else {
+                d.violations.push(Violation::OperationAdded {
+                    operation: op.clone(),
+                });
+                match related(&|p| want.covers(p)) {
+                    covered if covered.is_empty() => (OperationChange::Added, covered),
+                    covered => (OperationChange::Expanded, covered),
+                }
+            }
+        };
+        d.operations.push(OperationDiff {
+            operation: op.clone(),
+            change,
+            predecessor: predecessors,
+        });
+    }
+    for (op, granted) in predecessor.iter().zip(&pred) {
+        if !cur.iter().any(|c| granted.covers(c) || c.covers(granted)) {
+            d.operations.push(OperationDiff {
+                operation: op.clone(),
+                change: OperationChange::Removed,
+                predecessor: Vec::new(),
+            });
+        }
+    }
+}
+
+fn diff_contract(
+    d: &mut InvariantDiff,
+    models: &ModelLattice,
+    current: &ExecutionContract,
+    predecessor: &ExecutionContract,
+) {
+    let role = if !predecessor.role.is_empty() && current.role != predecessor.role {
+        Err(Violation::RoleRelaxed {
+            current: current.role.clone(),
+            predecessor: predecessor.role.clone(),
+        })
+    } else {
+        Ok(current.role == predecessor.role)
+    };
+    d.field("role", role, &current.role, &predecessor.role);
+
+    let dropped: Vec<Violation> = predecessor
+        .compliance
+        .iter()
+        .filter(|c| !current.compliance.contains(c))
+        .map(|c| Violation::ComplianceDropped {
+            compliance: c.clone(),
+        })
+        .collect();
+    let compliance = if dropped.is_empty() {
+        Ok(same(&current.compliance, &predecessor.compliance))
+    } else {
+        Err(dropped)
+    };
+    d.field_all(
+        "compliance",
+        compliance,
+        &go_list(&current.compliance),
+        &go_list(&predecessor.compliance),
+    );
+
+    let model = match restricts(
+        models,
+        &current.execution_model,
+        &predecessor.execution_model,
+    ) {
+        Ok(true) => Ok(current.execution_model == predecessor.execution_model),
+        Ok(false) => Err(Violation::ExecutionModelRelaxed {
+            current: current.execution_model.clone(),
+            predecessor: predecessor.execution_model.clone(),
+        }),
+        Err(v) => Err(v),
+    };
+    d.field(
+        "executionModel",
+        model,
+        &current.execution_model,
+        &predecessor.execution_model,
+    );
+
+    for (field, cur, pred) in [
+        (
+            "allowedRegions",
+            &current.allowed_regions,
+            &predecessor.allowed_regions,
+        ),
+        (
+            "allowedEnvironments",
+            &current.allowed_environments,
+            &predecessor.allowed_environments,
+        ),
+    ] {
+        let allowed = narrows(field, cur, pred).map(|()| same(cur, pred));
+        d.field(field, allowed, &go_list(cur), &go_list(pred));
+    }
+}
+
+fn diff_constraints(
+    d: &mut InvariantDiff,
+    current: &BTreeMap<String, Constraint>,
+    predecessor: &BTreeMap<String, Constraint>,
+) {
+    let names: BTreeSet<&String> = current.keys().chain(predecessor.keys()).collect();
+    for name in names {
+        let (cur, pred) = (current.get(name), predecessor.get(name));
+        let outcome = match (cur, pred) {
+            (_, None) => Ok(false),
+            (None, Some(_)) => Err(Violation::ConstraintDropped { name: name.clone() }),
+            (Some(c), Some(p)) if c.within(p) => Ok(c == p),
+            (Some(c), Some(p)) => Err(Violation::ConstraintRelaxed {
+                name: name.clone(),
+                current: c.to_string(),
+                predecessor: p.to_string(),
+            }),
+        };
+        let shown = |c: Option<&Constraint>| c.map(ToString::to_string).unwrap_or_default();
+        d.field(
+            &format!("constraints.{name}"),
+            outcome,
+            &shown(cur),
+            &shown(pred),
+        );
+    }
+}
+
+impl InvariantDiff {
+    /// Records a field whose check kept it (`Ok(unchanged)`) or broke it.
+    fn field(
+        &mut self,
+        field: &str,
+        outcome: Result<bool, Violation>,
+        current: &str,
+        predecessor: &str,
+    ) {
+        self.field_all(field, outcome.map_err(|v| vec![v]), current, predecessor);
+    }
+
+    fn field_all(
+        &mut self,
+        field: &str,
+        outcome: Result<bool, Vec<Violation>>,
+        current: &str,
+        predecessor: &str,
+    ) {
+        if current.is_empty() && predecessor.is_empty() {
+            return;
+        }
+        let change = match outcome {
+            Ok(true) => ContractChange::Preserved,
+            Ok(false) => ContractChange::Strengthened,
+            Err(violations) => {
+                self.violations.extend(violations);
+                ContractChange::Relaxed
+            }
+        };
+        self.contract.push(ContractDiff {
+            field: field.to_string(),
+            change,
+            current: current.to_string(),
+            predecessor: predecessor.to_string(),
+        });
+    }
+}
+
+/// Reports whether two lists hold the same members.
+fn same(a: &[String], b: &[String]) -> bool {
+    a.iter().all(|v| b.contains(v)) && b.iter().all(|v| a.contains(v))
+}
+
+/// A list as shown in a diff: empty when unset.
+fn go_list(v: &[String]) -> String {
+    if v.is_empty() {
+        String::new()
+    } else {
+        go_slice(v)
+    }
+}
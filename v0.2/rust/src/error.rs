@@ -0,0 +1,1251 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! The typed error model: one `PicError` variant per ordered Verifier check
+//! (Prover/Verifier spec §3.3) plus origin, envelope, snapshot, and
+//! guarded-acceptance failures.
+//!
+//! Every variant carries the offending values and a stable [`PicError::code`]
+//! tied to the spec section it enforces, so callers can tell a replay from an
+//! expired challenge or a revocation without matching on text. `Display`
+//! renders the same human-readable messages the prototype has always printed.
+
+use crate::authority::Violation;
+use crate::revocation::Revoked;
+use chrono::Duration;
+use std::fmt;
+
+/// A failure of any fallible PIC operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PicError {
+    /// PCA0 validation (§3.2, Revocation spec §2.1, §2.4).
+    Origin(OriginError),
+    /// Check 1: the single signature over the whole PCA.
+    Integrity {
+        hop: Option<usize>,
+        error: IntegrityError,
+    },
+    /// Check 2: `previousPcaHash` binds exactly the presented predecessor.
+    Binding {
+        hop: Option<usize>,
+        error: BindingError,
+    },
+    /// Revocation-coordinate continuity (Revocation spec §2.3).
+    Coordinates {
+        hop: Option<usize>,
+        error: CoordinateError,
+    },
+    /// Check 3: the continuation challenge response.
+    Continuation {
+        hop: Option<usize>,
+        error: ContinuationError,
+    },
+    /// Check 4: the embedded executor attestation.
+    Attestation {
+        hop: Option<usize>,
+        error: AttestationError,
+    },
+    /// Check 5: attested attributes against the predecessor contract.
+    Conformance {
+        hop: Option<usize>,
+        violation: Violation,
+    },
+    /// Check 6: the successor invariants are not expansive.
+    NonExpansion {
+        hop: Option<usize>,
+        violation: Violation,
+    },
+    /// Check 7: the hop window is contained in the predecessor's (§6.3).
+    Temporal {
+        hop: Option<usize>,
+        error: TemporalError,
+    },
+    /// Check 8: the executed request does not match the signed request
+    /// binding.
+    RequestBinding(RequestBindingError),
+    /// An active revocation strikes the PCA (Revocation spec §3.1).
+    Revocation {
+        hop: Option<usize>,
+        checkpoint: Checkpoint,
+        revoked: Revoked,
+    },
+    /// Handoff envelope validation (§2.5, §6.8).
+    Envelope(EnvelopeError),
+    /// Snapshot issuance or validation (§5.2).
+    Snapshot(SnapshotError),
+    /// Enforced acceptance of a Sandboxed Execution crossing.
+    GuardedAcceptance(AcceptanceError),
+    /// A document could not be decoded from its wire encoding (§6.4).
+    Codec(CodecError),
+    /// The Prover refused to emit an expansive or non-conforming successor
+    /// (§2.3, §2.4).
+    SelfCheck(Violation),
+    /// A full-chain validation was handed no PCAs.
+    EmptyChain,
+    /// Malformed key material.
+    Key(String),
+    /// The shared fixtures could not be read or parsed.
+    Fixture(String),
+    /// A [`crate::signer::Signer`] could not produce a signature.
+    Signer(SignerError),
+    /// An encrypted [`crate::keystore::Keystore`] could not be opened, read
+    /// or updated.
+    Keystore(KeystoreError),
+}
+
+impl PicError {
+    /// The stable, machine-readable code of this error, naming the spec section
+    /// whose check failed.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PicError::Origin(_) => "PIC-PV-3.2-ORIGIN",
+            PicError::Integrity { .. } => "PIC-PV-3.3.1-INTEGRITY",
+            PicError::Binding { .. } => "PIC-PV-3.3.2-BINDING",
+            PicError::Coordinates { .. } => "PIC-REV-2.3-COORDINATES",
+            PicError::Continuation { .. } => "PIC-PV-3.3.3-CONTINUATION",
+            PicError::Attestation { .. } => "PIC-PV-3.3.4-ATTESTATION",
+            PicError::Conformance { .. } => "PIC-PV-3.3.5-CONFORMANCE",
+            PicError::NonExpansion { .. } => "PIC-PV-3.3.6-NON-EXPANSION",
+            PicError::Temporal { .. } => "PIC-PV-3.3.7-TEMPORAL",
+            PicError::RequestBinding(_) => "PIC-PV-3.3.8-REQUEST-BINDING",
+            PicError::Revocation { .. } => "PIC-REV-3.1-REVOKED",
+            PicError::Envelope(_) => "PIC-PV-6.8-ENVELOPE",
+            PicError::Snapshot(_) => "PIC-PV-5.2-SNAPSHOT",
+            PicError::GuardedAcceptance(_) => "PIC-SE-ENFORCED-ACCEPTANCE",
+            PicError::Codec(_) => "PIC-PV-6.4-ENCODING",
+            PicError::SelfCheck(_) => "PIC-PV-2.4-SELF-CHECK",
+            PicError::EmptyChain => "PIC-PV-5.1-EMPTY-CHAIN",
+            PicError::Key(_) => "PIC-KEY",
+            PicError::Fixture(_) => "PIC-FIXTURE",
+            PicError::Signer(_) => "PIC-SIGNER",
+            PicError::Keystore(_) => "PIC-KEYSTORE",
+        }
+    }
+
+    /// The chain position the failure refers to: 0 for origin failures, the hop
+    /// index for per-hop checks run by a full-chain validation, and the index of
+    /// the innermost failing hop for wrapped errors. `None` when unknown (a
+    /// single `verify_hop` call) or not hop-related.
+    pub fn hop(&self) -> Option<usize> {
+        match self {
+            PicError::Origin(_) => Some(0),
+            PicError::Revocation {
+                checkpoint: Checkpoint::Origin,
+                ..
+            } => Some(0),
+            PicError::Integrity { hop, .. }
+            | PicError::Binding { hop, .. }
+            | PicError::Coordinates { hop, .. }
+            | PicError::Continuation { hop, .. }
+            | PicError::Attestation { hop, .. }
+            | PicError::Conformance { hop, .. }
+            | PicError::NonExpansion { hop, .. }
+            | PicError::Temporal { hop, .. }
+            | PicError::Revocation { hop, .. } => *hop,
+            PicError::Snapshot(SnapshotError::InvalidChain(e))
+            | PicError::Snapshot(SnapshotError::Hop(e))
+            | PicError::GuardedAcceptance(AcceptanceError::OuterInvalid(e))
+            | PicError::GuardedAcceptance(AcceptanceError::CarriedLineageInvalid {
+                error: e,
+                ..
+            }) => e.hop(),
+            _ => None,
+        }
+    }
+
+    /// Stamps a per-hop error with its index in the chain being validated.
+    /// Errors that are not per-hop checks are returned unchanged.
+    pub(crate) fn at_hop(mut self, index: usize) -> PicError {
+        match &mut self {
+            PicError::Integrity { hop, .. }
+            | PicError::Binding { hop, .. }
+            | PicError::Coordinates { hop, .. }
+            | PicError::Continuation { hop, .. }
+            | PicError::Attestation { hop, .. }
+            | PicError::Conformance { hop, .. }
+            | PicError::NonExpansion { hop, .. }
+            | PicError::Temporal { hop, .. } => *hop = Some(index),
+            PicError::Revocation {
+                hop,
+                checkpoint: Checkpoint::Hop,
+                ..
+            } => *hop = Some(index),
+            _ => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for PicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = |f: &mut fmt::Formatter<'_>, hop: &Option<usize>| match hop {
+            Some(i) => write!(f, "hop {i}: "),
+            None => Ok(()),
+        };
+        match self {
+            PicError::Origin(e) => write!(f, "origin validation: {e}"),
+            PicError::Integrity { hop, error } => {
+                prefix(f, hop)?;
+                match error {
+                    IntegrityError::MissingSignature => write!(f, "hop: missing signature"),
+                    IntegrityError::Signature(e) => write!(f, "hop integrity: {e}"),
+                }
+            }
+            PicError::Binding { hop, error } => {
+                prefix(f, hop)?;
+                match error {
+                    BindingError::NoProofOfRelationship => {
+                        write!(f, "hop validation: PCA carries no Proof of Relationship")
+                    }
+                    BindingError::PreviousHashMismatch { .. } => write!(
+                        f,
+                        "hop binding: previousPcaHash does not match the presented predecessor"
+                    ),
+                }
+            }
+            PicError::Coordinates { hop, error } => {
+                prefix(f, hop)?;
+                write!(f, "hop coordinates: {error}")
+            }
+            PicError::Continuation { hop, error } => {
+                prefix(f, hop)?;
+                write!(f, "hop continuation: {error}")
+            }
+            PicError::Attestation { hop, error } => {
+                prefix(f, hop)?;
+                write!(f, "hop attestation: {error}")
+            }
+            PicError::Conformance { hop, violation } => {
+                prefix(f, hop)?;
+                write!(f, "hop conformance: {violation}")
+            }
+            PicError::NonExpansion { hop, violation } => {
+                prefix(f, hop)?;
+                write!(f, "hop non-expansion: {violation}")
+            }
+            PicError::Temporal { hop, error } => {
+                prefix(f, hop)?;
+                write!(f, "hop temporal: {error}")
+            }
+            PicError::RequestBinding(e) => write!(f, "executed-vs-signed: {e}"),
+            PicError::Revocation {
+                hop,
+                checkpoint,
+                revoked,
+            } => {
+                prefix(f, hop)?;
+                match checkpoint {
+                    Checkpoint::Origin => write!(f, "origin validation: {revoked}"),
+                    Checkpoint::Hop => write!(f, "hop revocation: {revoked}"),
+                    Checkpoint::EnvelopePredecessor => {
+                        write!(f, "envelope predecessor: {revoked}")
+                    }
+                    Checkpoint::SnapshotTip => write!(f, "snapshot verify tip: {revoked}"),
+                }
+            }
+            PicError::Envelope(e) => write!(f, "{e}"),
+            PicError::Snapshot(e) => write!(f, "{e}"),
+            PicError::GuardedAcceptance(e) => write!(f, "enforced acceptance: {e}"),
+            PicError::Codec(e) => write!(f, "decode: {e}"),
+            PicError::SelfCheck(v) => write!(f, "prover self-check failed: {v}"),
+            PicError::EmptyChain => write!(f, "empty chain"),
+            PicError::Key(msg) | PicError::Fixture(msg) => write!(f, "{msg}"),
+            PicError::Signer(e) => write!(f, "signer: {e}"),
+            PicError::Keystore(e) => write!(f, "keystore: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PicError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PicError::Snapshot(SnapshotError::InvalidChain(e))
+            | PicError::Snapshot(SnapshotError::Hop(e))
+            | PicError::GuardedAcceptance(AcceptanceError::OuterInvalid(e))
+            | PicError::GuardedAcceptance(AcceptanceError::CarriedLineageInvalid {
+                error: e,
+                ..
+            }) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Lets command-line code that reports plain strings use `?` on PIC results.
+impl From<PicError> for String {
+    fn from(e: PicError) -> String {
+        e.to_string()
+    }
+}
+
+/// A wire-encoded document that is malformed, non-deterministic, or whose
+/// COSE_Sign1 seal does not verify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    UnknownEncoding,
+    Json {
+        reason: String,
+    },
+    Cbor {
+        reason: String,
+    },
+    NonDeterministic,
+    Cose {
+        reason: String,
+    },
+    UnsupportedAlgorithm {
+        alg: String,
+    },
+    ContentType {
+        expected: String,
+        found: String,
+    },
+    MissingKid,
+    MissingPayload,
+    Seal(SignatureError),
+    /// An untrusted document exceeds one of the decoder's [`crate::Limits`].
+    Limit {
+        limit: Limit,
+        max: usize,
+        found: usize,
+    },
+    /// Strict decoding met a field the profile does not define.
+    UnknownField {
+        path: String,
+    },
+    /// A number beyond ±2^53, which the canonical form would round.
+    InexactNumber {
+        value: String,
+    },
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::UnknownEncoding => write!(f, "neither JSON nor COSE_Sign1"),
+            CodecError::Json { reason } => write!(f, "json: {reason}"),
+            CodecError::Cbor { reason } => write!(f, "cbor: {reason}"),
+            CodecError::NonDeterministic => {
+                write!(f, "cbor: payload is not in core deterministic encoding")
+            }
+            CodecError::Cose { reason } => write!(f, "cose: {reason}"),
+            CodecError::UnsupportedAlgorithm { alg } => {
+                write!(f, "cose: unsupported algorithm {alg}")
+            }
+            CodecError::ContentType { expected, found } => {
+                write!(f, "cose: content type {found:?}, expected {expected:?}")
+            }
+            CodecError::MissingKid => write!(f, "cose: missing kid"),
+            CodecError::MissingPayload => write!(f, "cose: detached payloads are not supported"),
+            CodecError::Seal(e) => write!(f, "cose seal: {e}"),
+            CodecError::Limit { limit, max, found } => {
+                write!(f, "{limit} {found} exceeds the limit of {max}")
+            }
+            CodecError::UnknownField { path } => write!(f, "unknown field {path:?}"),
+            CodecError::InexactNumber { value } => {
+                write!(
+                    f,
+                    "number {value} has no exact canonical form (beyond ±2^53)"
+                )
+            }
+        }
+    }
+}
+
+/// The resource bounds enforced on untrusted input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Bytes,
+    Depth,
+    ChainLength,
+    CarriedLineages,
+    StringLength,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Bytes => write!(f, "input size"),
+            Limit::Depth => write!(f, "nesting depth"),
+            Limit::ChainLength => write!(f, "chain length"),
+            Limit::CarriedLineages => write!(f, "carried lineage count"),
+            Limit::StringLength => write!(f, "string length"),
+        }
+    }
+}
+
+/// A timestamp that is not a valid RFC 3339 date-time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeError {
+    pub value: String,
+    pub reason: String,
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rfc3339 time {:?}: {}", self.value, self.reason)
+    }
+}
+
+/// Where in validation a revocation was found to strike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checkpoint {
+    Origin,
+    Hop,
+    EnvelopePredecessor,
+    SnapshotTip,
+}
+
+/// A detached signature could not be checked or did not verify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    UnknownMethod {
+        reference: String,
+    },
+    Malformed {
+        reason: String,
+    },
+    Invalid {
+        reference: String,
+    },
+    /// The proof names a suite this build does not support.
+    UnsupportedSuite {
+        proof_type: String,
+    },
+    /// The proof names a suite other than that of the resolved key.
+    SuiteMismatch {
+        reference: String,
+        proof_type: String,
+        key_suite: String,
+    },
+    /// No key of `reference` was valid at the instant the document claims
+    /// to have been signed.
+    KeyNotValid {
+        reference: String,
+        at: String,
+    },
+    /// The key was marked compromised at or before that instant.
+    KeyCompromised {
+        reference: String,
+        revoked_at: String,
+    },
+    /// The reference's DID could not be resolved to a key.
+    Resolution(ResolveError),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::UnknownMethod { reference } => {
+                write!(f, "unknown verification method {reference:?}")
+            }
+            SignatureError::Malformed { reason } => write!(f, "malformed signature: {reason}"),
+            SignatureError::Invalid { reference } => {
+                write!(f, "signature does not verify under {reference:?}")
+            }
+            SignatureError::UnsupportedSuite { proof_type } => {
+                write!(f, "unsupported signature suite {proof_type:?}")
+            }
+            SignatureError::SuiteMismatch {
+                reference,
+                proof_type,
+                key_suite,
+            } => write!(
+                f,
+                "proof type {proof_type:?} does not match the {key_suite:?} key {reference:?}"
+            ),
+            SignatureError::KeyNotValid { reference, at } => {
+                write!(f, "no key of {reference:?} is valid at {at}")
+            }
+            SignatureError::KeyCompromised {
+                reference,
+                revoked_at,
+            } => write!(f, "key {reference:?} compromised as of {revoked_at}"),
+            SignatureError::Resolution(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// A [`crate::did::DidResolver`] could not produce the keys of a DID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// No resolver handles the DID method.
+    UnsupportedMethod { did: String },
+    /// The DID document does not exist.
+    NotFound { did: String, reason: String },
+    /// The DID, or its document, is malformed or names unsupported keys.
+    Invalid { did: String, reason: String },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::UnsupportedMethod { did } => {
+                write!(f, "cannot resolve {did:?}: unsupported DID method")
+            }
+            ResolveError::NotFound { did, reason } => {
+                write!(f, "cannot resolve {did:?}: not found: {reason}")
+            }
+            ResolveError::Invalid { did, reason } => write!(f, "cannot resolve {did:?}: {reason}"),
+        }
+    }
+}
+
+/// An encrypted keystore operation failed. No variant carries secret
+/// material.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeystoreError {
+    /// The keystore file could not be read or written.
+    Io { path: String, reason: String },
+    /// The passphrase does not open the keystore.
+    WrongPassphrase,
+    /// The file is not a keystore of a supported version, or an entry does
+    /// not decrypt under the keystore key (tampered or swapped).
+    Corrupt { reason: String },
+    /// No identity is stored under the name.
+    UnknownIdentity { name: String },
+    /// A different key is already stored under the name.
+    Conflict { name: String },
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeystoreError::Io { path, reason } => write!(f, "{path}: {reason}"),
+            KeystoreError::WrongPassphrase => write!(f, "wrong passphrase"),
+            KeystoreError::Corrupt { reason } => write!(f, "corrupt keystore: {reason}"),
+            KeystoreError::UnknownIdentity { name } => write!(f, "no identity named {name:?}"),
+            KeystoreError::Conflict { name } => {
+                write!(f, "a different key is already stored as {name:?}")
+            }
+        }
+    }
+}
+
+/// An executed request does not match the signed request binding (see
+/// [`crate::requestbinding`]). `field` is the wire name of the request field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestBindingError {
+    /// The signed request leaves the field empty, so nothing binds it.
+    Unbound { field: &'static str },
+    /// The field recomputed from the received request differs from the
+    /// signed one.
+    Mismatch {
+        field: &'static str,
+        signed: String,
+        received: String,
+    },
+}
+
+impl fmt::Display for RequestBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestBindingError::Unbound { field } => {
+                write!(f, "signed request carries no {field}")
+            }
+            RequestBindingError::Mismatch {
+                field,
+                signed,
+                received,
+            } => write!(f, "{field} signed {signed}, received {received}"),
+        }
+    }
+}
+
+/// A signer (in process or remote) did not return a usable signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerError {
+    /// The signing service could not be reached or did not answer.
+    Unavailable { reason: String },
+    /// The signing service answered with a refusal (unknown key, policy).
+    Refused { reason: String },
+    /// The service broke the protocol, e.g. returned a signature that does
+    /// not verify under the key it advertised.
+    Protocol { reason: String },
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerError::Unavailable { reason } => {
+                write!(f, "signing service unavailable: {reason}")
+            }
+            SignerError::Refused { reason } => write!(f, "signing refused: {reason}"),
+            SignerError::Protocol { reason } => write!(f, "signing protocol: {reason}"),
+        }
+    }
+}
+
+/// A signed document is used outside its `[issuedAt, expiresAt)` window, as
+/// widened by the skew tolerances, or its window is longer than allowed (see
+/// [`crate::TemporalPolicy`]). `by` is the amount past the bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidityError {
+    NotYetValid {
+        issued_at: String,
+        skew: Duration,
+        by: Duration,
+    },
+    Expired {
+        expires_at: String,
+        skew: Duration,
+        by: Duration,
+    },
+    WindowTooLong {
+        bound: TemporalBound,
+        max: Duration,
+        by: Duration,
+    },
+    MalformedTime(TimeError),
+}
+
+impl ValidityError {
+    /// The temporal bound crossed, if the error is not a malformed time.
+    pub fn bound(&self) -> Option<TemporalBound> {
+        match self {
+            ValidityError::NotYetValid { .. } => Some(TemporalBound::ForwardSkew),
+            ValidityError::Expired { .. } => Some(TemporalBound::BackwardSkew),
+            ValidityError::WindowTooLong { bound, .. } => Some(*bound),
+            ValidityError::MalformedTime(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ValidityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidityError::NotYetValid { skew, by, .. } => write!(
+                f,
+                "not yet valid: issued {} beyond the {} forward skew",
+                span(*by),
+                span(*skew)
+            ),
+            ValidityError::Expired { skew, by, .. } => write!(
+                f,
+                "expired: {} beyond the {} backward skew",
+                span(*by),
+                span(*skew)
+            ),
+            ValidityError::WindowTooLong { bound, max, by } => {
+                write!(f, "{bound} exceeded by {} (max {})", span(*by), span(*max))
+            }
+            ValidityError::MalformedTime(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// A temporal bound a document or a hop can cross.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemporalBound {
+    /// A document issued further ahead of the reader's clock than tolerated.
+    ForwardSkew,
+    /// An expiry passed (or a predecessor's `issuedAt` preceded) by more than
+    /// tolerated.
+    BackwardSkew,
+    HopWindow,
+    ChallengeLifetime,
+    LineageLifetime,
+    /// A successor's `expiresAt`, or its challenge's, past the predecessor's
+    /// `expiresAt`.
+    PredecessorExpiry,
+}
+
+impl fmt::Display for TemporalBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemporalBound::ForwardSkew => write!(f, "forward skew"),
+            TemporalBound::BackwardSkew => write!(f, "backward skew"),
+            TemporalBound::HopWindow => write!(f, "hop window"),
+            TemporalBound::ChallengeLifetime => write!(f, "challenge lifetime"),
+            TemporalBound::LineageLifetime => write!(f, "lineage lifetime"),
+            TemporalBound::PredecessorExpiry => write!(f, "predecessor expiry"),
+        }
+    }
+}
+
+/// Renders a duration for an error message: `350ms`, `2s`, `5m`, `1h30m`.
+fn span(d: Duration) -> String {
+    let ms = d.num_milliseconds();
+    if ms % 1000 != 0 || ms == 0 {
+        return format!("{ms}ms");
+    }
+    let (h, m, s) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60);
+    let mut out = String::new();
+    for (n, unit) in [(h, "h"), (m, "m"), (s, "s")] {
+        if n != 0 {
+            out.push_str(&format!("{n}{unit}"));
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginError {
+    HasProofOfRelationship,
+    MissingSignature,
+    Signature(SignatureError),
+    Validity(ValidityError),
+    UnknownProfile {
+        profile: String,
+    },
+    /// The lineage was minted under an authority profile the Verifier does
+    /// not implement.
+    UnknownAuthorityProfile {
+        profile: String,
+        implemented: String,
+    },
+    CounterNotZero {
+        counter: u64,
+    },
+    LineageIdMismatch {
+        expected: String,
+        found: String,
+    },
+    BranchIdMismatch {
+        expected: String,
+        found: String,
+    },
+    Continuation(ContinuationError),
+    Grant(GrantError),
+}
+
+impl fmt::Display for OriginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OriginError::HasProofOfRelationship => {
+                write!(f, "PCA carries a Proof of Relationship")
+            }
+            OriginError::MissingSignature => write!(f, "missing signature"),
+            OriginError::Signature(e) => write!(f, "{e}"),
+            OriginError::Validity(e) => write!(f, "{e}"),
+            OriginError::UnknownProfile { profile } => write!(f, "unknown profile {profile:?}"),
+            OriginError::UnknownAuthorityProfile {
+                profile,
+                implemented,
+            } => write!(
+                f,
+                "authority profile {profile:?}, verifier implements {implemented:?}"
+            ),
+            OriginError::CounterNotZero { counter } => {
+                write!(f, "lineageCounter must be 0, got {counter}")
+            }
+            OriginError::LineageIdMismatch { .. } => {
+                write!(f, "lineageId does not match origin commitment")
+            }
+            OriginError::BranchIdMismatch { .. } => {
+                write!(f, "branchId is not the derived root branch id")
+            }
+            OriginError::Continuation(e) => write!(f, "{e}"),
+            OriginError::Grant(e) => write!(f, "grant: {e}"),
+        }
+    }
+}
+
+/// A grant does not admit a PCA0, or a GRANT revocation is not the grantor's
+/// (see [`crate::grant`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrantError {
+    /// No trusted grant has the id.
+    Unknown {
+        grant_id: String,
+    },
+    MissingSignature,
+    /// The grantor's signature does not verify.
+    Signature(SignatureError),
+    Validity(ValidityError),
+    /// The PCA0 issuer is not the grantee.
+    GranteeMismatch {
+        grantee: String,
+        issuer: String,
+    },
+    /// The PCA0 invariants exceed the grant's.
+    NotAttenuated(Violation),
+    /// A revocation handed to the grant path is not a GRANT revocation.
+    NotGrantRevocation {
+        strategy: String,
+    },
+    /// A GRANT revocation issued by someone other than the grantor.
+    NotGrantor {
+        grant_id: String,
+        grantor: String,
+        issuer: String,
+    },
+}
+
+impl fmt::Display for GrantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrantError::Unknown { grant_id } => write!(f, "unknown grant {grant_id:?}"),
+            GrantError::MissingSignature => write!(f, "missing grantor signature"),
+            GrantError::Signature(e) => write!(f, "grantor signature: {e}"),
+            GrantError::Validity(e) => write!(f, "{e}"),
+            GrantError::GranteeMismatch { grantee, issuer } => {
+                write!(f, "issuer {issuer} is not the grantee {grantee}")
+            }
+            GrantError::NotAttenuated(v) => write!(f, "exceeds the grant: {v}"),
+            GrantError::NotGrantRevocation { strategy } => {
+                write!(f, "{strategy} is not a GRANT revocation")
+            }
+            GrantError::NotGrantor {
+                grant_id,
+                grantor,
+                issuer,
+            } => write!(
+                f,
+                "only the grantor {grantor} may revoke {grant_id}, not {issuer}"
+            ),
+        }
+    }
+}
+
+/// An execution-model lattice that cannot be built, or a model it does not
+/// declare (see [`crate::execution_model`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LatticeError {
+    /// The model is not declared in the lattice.
+    Unknown { model: String },
+    /// `within` names a model less restrictive than it that is not declared.
+    Undeclared { model: String, within: String },
+    /// The model is, through others, less restrictive than itself.
+    Cycle { model: String },
+    /// The empty name is reserved for the unset model.
+    EmptyModel,
+}
+
+impl fmt::Display for LatticeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LatticeError::Unknown { model } => write!(f, "unknown execution model {model:?}"),
+            LatticeError::Undeclared { model, within } => {
+                write!(f, "{within:?} is within undeclared model {model:?}")
+            }
+            LatticeError::Cycle { model } => {
+                write!(f, "execution model {model:?} is within itself")
+            }
+            LatticeError::EmptyModel => write!(f, "empty execution model name"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    MissingSignature,
+    Signature(SignatureError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingError {
+    NoProofOfRelationship,
+    PreviousHashMismatch { expected: String, found: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoordinateError {
+    ProfileChanged {
+        predecessor: String,
+        current: String,
+    },
+    LineageIdChanged {
+        predecessor: String,
+        current: String,
+    },
+    GrantIdChanged {
+        predecessor: String,
+        current: String,
+    },
+    OriginIssuerChanged {
+        predecessor: String,
+        current: String,
+    },
+    AuthorityProfileChanged {
+        predecessor: String,
+        current: String,
+    },
+    /// The lineage was minted under a profile the Verifier does not implement.
+    UnknownAuthorityProfile {
+        profile: String,
+        implemented: String,
+    },
+    BranchIdChanged {
+        predecessor: String,
+        current: String,
+    },
+    BranchIdNotDerived {
+        expected: String,
+        found: String,
+    },
+    BranchNonceWithoutBranch,
+    CounterNotSuccessor {
+        expected: u64,
+        found: u64,
+    },
+    /// The predecessor's counter is `u64::MAX`: the lineage cannot grow.
+    CounterExhausted {
+        predecessor: u64,
+    },
+}
+
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordinateError::ProfileChanged { .. } => write!(f, "profile changed"),
+            CoordinateError::LineageIdChanged { .. } => write!(f, "lineageId changed"),
+            CoordinateError::GrantIdChanged { .. } => write!(f, "grantId presence/value changed"),
+            CoordinateError::OriginIssuerChanged { .. } => write!(f, "originIssuer changed"),
+            CoordinateError::AuthorityProfileChanged { .. } => {
+                write!(f, "authorityProfile changed")
+            }
+            CoordinateError::UnknownAuthorityProfile {
+                profile,
+                implemented,
+            } => write!(
+                f,
+                "authority profile {profile:?}, verifier implements {implemented:?}"
+            ),
+            CoordinateError::BranchIdChanged { .. } => write!(
+                f,
+                "branchId changed without an authorized branch-creation transition"
+            ),
+            CoordinateError::BranchIdNotDerived { .. } => write!(
+                f,
+                "branchId is not derived from the parent branch, predecessor and branchNonce"
+            ),
+            CoordinateError::BranchNonceWithoutBranch => {
+                write!(f, "branchNonce set but branchId unchanged")
+            }
+            CoordinateError::CounterNotSuccessor { expected, found } => write!(
+                f,
+                "lineageCounter must be predecessor+1 ({expected}), got {found}"
+            ),
+            CoordinateError::CounterExhausted { predecessor } => {
+                write!(f, "lineageCounter {predecessor} has no successor")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContinuationError {
+    ChallengeMismatch {
+        expected: String,
+        found: String,
+    },
+    Expired {
+        expires_at: String,
+        skew: Duration,
+        by: Duration,
+    },
+    Replayed {
+        challenge: String,
+    },
+    Exhausted {
+        challenge: String,
+        max_uses: u32,
+    },
+    UnknownMode {
+        mode: String,
+    },
+    InvalidMaxUses {
+        mode: String,
+        max_uses: i64,
+    },
+    /// The predecessor's audience admits neither the executor nor its
+    /// attested role.
+    NotInAudience {
+        executor: String,
+        role: String,
+    },
+    MalformedTime(TimeError),
+}
+
+impl fmt::Display for ContinuationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContinuationError::ChallengeMismatch { .. } => {
+                write!(f, "response does not answer the predecessor challenge")
+            }
+            ContinuationError::Expired { skew, by, .. } => write!(
+                f,
+                "predecessor challenge expired: {} beyond the {} backward skew",
+                span(*by),
+                span(*skew)
+            ),
+            ContinuationError::Replayed { .. } => {
+                write!(f, "single-use challenge already consumed (replay)")
+            }
+            ContinuationError::Exhausted { max_uses, .. } => write!(
+                f,
+                "multi-use challenge already consumed {max_uses} times (replay)"
+            ),
+            ContinuationError::UnknownMode { mode } => {
+                write!(f, "unknown continuation mode {mode:?}")
+            }
+            ContinuationError::InvalidMaxUses { mode, max_uses } => {
+                write!(f, "maxUses {max_uses} is invalid for a {mode} continuation")
+            }
+            ContinuationError::NotInAudience { executor, role } => write!(
+                f,
+                "executor {executor} (role {role:?}) is not in the predecessor's audience"
+            ),
+            ContinuationError::MalformedTime(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttestationError {
+    MissingSignature,
+    IssuerSignature(SignatureError),
+    Validity(ValidityError),
+    SubjectMismatch {
+        subject: String,
+        executor: String,
+    },
+    KeyNotExecutors {
+        verification_method: String,
+        executor: String,
+    },
+    Missing,
+    BothForms,
+    Unresolved {
+        digest: String,
+    },
+}
+
+impl fmt::Display for AttestationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttestationError::MissingSignature => write!(f, "attestation missing issuer signature"),
+            AttestationError::IssuerSignature(e) => write!(f, "issuer signature: {e}"),
+            AttestationError::Validity(e) => write!(f, "attestation validity: {e}"),
+            AttestationError::SubjectMismatch { subject, executor } => write!(
+                f,
+                "attestation subject {subject:?} does not match executor {executor:?}"
+            ),
+            AttestationError::KeyNotExecutors {
+                verification_method,
+                executor,
+            } => write!(
+                f,
+                "PCA signing key {verification_method:?} does not belong to executor {executor:?}"
+            ),
+            AttestationError::Missing => write!(f, "PoR carries no executor attestation"),
+            AttestationError::BothForms => write!(
+                f,
+                "PoR carries both an embedded attestation and a reference"
+            ),
+            AttestationError::Unresolved { digest } => write!(
+                f,
+                "referenced attestation {digest} is in neither the side table nor the cache"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemporalError {
+    IssuedBeforePredecessor {
+        issued_at: String,
+        predecessor_issued_at: String,
+        skew: Duration,
+        by: Duration,
+    },
+    ExpiresAfterPredecessor {
+        expires_at: String,
+        predecessor_expires_at: String,
+        by: Duration,
+    },
+    Validity(ValidityError),
+    /// The successor's temporal profile relaxes the predecessor's `bound`.
+    ProfileRelaxed {
+        bound: TemporalBound,
+        max: Duration,
+        by: Duration,
+    },
+    ChallengeOutlivesLineage {
+        challenge_expires_at: String,
+        lineage_expires_at: String,
+        by: Duration,
+    },
+    MalformedTime(TimeError),
+}
+
+impl TemporalError {
+    /// The temporal bound crossed, if the error is not a malformed time.
+    pub fn bound(&self) -> Option<TemporalBound> {
+        match self {
+            TemporalError::IssuedBeforePredecessor { .. } => Some(TemporalBound::BackwardSkew),
+            TemporalError::ExpiresAfterPredecessor { .. }
+            | TemporalError::ChallengeOutlivesLineage { .. } => {
+                Some(TemporalBound::PredecessorExpiry)
+            }
+            TemporalError::Validity(e) => e.bound(),
+            TemporalError::ProfileRelaxed { bound, .. } => Some(*bound),
+            TemporalError::MalformedTime(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for TemporalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemporalError::IssuedBeforePredecessor { skew, by, .. } => write!(
+                f,
+                "issuedAt precedes predecessor by {} beyond the {} backward skew",
+                span(*by),
+                span(*skew)
+            ),
+            TemporalError::ExpiresAfterPredecessor { by, .. } => {
+                write!(f, "expiresAt exceeds predecessor by {}", span(*by))
+            }
+            TemporalError::Validity(e) => write!(f, "{e}"),
+            TemporalError::ProfileRelaxed { bound, max, by } => write!(
+                f,
+                "temporal profile relaxes the predecessor's {bound} by {} (max {})",
+                span(*by),
+                span(*max)
+            ),
+            TemporalError::ChallengeOutlivesLineage { by, .. } => {
+                write!(f, "emitted challenge outlives the lineage by {}", span(*by))
+            }
+            TemporalError::MalformedTime(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvelopeError {
+    MissingPca,
+    MissingSignature,
+    Signature(SignatureError),
+    DigestMismatch {
+        predecessor_digest: String,
+        current_digest: String,
+    },
+    CurrentIsOrigin,
+    PredecessorDigestMismatch {
+        previous_pca_hash: String,
+        predecessor_digest: String,
+    },
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::MissingPca => write!(f, "envelope: missing predecessor or current"),
+            EnvelopeError::MissingSignature => write!(f, "envelope: missing signature"),
+            EnvelopeError::Signature(e) => write!(f, "envelope signature: {e}"),
+            EnvelopeError::DigestMismatch { .. } => write!(
+                f,
+                "envelope: supplied digest does not match recomputed digest"
+            ),
+            EnvelopeError::CurrentIsOrigin => {
+                write!(f, "envelope: current carries no Proof of Relationship")
+            }
+            EnvelopeError::PredecessorDigestMismatch { .. } => write!(
+                f,
+                "envelope: current.previousPcaHash does not equal predecessorDigest"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    ThroughIndexOutOfRange { index: usize, len: usize },
+    InvalidChain(Box<PicError>),
+    EmptyTail,
+    MissingSignature,
+    Signature(SignatureError),
+    Validity(ValidityError),
+    TipDigestMismatch { expected: String, found: String },
+    TipCoordinatesMismatch,
+    UnknownAuthorityProfile { expected: String, found: String },
+    Hop(Box<PicError>),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::ThroughIndexOutOfRange { index, .. } => {
+                write!(f, "snapshot: throughIndex {index} out of range")
+            }
+            SnapshotError::InvalidChain(e) => {
+                write!(f, "snapshot: refusing to attest an invalid chain: {e}")
+            }
+            SnapshotError::EmptyTail => write!(f, "snapshot verify: empty tail"),
+            SnapshotError::MissingSignature => write!(f, "snapshot verify: missing signature"),
+            SnapshotError::Signature(e) => write!(f, "snapshot verify: {e}"),
+            SnapshotError::Validity(e) => write!(f, "snapshot verify: {e}"),
+            SnapshotError::TipDigestMismatch { .. } => write!(
+                f,
+                "snapshot verify: tip digest does not match snapshot commitment"
+            ),
+            SnapshotError::TipCoordinatesMismatch => {
+                write!(f, "snapshot verify: tip coordinates do not match snapshot")
+            }
+            SnapshotError::UnknownAuthorityProfile { expected, found } => write!(
+                f,
+                "snapshot verify: authority profile {found:?}, verifier implements {expected:?}"
+            ),
+            SnapshotError::Hop(e) => write!(f, "post-snapshot {e}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcceptanceError {
+    NoSandboxedExecution,
+    OuterInvalid(Box<PicError>),
+    NotStartedAtOrigin,
+    UnauthorizedOrigin { origin: String },
+    NoGuardrailHop,
+    EnforceNotAuthorized,
+    NotEnforceRequest { operation: String },
+    NoMultiLineage,
+    MultiLineageDigestMismatch { expected: String, found: String },
+    EmptyCarriedLineages,
+    CarriedLineageInvalid { label: String, error: Box<PicError> },
+    NotPermitted { result: String },
+    Stale { expires_at: String },
+    MalformedTime(TimeError),
+}
+
+impl fmt::Display for AcceptanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcceptanceError::NoSandboxedExecution => {
+                write!(f, "no Sandboxed Execution presented")
+            }
+            AcceptanceError::OuterInvalid(e) => write!(f, "invalid outer continuation: {e}"),
+            AcceptanceError::NotStartedAtOrigin => {
+                write!(f, "outer chain does not start at PCA0-G")
+            }
+            AcceptanceError::UnauthorizedOrigin { origin } => {
+                write!(f, "sandbox origin {origin:?} is not authorized")
+            }
+            AcceptanceError::NoGuardrailHop => {
+                write!(f, "no guardrail hop (PCA0-G is not a guardrail decision)")
+            }
+            AcceptanceError::EnforceNotAuthorized => {
+                write!(f, "ENFORCE not in outer authority context")
+            }
+            AcceptanceError::NotEnforceRequest { .. } => {
+                write!(f, "executed request is not ENFORCE")
+            }
+            AcceptanceError::NoMultiLineage => write!(f, "no multiLineage"),
+            AcceptanceError::MultiLineageDigestMismatch { .. } => {
+                write!(f, "multiLineageDigest does not match recomputed digest")
+            }
+            AcceptanceError::EmptyCarriedLineages => write!(f, "empty carriedLineages"),
+            AcceptanceError::CarriedLineageInvalid { label, error } => {
+                write!(f, "carried lineage {label:?} invalid: {error}")
+            }
+            AcceptanceError::NotPermitted { .. } => write!(f, "enforcementResult is not permit"),
+            AcceptanceError::Stale { .. } => write!(f, "outside the freshness window"),
+            AcceptanceError::MalformedTime(e) => write!(f, "{e}"),
+        }
+    }
+}
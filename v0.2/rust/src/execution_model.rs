@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! The execution-model order of the reference authority profile.
+//!
+//! Execution models (`deterministic`, `agentic`, `human-in-the-loop`, ...) are
+//! partially ordered by restrictiveness: `a ⊑ b` ([`ModelLattice::restricts`],
+//! read "a is at least as restrictive as b") when an executor running as `a`
+//! may stand wherever `b` is permitted. A contract's `executionModel` names the
+//! least restrictive model it permits, so a conforming executor's attested
+//! model is `⊑` it, and a successor's contract model is `⊑` its predecessor's.
+//! The unset model is the top: it permits any model and is permitted by none
+//! but itself.
+//!
+//! The order is configuration, not code: a [`ModelLattice`] is declared as each
+//! model and the models directly less restrictive than it. In JSON,
+//! [`ModelLattice::reference`] is
+//!
+//! ```json
+//! { "models": { "deterministic": ["agentic"], "agentic": [] } }
+//! ```
+//!
+//! and the fixtures declare a richer one in
+//! `v0.2/fixtures/execution-models.json`. Two models need not be comparable,
+//! and their meet, the least restrictive model within both, need not exist.
+//! A model the lattice does not declare is an error, never a rank.
+
+use crate::error::LatticeError;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+/// A partial order of execution models by restrictiveness.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ModelsFile", into = "ModelsFile")]
+pub struct ModelLattice {
+    /// Each model and the models directly less restrictive than it.
+    within: BTreeMap<String, BTreeSet<String>>,
+    /// Each model and every model at most as restrictive, itself included.
+    up: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModelsFile {
+    models: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl TryFrom<ModelsFile> for ModelLattice {
+    type Error = LatticeError;
+
+    fn try_from(f: ModelsFile) -> Result<ModelLattice, LatticeError> {
+        ModelLattice::new(f.models)
+    }
+}
+
+impl From<ModelLattice> for ModelsFile {
+    fn from(l: ModelLattice) -> ModelsFile {
+        ModelsFile { models: l.within }
+    }
+}
+
+impl ModelLattice {
+    /// Builds the order from each model and the models directly less
+    /// restrictive than it. Every model named must be declared, and the
+    /// declarations must not be circular.
+    pub fn new(within: BTreeMap<String, BTreeSet<String>>) -> Result<ModelLattice, LatticeError> {
+        for (model, above) in &within {
+            if model.is_empty() {
+                return Err(LatticeError::EmptyModel);
+            }
+            if let Some(undeclared) = above.iter().find(|m| !within.contains_key(*m)) {
+                return Err(LatticeError::Undeclared {
+                    model: undeclared.clone(),
+                    within: model.clone(),
+                });
+            }
+        }
+        let mut up = BTreeMap::new();
+        for model in within.keys() {
+            let mut reached = BTreeSet::new();
+            let mut pending = vec![model];
+            while let Some(m) = pending.pop() {
+                if reached.insert(m.clone()) {
+                    pending.extend(&within[m]);
+                }
+            }
+            up.insert(model.clone(), reached);
+        }
+        for (model, reached) in &up {
+            let cycle = reached.iter().any(|m| m != model && up[m].contains(model));
+            if cycle {
+                return Err(LatticeError::Cycle {
+                    model: model.clone(),
+                });
+            }
+        }
+        Ok(ModelLattice { within, up })
+    }
+
+    /// The reference order: `deterministic ⊑ agentic`.
+    pub fn reference() -> &'static ModelLattice {
+        static REFERENCE: OnceLock<ModelLattice> = OnceLock::new();
+        REFERENCE.get_or_init(|| {
+            ModelLattice::new(BTreeMap::from([
+                ("deterministic".to_string(), ["agentic".to_string()].into()),
+                ("agentic".to_string(), BTreeSet::new()),
+            ]))
+            .expect("the reference lattice is well-formed")
+        })
+    }
+
+    /// The declared models, in name order.
+    pub fn models(&self) -> impl Iterator<Item = &str> {
+        self.within.keys().map(String::as_str)
+    }
+
+    /// Reports whether `model` is declared or unset.
+    pub fn contains(&self, model: &str) -> bool {
+        model.is_empty() || self.within.contains_key(model)
+    }
+
+    /// Reports whether `a ⊑ b`: `a` is at least as restrictive as `b`.
+    pub fn restricts(&self, a: &str, b: &str) -> Result<bool, LatticeError> {
+        let above = self.above(a)?;
+        self.above(b)?;
+        Ok(b.is_empty() || above.is_some_and(|up| up.contains(b)))
+    }
+
+    /// Orders `a` against `b`, `Less` being more restrictive; `None` when they
+    /// are incomparable.
+    pub fn compare(&self, a: &str, b: &str) -> Result<Option<Ordering>, LatticeError> {
+        Ok(match (self.restricts(a, b)?, self.restricts(b, a)?) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        })
+    }
+
+    /// The meet of `a` and `b`: the least restrictive model within both, or
+    /// `None` when no single one is. The meet with the unset model is the
+    /// other model.
+    pub fn meet<'s>(&'s self, a: &'s str, b: &'s str) -> Result<Option<&'s str>, LatticeError> {
+        self.above(a)?;
+        self.above(b)?;
+        if a.is_empty() {
+            return Ok(Some(b));
+        }
+        if b.is_empty() {
+            return Ok(Some(a));
+        }
+        let lower: Vec<&str> = self
+            .up
+            .iter()
+            .filter(|(_, up)| up.contains(a) && up.contains(b))
+            .map(|(m, _)| m.as_str())
+            .collect();
+        Ok(lower
+            .iter()
+            .find(|g| lower.iter().all(|l| self.up[*l].contains(**g)))
+            .copied())
+    }
+
+    /// The models at most as restrictive as `model`; `None` for the unset one.
+    fn above(&self, model: &str) -> Result<Option<&BTreeSet<String>>, LatticeError> {
+        if model.is_empty() {
+            return Ok(None);
+        }
+        self.up
+            .get(model)
+            .map(Some)
+            .ok_or_else(|| LatticeError::Unknown {
+                model: model.to_string(),
+            })
+    }
+}
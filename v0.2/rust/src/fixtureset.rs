@@ -3,13 +3,19 @@
 // Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
 // Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
 
-//! Loads the shared `v0.2/fixtures` (DID identities and signed attestations) once
-//! and caches them, so scenarios and benchmarks pay no per-use disk cost.
+//! Loads the shared `v0.2/fixtures` (DID identities, signed attestations,
+//! grants and the execution-model lattice) once
+//! and caches them, so scenarios and benchmarks pay no per-use disk cost. The
+//! identities' keys come from the encrypted fixture keystore.
 
-use crate::crypto::{b64_decode, Identity, Registry};
+use crate::crypto::{Identity, Registry};
+use crate::did::{DidWeb, Resolvers};
+use crate::execution_model::ModelLattice;
+use crate::grant::GrantRegistry;
+use crate::keystore::Keystore;
 use crate::sandboxed::{Policy, ScopeBindings};
-use crate::types::Attestation;
-use crate::PicResult;
+use crate::types::{Attestation, Grant};
+use crate::{PicError, PicResult};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -17,12 +23,15 @@ use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 /// The loaded, cached fixture cast: a key registry with every identity, the
-/// identities by actor name, the signed executor attestations by name, and the
-/// Execution Guardrail fixtures (policy and semantic-scope bindings).
+/// identities by actor name, the signed executor attestations by name, the
+/// signed grants, the execution-model lattice of the fixture deployment, and
+/// the Execution Guardrail fixtures (policy and semantic-scope bindings).
 pub struct Set {
     pub registry: Registry,
     pub identities: HashMap<String, Identity>,
     pub attestations: HashMap<String, Attestation>,
+    pub grants: GrantRegistry,
+    pub execution_models: ModelLattice,
     pub policy: Policy,
     pub scopes: ScopeBindings,
 }
@@ -43,55 +52,59 @@ impl Set {
             .cloned()
             .unwrap_or_else(|| panic!("fixtureset: unknown attestation {name}"))
     }
-}
 
-#[derive(Deserialize)]
-struct Jwk {
-    #[serde(default)]
-    kid: String,
-    #[serde(default)]
-    d: String,
+    /// Returns the loaded signed grant with the given id.
+    pub fn grant(&self, id: &str) -> &Grant {
+        self.grants
+            .get(id)
+            .unwrap_or_else(|| panic!("fixtureset: unknown grant {id}"))
+    }
 }
 
+/// The fixture keystore, relative to `v0.2/fixtures`.
+pub const KEYSTORE_FILE: &str = "keystore.json";
+
+/// The execution-model lattice, relative to `v0.2/fixtures`.
+pub const EXECUTION_MODELS_FILE: &str = "execution-models.json";
+
+/// The passphrase of the fixture keystore. Like the keys it protects, it is
+/// public and for demos and tests only.
+pub const FIXTURE_PASSPHRASE: &str = "PIC-v0.2-fixtures (throwaway, do not reuse)";
+
 /// Reads the fixtures once and returns the cached set on every later call.
 pub fn load() -> PicResult<&'static Set> {
     static CACHE: OnceLock<Result<Set, String>> = OnceLock::new();
     match CACHE.get_or_init(|| load_from(&fixtures_dir())) {
         Ok(s) => Ok(s),
-        Err(e) => Err(e.clone()),
+        Err(e) => Err(PicError::Fixture(e.clone())),
     }
 }
 
+/// A registry built from public material only: the identities' `did.json`
+/// documents, resolved as `did:web` on first use, plus `did:key` and
+/// `did:jwk`. This is what a verifier-only deployment holds; no
+/// `private.jwk` is read.
+pub fn public_registry() -> PicResult<Registry> {
+    let web = DidWeb::index(&fixtures_dir().join("identities")).map_err(PicError::Fixture)?;
+    Ok(Registry::with_resolver(Resolvers::new().with("web", web)))
+}
+
 fn load_from(dir: &Path) -> Result<Set, String> {
     let mut set = Set {
         registry: Registry::new(),
         identities: HashMap::new(),
         attestations: HashMap::new(),
+        grants: GrantRegistry::new(),
+        execution_models: ModelLattice::reference().clone(),
         policy: Policy::default(),
         scopes: ScopeBindings::new(),
     };
 
-    let id_root = dir.join("identities");
-    let entries = fs::read_dir(&id_root).map_err(|e| format!("read identities: {e}"))?;
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("read identities: {e}"))?;
-        if !entry.file_type().map_err(|e| e.to_string())?.is_dir() {
-            continue;
-        }
-        let name = entry.file_name().to_string_lossy().into_owned();
-        let path = id_root.join(&name).join("private.jwk");
-        let raw = fs::read(&path).map_err(|e| format!("{name}: {e}"))?;
-        let k: Jwk =
-            serde_json::from_slice(&raw).map_err(|e| format!("{name} private.jwk: {e}"))?;
-        let seed = b64_decode(&k.d).map_err(|e| format!("{name} seed: {e}"))?;
-        let did = match k.kid.split_once('#') {
-            Some((before, _)) => before.to_string(),
-            None => k.kid.clone(),
-        };
-        let id = Identity::load(&did, &k.kid, &seed).map_err(|e| format!("{name}: {e}"))?;
-        set.registry.add(&id);
-        set.identities.insert(name, id);
-    }
+    let keys = Keystore::open(dir.join(KEYSTORE_FILE), FIXTURE_PASSPHRASE)
+        .and_then(|k| k.load())
+        .map_err(|e| e.to_string())?;
+    set.registry = keys.registry;
+    set.identities = keys.identities;
 
     let att_root = dir.join("attestations");
     let entries = fs::read_dir(&att_root).map_err(|e| format!("read attestations: {e}"))?;
@@ -108,6 +121,24 @@ fn load_from(dir: &Path) -> Result<Set, String> {
         set.attestations.insert(key, att);
     }
 
+    let grant_root = dir.join("grants");
+    let entries = fs::read_dir(&grant_root).map_err(|e| format!("read grants: {e}"))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("read grants: {e}"))?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if !file_name.ends_with(".json") {
+            continue;
+        }
+        let raw = fs::read(entry.path()).map_err(|e| format!("{file_name}: {e}"))?;
+        let grant: Grant = serde_json::from_slice(&raw).map_err(|e| format!("{file_name}: {e}"))?;
+        set.grants.insert(grant);
+    }
+
+    let mraw = fs::read(dir.join(EXECUTION_MODELS_FILE))
+        .map_err(|e| format!("read execution models: {e}"))?;
+    set.execution_models =
+        serde_json::from_slice(&mraw).map_err(|e| format!("{EXECUTION_MODELS_FILE}: {e}"))?;
+
     // Execution Guardrail fixtures: the policy and the scope bindings.
     let praw = fs::read(dir.join("guardrail").join("policy.json"))
         .map_err(|e| format!("read guardrail policy: {e}"))?;
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Signed grants: the documents a PCA0's `grantId` refers to.
+//!
+//! A [`Grant`] names its grantor, the grantee principal, the maximal
+//! invariants it delegates, its validity and its semantic scopes. A PCA0
+//! under a grant must be issued by the grantee, within the grant's validity,
+//! with invariants that attenuate the grant's; the Prover refuses to mint
+//! anything else, and a Verifier holding a [`GrantRegistry`] checks the same
+//! plus the grantor's signature. Only the grantor may revoke a grant (see
+//! [`crate::RevocationStore::revoke_grant`]).
+
+use crate::authority::AuthorityProfile;
+use crate::error::GrantError;
+use crate::temporal::TemporalPolicy;
+use crate::types::{Grant, Invariants};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// The grants a Verifier, a guardrail or a revocation store trusts, keyed by
+/// id. Entries are still verified against their grantor's key on use.
+#[derive(Default)]
+pub struct GrantRegistry {
+    by_id: HashMap<String, Grant>,
+}
+
+impl GrantRegistry {
+    pub fn new() -> GrantRegistry {
+        GrantRegistry::default()
+    }
+
+    /// Adds a grant, replacing any earlier one with the same id.
+    pub fn insert(&mut self, grant: Grant) {
+        self.by_id.insert(grant.id.clone(), grant);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Grant> {
+        self.by_id.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Grant> {
+        self.by_id.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+impl FromIterator<Grant> for GrantRegistry {
+    fn from_iter<I: IntoIterator<Item = Grant>>(iter: I) -> GrantRegistry {
+        let mut grants = GrantRegistry::new();
+        for grant in iter {
+            grants.insert(grant);
+        }
+        grants
+    }
+}
+
+/// Checks what `grant` requires of a PCA0 `issuer` mints under it at `at`:
+/// the grant is signed, names `issuer` as grantee, is valid at `at`, and
+/// `inv` attenuates its invariants under `authority`. The grantor's signature
+/// itself is the Verifier's to check.
+pub(crate) fn admits(
+    grant: &Grant,
+    issuer: &str,
+    inv: &Invariants,
+    at: DateTime<Utc>,
+    temporal: &TemporalPolicy,
+    authority: &dyn AuthorityProfile,
+) -> Result<(), GrantError> {
+    if grant.proof.is_none() {
+        return Err(GrantError::MissingSignature);
+    }
+    if grant.grantee != issuer {
+        return Err(GrantError::GranteeMismatch {
+            grantee: grant.grantee.clone(),
+            issuer: issuer.to_string(),
+        });
+    }
+    temporal
+        .within(&grant.issued_at, &grant.expires_at, at)
+        .map_err(GrantError::Validity)?;
+    authority
+        .attenuates(inv, &grant.invariants)
+        .map_err(GrantError::NotAttenuated)
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! The JSON Canonicalization Scheme (RFC 8785): the byte representation every
+//! PIC signature and digest covers (spec §6.4).
+//!
+//! The serializer is explicit about the three rules that matter: object members
+//! sorted by the UTF-16 code units of their names (§3.2.3), strings escaped
+//! exactly as ECMAScript `JSON.stringify` does (§3.2.2.2), and numbers in the
+//! ECMAScript `Number.prototype.toString` form (§3.2.2.3). It does not depend on
+//! the map type or the number representation `serde_json` was built with, so
+//! `preserve_order` or `arbitrary_precision` being unified into the build cannot
+//! change signed bytes.
+//!
+//! RFC 8785 numbers are IEEE 754 doubles, so an integer beyond ±2^53 has no
+//! canonical form of its own: 2^53+1 canonicalizes as 2^53. A signature or
+//! digest over such a document would not pin the value, so PIC documents must
+//! not carry one (see [`exact_numbers`]); signing and untrusted decoding
+//! refuse them.
+
+use crate::error::CodecError;
+use serde_json::{Number, Value};
+
+/// The largest magnitude a number may have and still canonicalize exactly.
+pub const MAX_EXACT_INTEGER: u64 = 1 << 53;
+
+/// Returns the RFC 8785 canonical encoding of `v`.
+pub fn to_vec(v: &Value) -> Vec<u8> {
+    let mut out = String::new();
+    write_value(&mut out, v);
+    out.into_bytes()
+}
+
+/// Formats an IEEE 754 double as ECMAScript's `Number.prototype.toString`
+/// (RFC 8785 §3.2.2.3). Returns `None` for NaN and the infinities, which have
+/// no JSON representation.
+pub fn format_number(f: f64) -> Option<String> {
+    if !f.is_finite() {
+        return None;
+    }
+    if f == 0.0 {
+        return Some("0".to_string()); // also -0
+    }
+    // Rust's `{:e}` yields the shortest round-tripping digits; ECMAScript picks
+    // the same length but breaks an exact tie to even, and the layout differs.
+    let (digits, exp) = split_sci(&format!("{:e}", f.abs()));
+    let (digits, exp) = tie_to_even(f.abs(), digits, exp);
+    let k = digits.len() as i32;
+    let n = exp + 1;
+
+    let mut s = String::new();
+    if f < 0.0 {
+        s.push('-');
+    }
+    if k <= n && n <= 21 {
+        s.push_str(&digits);
+        s.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        s.push_str(&digits[..n as usize]);
+        s.push('.');
+        s.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        s.push_str("0.");
+        s.push_str(&"0".repeat((-n) as usize));
+        s.push_str(&digits);
+    } else {
+        s.push_str(&digits[..1]);
+        if k > 1 {
+            s.push('.');
+            s.push_str(&digits[1..]);
+        }
+        s.push('e');
+        s.push(if n >= 1 { '+' } else { '-' });
+        s.push_str(&(n - 1).abs().to_string());
+    }
+    Some(s)
+}
+
+/// Splits Rust's `{:e}` output into its significant digits and exponent.
+fn split_sci(sci: &str) -> (String, i32) {
+    let (mantissa, exp) = sci.split_once('e').expect("exponent");
+    let digits = mantissa.chars().filter(|c| *c != '.').collect();
+    (digits, exp.parse().expect("exponent"))
+}
+
+/// When `f` lies exactly halfway between two shortest candidates, returns the
+/// one with the even last digit (ECMAScript Number::toString, step 5); the
+/// digits are otherwise returned unchanged.
+fn tie_to_even(f: f64, digits: String, exp: i32) -> (String, i32) {
+    // 767 significant digits hold any double exactly.
+    let (exact, exact_exp) = split_sci(&format!("{:.767e}", f));
+    let exact = exact.trim_end_matches('0');
+    let k = digits.len();
+    if exact.len() != k + 1 || !exact.ends_with('5') {
+        return (digits, exp);
+    }
+    let low = &exact[..k];
+    if low.as_bytes()[k - 1] % 2 == 0 {
+        return (low.trim_end_matches('0').to_string(), exact_exp);
+    }
+    // Round the odd candidate up, carrying through trailing nines.
+    let mut up = low.as_bytes().to_vec();
+    let mut i = k;
+    while i > 0 && up[i - 1] == b'9' {
+        up[i - 1] = b'0';
+        i -= 1;
+    }
+    if i == 0 {
+        return ("1".to_string(), exact_exp + 1);
+    }
+    up[i - 1] += 1;
+    let up = String::from_utf8(up).expect("ascii digits");
+    (up.trim_end_matches('0').to_string(), exact_exp)
+}
+
+fn write_value(out: &mut String, v: &Value) {
+    match v {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&number(n)),
+        Value::String(s) => write_string(out, s),
+        Value::Array(a) => {
+            out.push('[');
+            for (i, item) in a.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(o) => {
+            let mut members: Vec<(&String, &Value)> = o.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (k, item)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, k);
+                out.push(':');
+                write_value(out, item);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Reports whether `n` is within ±[`MAX_EXACT_INTEGER`], where its canonical
+/// form is exact.
+pub fn is_exact(n: &Number) -> bool {
+    match (n.as_u64(), n.as_i64()) {
+        (Some(u), _) => u <= MAX_EXACT_INTEGER,
+        (None, Some(i)) => i.unsigned_abs() <= MAX_EXACT_INTEGER,
+        _ => n
+            .as_f64()
+            .is_some_and(|f| f.abs() <= MAX_EXACT_INTEGER as f64),
+    }
+}
+
+/// Checks that every number in `v` canonicalizes exactly (see [`is_exact`]).
+pub fn exact_numbers(v: &Value) -> Result<(), CodecError> {
+    match v {
+        Value::Number(n) if !is_exact(n) => Err(CodecError::InexactNumber {
+            value: n.to_string(),
+        }),
+        Value::Array(items) => items.iter().try_for_each(exact_numbers),
+        Value::Object(members) => members.values().try_for_each(exact_numbers),
+        _ => Ok(()),
+    }
+}
+
+/// Integers within the IEEE 754 exact range print as themselves; anything else
+/// goes through the double, as an ECMAScript parser would see it.
+fn number(n: &Number) -> String {
+    if let Some(u) = n.as_u64().filter(|u| *u <= MAX_EXACT_INTEGER) {
+        return u.to_string();
+    }
+    if let Some(i) = n.as_i64().filter(|i| i.unsigned_abs() <= MAX_EXACT_INTEGER) {
+        return i.to_string();
+    }
+    let f = n.as_f64().expect("json number as f64");
+    format_number(f).expect("json numbers are finite")
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < '\u{20}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,556 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! An encrypted on-disk keystore of [`Identity`] secrets, one JSON file.
+//!
+//! The keystore key is derived from a passphrase with Argon2id (parameters
+//! and salt stored in the file), and each key version's secret is sealed
+//! with XChaCha20-Poly1305 under a fresh nonce. The associated data binds
+//! every ciphertext to its name, identifier, verification method, suite,
+//! public key and validity window, so entries cannot be swapped, relabelled
+//! or have their windows moved without failing to decrypt. Public material
+//! stays in the clear, so listing a keystore needs no decryption; it is
+//! authenticated when [`Keystore::load`] decrypts every version.
+//!
+//! The derived key and every decrypted secret are held in zeroize-on-drop
+//! buffers; an [`Identity`]'s own key is wiped when it is dropped.
+//!
+//! An identity keeps its retired key versions, with the windows set by
+//! [`Keystore::rotate`], so the [`Registry`] of [`Keystore::load`] still
+//! verifies what they signed (see [`Registry::rotate`]).
+
+use crate::crypto::{b64_decode, b64_encode, Identity, KeyEntry, Registry};
+use crate::error::KeystoreError;
+use crate::suite::{PublicKey, SignatureSuite};
+use crate::{parse_rfc3339, rfc3339, PicError, PicResult};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// The keystore file format version. Version 2 binds each key version's
+/// window into its associated data.
+pub const KEYSTORE_VERSION: u32 = 2;
+
+const KDF_ALGORITHM: &str = "argon2id";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// The associated data of the passphrase check value.
+const CHECK_AAD: &[u8] = b"PIC-v0.2-keystore-check";
+
+fn err(e: KeystoreError) -> PicError {
+    PicError::Keystore(e)
+}
+
+fn corrupt(reason: impl Into<String>) -> PicError {
+    err(KeystoreError::Corrupt {
+        reason: reason.into(),
+    })
+}
+
+/// Argon2id cost parameters. The default is the Argon2 crate's (19 MiB,
+/// two passes, one lane), the OWASP minimum for Argon2id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> KdfParams {
+        KdfParams {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfSection {
+    alg: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct File {
+    version: u32,
+    kdf: KdfSection,
+    /// An empty plaintext sealed under the keystore key: the passphrase
+    /// check.
+    check: String,
+    /// The identities by name.
+    #[serde(default)]
+    identities: BTreeMap<String, Stored>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stored {
+    id: String,
+    /// The key versions, oldest first; the last is current.
+    versions: Vec<StoredKey>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredKey {
+    verification_method: String,
+    /// The proof type of the key's suite.
+    #[serde(rename = "type")]
+    type_: String,
+    /// The base64url raw public key.
+    public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_before: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_after: Option<String>,
+    /// The base64url nonce followed by the sealed raw secret.
+    secret: String,
+}
+
+/// The public view of a stored identity: its key versions, oldest first,
+/// with their windows. The last is the version [`Keystore::export`] returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredIdentity {
+    pub name: String,
+    pub id: String,
+    pub keys: Vec<KeyEntry>,
+}
+
+/// Everything a keystore holds, decrypted: a registry of every key version
+/// and the current identity by name.
+pub struct Loaded {
+    pub registry: Registry,
+    pub identities: HashMap<String, Identity>,
+}
+
+/// An open keystore. Changes are written to its file as they are made.
+pub struct Keystore {
+    path: PathBuf,
+    file: File,
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl Keystore {
+    /// Creates an empty keystore at `path` under `passphrase`, with the
+    /// default KDF parameters. Fails if `path` exists.
+    pub fn create(path: impl AsRef<Path>, passphrase: &str) -> PicResult<Keystore> {
+        Keystore::create_with(path, passphrase, KdfParams::default())
+    }
+
+    /// [`Keystore::create`] with explicit KDF parameters.
+    pub fn create_with(
+        path: impl AsRef<Path>,
+        passphrase: &str,
+        params: KdfParams,
+    ) -> PicResult<Keystore> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            return Err(io_error(&path, "already exists"));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        let kdf = KdfSection {
+            alg: KDF_ALGORITHM.to_string(),
+            memory_kib: params.memory_kib,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+            salt: b64_encode(&salt),
+        };
+        let key = derive(passphrase, &kdf)?;
+        let check = seal(&key, &[], CHECK_AAD)?;
+        let store = Keystore {
+            path,
+            file: File {
+                version: KEYSTORE_VERSION,
+                kdf,
+                check,
+                identities: BTreeMap::new(),
+            },
+            key,
+        };
+        store.save()?;
+        Ok(store)
+    }
+
+    /// Opens the keystore at `path`. A passphrase that does not open it is
+    /// [`KeystoreError::WrongPassphrase`].
+    pub fn open(path: impl AsRef<Path>, passphrase: &str) -> PicResult<Keystore> {
+        let path = path.as_ref().to_path_buf();
+        let raw = fs::read(&path).map_err(|e| io_error(&path, e))?;
+        let file: File = serde_json::from_slice(&raw).map_err(|e| corrupt(e.to_string()))?;
+        if file.version != KEYSTORE_VERSION {
+            return Err(corrupt(format!("unsupported version {}", file.version)));
+        }
+        let key = derive(passphrase, &file.kdf)?;
+        open_sealed(&key, &file.check, CHECK_AAD)
+            .map_err(|_| err(KeystoreError::WrongPassphrase))?;
+        Ok(Keystore { path, file, key })
+    }
+
+    /// Opens the keystore at `path`, creating it empty if it does not exist.
+    pub fn open_or_create(path: impl AsRef<Path>, passphrase: &str) -> PicResult<Keystore> {
+        match path.as_ref().exists() {
+            true => Keystore::open(path, passphrase),
+            false => Keystore::create(path, passphrase),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The stored identities, by name, without decrypting anything: the
+    /// public material as the file holds it, not yet authenticated.
+    pub fn list(&self) -> PicResult<Vec<StoredIdentity>> {
+        self.file
+            .identities
+            .iter()
+            .map(|(name, stored)| {
+                Ok(StoredIdentity {
+                    name: name.clone(),
+                    id: stored.id.clone(),
+                    keys: stored
+                        .versions
+                        .iter()
+                        .map(|k| k.entry(&stored.id))
+                        .collect::<PicResult<_>>()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Stores `identity` under `name` as a new identity. Importing the key
+    /// `name` already holds as its current version changes nothing; any
+    /// other key under an existing name is [`KeystoreError::Conflict`].
+    pub fn import(&mut self, name: &str, identity: &Identity) -> PicResult<()> {
+        if let Some(stored) = self.file.identities.get(name) {
+            let current = stored.versions.last();
+            if stored.id == identity.id
+                && current.is_some_and(|k| {
+                    k.verification_method == identity.verification_method
+                        && k.public_key == identity.encode_public()
+                })
+            {
+                return Ok(());
+            }
+            return Err(err(KeystoreError::Conflict {
+                name: name.to_string(),
+            }));
+        }
+        let version = self.seal_version(name, identity, None)?;
+        self.file.identities.insert(
+            name.to_string(),
+            Stored {
+                id: identity.id.clone(),
+                versions: vec![version],
+            },
+        );
+        self.save()
+    }
+
+    /// [`Keystore::import`] of a private JWK (`kty`, `crv`, `kid`, `d`), as
+    /// the plaintext `private.jwk` fixtures hold. A JWK without `crv` is an
+    /// Ed25519 key; `kid` is the verification method.
+    pub fn import_jwk(&mut self, name: &str, jwk: &[u8]) -> PicResult<()> {
+        let identity = identity_from_jwk(jwk)?;
+        self.import(name, &identity)
+    }
+
+    /// The current key of `name`, decrypted.
+    pub fn export(&self, name: &str) -> PicResult<Identity> {
+        let stored = self.stored(name)?;
+        let current = stored
+            .versions
+            .last()
+            .ok_or_else(|| corrupt(format!("{name:?} has no key")))?;
+        self.unseal_version(name, &stored.id, current)
+    }
+
+    /// The current key of `name` as a private JWK, for tools that take one.
+    pub fn export_jwk(&self, name: &str) -> PicResult<Zeroizing<String>> {
+        Ok(identity_to_jwk(&self.export(name)?))
+    }
+
+    /// Rotates `name` to a fresh key of the same suite as of `at`: the
+    /// current version stops being valid at `at` and the new one, the next
+    /// `#key-N`, starts there. Returns the new identity.
+    pub fn rotate(&mut self, name: &str, at: DateTime<Utc>) -> PicResult<Identity> {
+        let stored = self.stored(name)?.clone();
+        let current = stored
+            .versions
+            .last()
+            .ok_or_else(|| corrupt(format!("{name:?} has no key")))?;
+        let suite = current.suite()?;
+        let mut next = Identity::new_with(&stored.id, suite);
+        let mut n = stored.versions.len() + 1;
+        while stored
+            .versions
+            .iter()
+            .any(|k| k.verification_method == format!("{}#key-{n}", stored.id))
+        {
+            n += 1;
+        }
+        next.verification_method = format!("{}#key-{n}", stored.id);
+        let version = self.seal_version(name, &next, Some(at))?;
+
+        // Closing a window changes the version's associated data, so the
+        // secret is sealed again under it.
+        let mut versions = stored.versions.clone();
+        for k in &mut versions {
+            let closes = match k.not_after.as_deref().map(parse_rfc3339) {
+                Some(Ok(t)) => t > at,
+                Some(Err(e)) => return Err(corrupt(e.to_string())),
+                None => true,
+            };
+            if closes {
+                let identity = self.unseal_version(name, &stored.id, k)?;
+                k.not_after = Some(rfc3339(at));
+                k.secret = seal(&self.key, &identity.secret(), &k.aad(name, &stored.id))?;
+            }
+        }
+        versions.push(version);
+        self.file
+            .identities
+            .get_mut(name)
+            .expect("identity looked up above")
+            .versions = versions;
+        self.save()?;
+        Ok(next)
+    }
+
+    /// Decrypts every identity: a registry holding every key version with
+    /// its window, and the current identity by name. Every version, retired
+    /// ones included, is decrypted, so a public key or window altered in the
+    /// file is refused rather than registered. This is the loader
+    /// [`crate::fixtureset`] and the command-line tools use.
+    pub fn load(&self) -> PicResult<Loaded> {
+        let mut loaded = Loaded {
+            registry: Registry::new(),
+            identities: HashMap::new(),
+        };
+        for (name, stored) in &self.file.identities {
+            let mut current = None;
+            for version in &stored.versions {
+                let identity = self.unseal_version(name, &stored.id, version)?;
+                loaded.registry.add_key(version.entry(&stored.id)?);
+                current = Some(identity);
+            }
+            let identity = current.ok_or_else(|| corrupt(format!("{name:?} has no key")))?;
+            loaded.identities.insert(name.clone(), identity);
+        }
+        Ok(loaded)
+    }
+
+    fn stored(&self, name: &str) -> PicResult<&Stored> {
+        self.file.identities.get(name).ok_or_else(|| {
+            err(KeystoreError::UnknownIdentity {
+                name: name.to_string(),
+            })
+        })
+    }
+
+    fn seal_version(
+        &self,
+        name: &str,
+        identity: &Identity,
+        not_before: Option<DateTime<Utc>>,
+    ) -> PicResult<StoredKey> {
+        let mut version = StoredKey {
+            verification_method: identity.verification_method.clone(),
+            type_: identity.proof_type().to_string(),
+            public_key: identity.encode_public(),
+            not_before: not_before.map(rfc3339),
+            not_after: None,
+            secret: String::new(),
+        };
+        version.secret = seal(
+            &self.key,
+            &identity.secret(),
+            &version.aad(name, &identity.id),
+        )?;
+        Ok(version)
+    }
+
+    fn unseal_version(&self, name: &str, id: &str, version: &StoredKey) -> PicResult<Identity> {
+        let secret =
+            open_sealed(&self.key, &version.secret, &version.aad(name, id)).map_err(|_| {
+                corrupt(format!(
+                    "{name:?}: {} does not decrypt",
+                    version.verification_method
+                ))
+            })?;
+        let identity =
+            Identity::load_with(version.suite()?, id, &version.verification_method, &secret)?;
+        if identity.encode_public() != version.public_key {
+            return Err(corrupt(format!(
+                "{name:?}: {} does not match its public key",
+                version.verification_method
+            )));
+        }
+        Ok(identity)
+    }
+
+    /// Writes the keystore through a temporary file, so a crash leaves either
+    /// the old or the new file.
+    fn save(&self) -> PicResult<()> {
+        let mut raw = serde_json::to_vec_pretty(&self.file).expect("keystore to_vec");
+        raw.push(b'\n');
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, raw).map_err(|e| io_error(&tmp, e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| io_error(&self.path, e))
+    }
+}
+
+impl StoredKey {
+    fn suite(&self) -> PicResult<SignatureSuite> {
+        SignatureSuite::from_proof_type(&self.type_).map_err(|e| corrupt(e.to_string()))
+    }
+
+    /// The associated data sealing this version's secret; an open bound of
+    /// the window is empty.
+    fn aad(&self, name: &str, id: &str) -> Vec<u8> {
+        serde_json::to_vec(&[
+            name,
+            id,
+            &self.verification_method,
+            &self.type_,
+            &self.public_key,
+            self.not_before.as_deref().unwrap_or_default(),
+            self.not_after.as_deref().unwrap_or_default(),
+        ])
+        .expect("aad to_vec")
+    }
+
+    fn entry(&self, id: &str) -> PicResult<KeyEntry> {
+        let time = |t: &Option<String>| {
+            t.as_deref()
+                .map(parse_rfc3339)
+                .transpose()
+                .map_err(|e| corrupt(e.to_string()))
+        };
+        let key = b64_decode(&self.public_key)
+            .ok()
+            .and_then(|raw| PublicKey::from_bytes(self.suite().ok()?, &raw))
+            .ok_or_else(|| {
+                corrupt(format!(
+                    "invalid public key of {}",
+                    self.verification_method
+                ))
+            })?;
+        Ok(KeyEntry {
+            controller: id.to_string(),
+            verification_method: self.verification_method.clone(),
+            key,
+            not_before: time(&self.not_before)?,
+            not_after: time(&self.not_after)?,
+            revoked_at: None,
+        })
+    }
+}
+
+/// The private JWK of `identity`, the inverse of [`identity_from_jwk`]: the
+/// one way a secret leaves the crate.
+pub fn identity_to_jwk(identity: &Identity) -> Zeroizing<String> {
+    let mut public = crate::did::public_key_jwk(&identity.public_key());
+    public["kid"] = identity.verification_method.clone().into();
+    let public = public.to_string();
+    let d = Zeroizing::new(b64_encode(&identity.secret()));
+    // Built in place so the secret is never copied into a buffer that is not
+    // wiped.
+    let mut jwk = Zeroizing::new(String::with_capacity(public.len() + d.len() + 8));
+    jwk.push_str(&public[..public.len() - 1]);
+    jwk.push_str(",\"d\":\"");
+    jwk.push_str(&d);
+    jwk.push_str("\"}");
+    jwk
+}
+
+/// Builds an identity from a private JWK (see [`Keystore::import_jwk`]).
+pub fn identity_from_jwk(jwk: &[u8]) -> PicResult<Identity> {
+    #[derive(Deserialize)]
+    struct Jwk {
+        #[serde(default)]
+        kid: String,
+        #[serde(default)]
+        crv: String,
+        #[serde(default)]
+        d: String,
+    }
+    let k: Jwk = serde_json::from_slice(jwk).map_err(|e| PicError::Key(format!("jwk: {e}")))?;
+    let d = Zeroizing::new(k.d);
+    let secret =
+        Zeroizing::new(b64_decode(&d).map_err(|e| PicError::Key(format!("jwk secret: {e}")))?);
+    let did = match k.kid.split_once('#') {
+        Some((before, _)) => before,
+        None => k.kid.as_str(),
+    };
+    // A JWK without "crv" is the profile's default Ed25519 key.
+    let suite = match k.crv.as_str() {
+        "" => SignatureSuite::Ed25519,
+        crv => SignatureSuite::from_jwk_curve(crv)
+            .ok_or_else(|| PicError::Key(format!("unsupported curve {crv:?}")))?,
+    };
+    Identity::load_with(suite, did, &k.kid, &secret)
+}
+
+fn io_error(path: &Path, e: impl ToString) -> PicError {
+    err(KeystoreError::Io {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+fn derive(passphrase: &str, kdf: &KdfSection) -> PicResult<Zeroizing<[u8; 32]>> {
+    if kdf.alg != KDF_ALGORITHM {
+        return Err(corrupt(format!("unsupported KDF {:?}", kdf.alg)));
+    }
+    let salt = b64_decode(&kdf.salt).map_err(|e| corrupt(format!("salt: {e}")))?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| corrupt(format!("KDF parameters: {e}")))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| corrupt(format!("KDF: {e}")))?;
+    Ok(key)
+}
+
+/// Seals `msg` under `key` with a fresh nonce: base64url(nonce ‖ ciphertext).
+fn seal(key: &[u8; 32], msg: &[u8], aad: &[u8]) -> PicResult<String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+    let ct = XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(&nonce), Payload { msg, aad })
+        .map_err(|_| corrupt("encryption failed"))?;
+    let mut out = nonce.to_vec();
+    out.extend(ct);
+    Ok(b64_encode(&out))
+}
+
+/// Opens what [`seal`] produced; any failure is reported the same way.
+fn open_sealed(key: &[u8; 32], sealed: &str, aad: &[u8]) -> Result<Zeroizing<Vec<u8>>, ()> {
+    let raw = b64_decode(sealed).map_err(|_| ())?;
+    if raw.len() < NONCE_LEN {
+        return Err(());
+    }
+    let (nonce, ct) = raw.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ct, aad })
+        .map(Zeroizing::new)
+        .map_err(|_| ())
+}
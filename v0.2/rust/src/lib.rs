@@ -13,30 +13,52 @@
 
 use chrono::{DateTime, SecondsFormat, Utc};
 
+pub mod attestation;
 pub mod authority;
+pub mod codec;
 pub mod crypto;
+pub mod did;
+pub mod diff;
+pub mod error;
+pub mod execution_model;
 pub mod fixtureset;
+pub mod grant;
+pub mod jcs;
+pub mod keystore;
+pub mod operation;
 pub mod prover;
+pub mod requestbinding;
 pub mod sandboxed;
 pub mod revocation;
 pub mod scenario;
+pub mod signer;
 pub mod snapshot;
+pub mod sources;
+pub mod suite;
+pub mod temporal;
 pub mod types;
+pub mod untrusted;
+pub mod vectors;
 pub mod verifier;
 
-/// The prototype's error type: a message string, mirroring Go's `error` values.
-pub type PicResult<T> = Result<T, String>;
+/// The result type of every fallible PIC operation; see [`PicError`].
+pub type PicResult<T> = Result<T, PicError>;
 
 // Suite identifiers used by this illustrative profile (Prover/Verifier spec §6.4).
+/// The proof type of the default (Ed25519) suite; see [`SignatureSuite`].
 pub const SIGNATURE_TYPE: &str = "Ed25519Signature2020";
 pub const DIGEST_PREFIX: &str = "sha256:";
 pub const LINEAGE_DOMAIN_SEP: &str = "PIC-Lineage-v0";
 pub const BRANCH_ROOT_DOMAIN: &str = "PIC-Root-Branch-v0";
-/// Branch derivation domain (unused at runtime in this prototype; kept for parity).
+/// Branch derivation domain (see [`derive_branch_id`]).
 pub const BRANCH_DOMAIN: &str = "PIC-Branch-v0";
+/// Request binding domains (see [`ExecutedRequest`]).
+pub const REQUEST_DOMAIN: &str = "PIC-Request-v0";
+pub const PAYLOAD_DOMAIN: &str = "PIC-Payload-v0";
 
 // Profile identifiers used by this prototype.
 pub const REVOCABLE_PROFILE: &str = "PIC-Revocable-v0";
+pub const REFERENCE_AUTHORITY_PROFILE: &str = "PIC-Authority-Reference-v0";
 pub const POR_TYPE: &str = "PIC-PoR-v0";
 pub const REVOCATION_TYPE: &str = "PIC-Revocation-v0";
 
@@ -51,28 +73,63 @@ pub fn rfc3339(dt: DateTime<Utc>) -> String {
     dt.to_rfc3339_opts(SecondsFormat::Nanos, true)
 }
 
-/// Parses an RFC3339 string into a UTC instant for temporal comparisons.
-pub fn parse_rfc3339(s: &str) -> DateTime<Utc> {
+/// Parses an RFC3339 string into a UTC instant for temporal comparisons. Time
+/// fields arrive in untrusted documents, so a malformed value is an error.
+pub fn parse_rfc3339(s: &str) -> Result<DateTime<Utc>, TimeError> {
     DateTime::parse_from_rfc3339(s)
-        .unwrap_or_else(|e| panic!("invalid rfc3339 time {s:?}: {e}"))
-        .with_timezone(&Utc)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| TimeError {
+            value: s.to_string(),
+            reason: e.to_string(),
+        })
 }
 
 // Re-exports so callers can use `pic::MintPCA0`-style flat paths (like the Go
 // `pic.` package qualifier).
-pub use authority::{attenuates, authorize, conforms};
-pub use crypto::{canonical_json, digest_of, hash_parts, random_b64, Identity, Registry};
+pub use attestation::AttestationCache;
+pub use authority::{
+    attenuates, authorize, conforms, AuthorityProfile, ReferenceProfile, Violation,
+};
+pub use error::{
+    AcceptanceError, AttestationError, BindingError, Checkpoint, CodecError, ContinuationError,
+    CoordinateError, EnvelopeError, GrantError, IntegrityError, KeystoreError, LatticeError, Limit,
+    OriginError, PicError, RequestBindingError, ResolveError, SignatureError, SignerError,
+    SnapshotError, TemporalBound, TemporalError, TimeError, ValidityError,
+};
+pub use codec::{Document, Encoding};
+pub use crypto::{canonical_json, digest_of, hash_parts, random_b64, Identity, KeyEntry, Registry};
+pub use did::{DidJwk, DidKey, DidResolver, DidWeb, Resolvers};
+pub use diff::{diff, ContractChange, ContractDiff, InvariantDiff, OperationChange, OperationDiff};
+pub use execution_model::ModelLattice;
+pub use grant::GrantRegistry;
+pub use keystore::{KdfParams, Keystore};
+pub use operation::Operation;
 pub use sandboxed::{
     accept_guarded_crossing, multi_lineage_digest, scopes_of, CarriedLineage, CrossingContext,
     EnforcementTrace, Freshness, Guardrail, LocalPdp, MultiLineage, MultiLineageExecution,
     Participant, Pdp, PdpDecision, PdpParticipant, PdpRequest, Policy, SandboxedExecution,
     ScopeBindings, TraceParticipant, ENFORCE_OPERATION, MULTI_LINEAGE_PROFILE,
 };
-pub use prover::{mint_pca0, sign_attestation, wrap_envelope, Prover};
-pub use revocation::{derive_lineage_id, root_branch_id, RevocationStore};
-pub use snapshot::issue_snapshot;
+pub use prover::{
+    mint_pca0, mint_pca0_async, mint_pca0_with, sign_attestation, sign_attestation_async,
+    sign_grant, sign_revocation, wrap_envelope, wrap_envelope_async, wrap_envelope_with,
+    OriginOptions, Prover,
+};
+pub use requestbinding::ExecutedRequest;
+pub use revocation::{
+    derive_branch_id, derive_lineage_id, root_branch_id, RevocationStore, Revoked,
+};
+#[cfg(unix)]
+pub use signer::RemoteSigner;
+pub use signer::{SignFuture, Signer};
+pub use snapshot::{issue_snapshot, issue_snapshot_async};
+pub use sources::{Clock, Entropy, FixedClock, OsEntropy, SeededEntropy, SystemClock};
+pub use suite::{PublicKey, SignatureSuite};
+pub use temporal::TemporalPolicy;
 pub use types::{
-    Attestation, Continuation, ContinuationResponse, ContractAttributes, Envelope, EnvelopeBody,
-    ExecutionContract, Invariants, Pca, Por, Proof, Request, Revocation, Snapshot,
+    Attestation, Audience, Constraint, Continuation, ContinuationMode, ContinuationResponse,
+    ContractAttributes, Envelope, EnvelopeBody, ExecutionContract, Grant, Invariants, Pca, Por,
+    Proof, Quantity, Request, Revocation, Snapshot, TemporalProfile, TimeOfDay,
 };
+pub use untrusted::{from_untrusted_bytes, Limits};
 pub use verifier::Verifier;
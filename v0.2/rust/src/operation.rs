@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! The reference operation grammar and its attenuation lattice.
+//!
+//! An operation is a verb, optionally followed by `:` and a resource path:
+//!
+//! ```text
+//! operation = verb [ ":" path ]
+//! path      = segment *( "/" segment )
+//! segment   = "**" | glob
+//! ```
+//!
+//! The verb is everything before the first `:`. The path is split on `/`, so
+//! a leading `/` is an empty first segment and `/user/*` and `user/*` name
+//! different resources. A segment is exact unless it contains `*`: as a whole
+//! segment `**` matches zero or more segments, and any other `*` matches a
+//! run of characters within one segment (so `/user/*.csv` matches
+//! `/user/a.csv` but not `/user/docs/a.csv`, which `/user/**/*.csv` matches).
+//!
+//! A path whose last segment ends in `*` (but is not `**`) stays open: the
+//! segment also matches any further segments, so a trailing `*` is a prefix
+//! match as it always was (and as in the Go implementation). `/user/*`
+//! matches `/user/a.txt` and `/user/docs/a.txt`, and `/user/a*` matches
+//! `/user/abc/d.txt`; `/user/**` is `/user/*` that also matches `/user`
+//! itself. The verb is a single segment: in `*:/user/a` the `*` matches any
+//! verb. An operation without a path (`read-all`) is a bare verb and matches
+//! only that bare verb, unless it ends in `*`: then it is a prefix of the
+//! whole operation, path included, as in Go. `*` matches every operation, and
+//! `read*` matches `read`, `readme` and `read:/user/a`.
+//!
+//! # The lattice
+//!
+//! Each pattern denotes the set of concrete operations it matches, and
+//! `a ⊑ b` ([`Operation::covers`], read "b covers a") when every operation `a`
+//! matches, `b` matches too. This is a preorder: distinct patterns may denote
+//! the same set (`/a/**/**` and `/a/**`), and attenuation only cares about
+//! the order. A set of operations `S` attenuates `T` when every member of `S`
+//! is covered by some single member of `T`; the empty set is the bottom and
+//! `*` (any operation) is the top. So `read:/user/docs/*` attenuates
+//! `read:/user/*`, `read:/user/*/report.csv` attenuates `read:/user/**`, and
+//! `read:/user/*` attenuates `read:/user/**` but not the reverse.
+//!
+//! The check is conservative: it decides coverage pattern by pattern, so it
+//! never accepts an expansion, but it rejects a member covered only by the
+//! union of several (`read:/a/**` is not covered by `{read:/a, read:/a/*/**}`).
+//! Authorizing a concrete request ([`crate::authorize`]) is coverage of the
+//! request, read literally, by one granted pattern.
+
+use std::fmt;
+
+/// One segment of a parsed operation pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Matches exactly this segment.
+    Exact(String),
+    /// Matches one segment; each `*` matches any run of characters in it.
+    Glob(String),
+    /// `**`: matches zero or more whole segments.
+    Any,
+    /// A path's last segment ending in `*`: matches one segment like
+    /// [`Segment::Glob`], then any further segments. As the verb of a bare
+    /// verb, it matches the whole operation by prefix.
+    Prefix(String),
+}
+
+impl Segment {
+    fn parse(s: &str) -> Segment {
+        match s {
+            "**" => Segment::Any,
+            _ if s.contains('*') => Segment::Glob(s.to_string()),
+            _ => Segment::Exact(s.to_string()),
+        }
+    }
+
+    fn verb(s: &str) -> Segment {
+        match Segment::parse(s) {
+            Segment::Any => Segment::Glob(s.to_string()),
+            seg => seg,
+        }
+    }
+
+    /// Reports whether every segment `other` matches, this one matches too.
+    /// [`Segment::Any`] and [`Segment::Prefix`] span segments, so they are
+    /// the path's to place.
+    fn covers(&self, other: &Segment) -> bool {
+        match (self, other) {
+            (Segment::Any | Segment::Prefix(_), _) | (_, Segment::Any | Segment::Prefix(_)) => {
+                false
+            }
+            (Segment::Exact(a), Segment::Exact(b)) => a == b,
+            (Segment::Exact(_), Segment::Glob(_)) => false,
+            (Segment::Glob(g), Segment::Exact(s)) => glob(g, s, false),
+            (Segment::Glob(g), Segment::Glob(h)) => glob(g, h, true),
+        }
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Exact(s) | Segment::Glob(s) | Segment::Prefix(s) => f.write_str(s),
+            Segment::Any => f.write_str("**"),
+        }
+    }
+}
+
+/// A parsed operation pattern: a verb and, unless it is a bare verb, a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub verb: Segment,
+    pub path: Option<Vec<Segment>>,
+}
+
+impl Operation {
+    /// Parses an operation pattern. Every string parses: the grammar has no
+    /// invalid forms, only exact segments where no `*` appears.
+    pub fn parse(s: &str) -> Operation {
+        match s.split_once(':') {
+            Some((verb, path)) => Operation {
+                verb: Segment::verb(verb),
+                path: Some(open_tail(path.split('/').map(Segment::parse).collect())),
+            },
+            None if s.ends_with('*') => Operation {
+                verb: Segment::Prefix(s.to_string()),
+                path: None,
+            },
+            None => Operation {
+                verb: Segment::verb(s),
+                path: None,
+            },
+        }
+    }
+
+    /// The concrete operation of a request, read literally: a `*` in the
+    /// operation or target is an ordinary character. An empty target is a
+    /// bare verb.
+    pub fn concrete(operation: &str, target: &str) -> Operation {
+        Operation {
+            verb: Segment::Exact(operation.to_string()),
+            path: (!target.is_empty()).then(|| {
+                target
+                    .split('/')
+                    .map(|s| Segment::Exact(s.to_string()))
+                    .collect()
+            }),
+        }
+    }
+
+    /// Reports whether this pattern covers `other`: every concrete operation
+    /// `other` matches, this one matches too.
+    pub fn covers(&self, other: &Operation) -> bool {
+        if let (Segment::Prefix(g), None) = (&self.verb, &self.path) {
+            return glob(g, &other.to_string(), true);
+        }
+        if !self.verb.covers(&other.verb) {
+            return false;
+        }
+        match (&self.path, &other.path) {
+            (None, None) => true,
+            (Some(p), Some(q)) => covers_path(p, q),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.verb)?;
+        if let Some(path) = &self.path {
+            f.write_str(":")?;
+            for (i, seg) in path.iter().enumerate() {
+                if i > 0 {
+                    f.write_str("/")?;
+                }
+                write!(f, "{seg}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports whether the pattern `granted` covers the pattern `op`.
+pub fn covers(granted: &str, op: &str) -> bool {
+    Operation::parse(granted).covers(&Operation::parse(op))
+}
+
+/// Marks a last segment ending in `*` as [`Segment::Prefix`].
+fn open_tail(mut path: Vec<Segment>) -> Vec<Segment> {
+    match path.pop() {
+        Some(Segment::Glob(g)) if g.ends_with('*') => path.push(Segment::Prefix(g)),
+        Some(seg) => path.push(seg),
+        None => {}
+    }
+    path
+}
+
+/// A path with each [`Segment::Prefix`] spelled as the glob followed by `**`.
+fn closed(path: &[Segment]) -> Vec<Segment> {
+    let mut out = Vec::with_capacity(path.len() + 1);
+    for seg in path {
+        match seg {
+            Segment::Prefix(g) => out.extend([Segment::Glob(g.clone()), Segment::Any]),
+            seg => out.push(seg.clone()),
+        }
+    }
+    out
+}
+
+/// Path coverage: `p[i..]` covers `q[j..]`, where a `**` in `p` absorbs any
+/// run of `q`'s segments (its `**` included) and a `**` in `q` is absorbed
+/// only by one in `p`.
+fn covers_path(p: &[Segment], q: &[Segment]) -> bool {
+    let (p, q) = (closed(p), closed(q));
+    // dp[j]: p[i..] covers q[j..], computed for i from the end.
+    let mut dp = vec![false; q.len() + 1];
+    dp[q.len()] = true;
+    for seg in p.iter().rev() {
+        let mut next = vec![false; q.len() + 1];
+        for j in (0..=q.len()).rev() {
+            next[j] = match seg {
+                Segment::Any => dp[j] || (j < q.len() && next[j + 1]),
+                _ => j < q.len() && seg.covers(&q[j]) && dp[j + 1],
+            };
+        }
+        dp = next;
+    }
+    dp[0]
+}
+
+/// Matches the glob `pattern` against `s`. With `s_is_glob`, `s` is itself a
+/// glob and its `*` can be absorbed only by a `*` of `pattern`, so a match
+/// means every string `s` matches, `pattern` matches too.
+fn glob(pattern: &str, s: &str, s_is_glob: bool) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = s.chars().collect();
+    let (mut i, mut j) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while j < s.len() {
+        if i < p.len() && p[i] == '*' {
+            star = Some((i, j));
+            i += 1;
+        } else if i < p.len() && p[i] == s[j] && !(s_is_glob && s[j] == '*') {
+            i += 1;
+            j += 1;
+        } else if let Some((si, sj)) = star {
+            i = si + 1;
+            j = sj + 1;
+            star = Some((si, sj + 1));
+        } else {
+            return false;
+        }
+    }
+    p[i..].iter().all(|&c| c == '*')
+}
@@ -4,76 +4,187 @@
 // Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
 
 //! Minting and signing: origin PCA0, successor PCAs (with the Prover self-check),
-//! signed attestations, and handoff envelopes.
+//! signed attestations, grants and grant revocations, and handoff envelopes.
 
-use crate::authority::{attenuates, conforms};
-use crate::crypto::{random_b64, Identity};
-use crate::revocation::{derive_lineage_id, root_branch_id};
+use crate::authority::{AuthorityProfile, ReferenceProfile};
+use crate::error::{OriginError, TemporalError};
+use crate::grant;
+use crate::revocation::{derive_branch_id, derive_lineage_id, root_branch_id};
+use crate::signer::{proof, proof_async, Signer};
+use crate::sources::{random_b64_from, Entropy, OsEntropy};
+use crate::temporal::TemporalPolicy;
 use crate::types::{
-    Attestation, Continuation, ContinuationResponse, Envelope, EnvelopeBody, Invariants, Pca, Por,
-    Proof, Request,
+    Attestation, Audience, Continuation, ContinuationMode, ContinuationResponse, Envelope,
+    EnvelopeBody, Grant, Invariants, Pca, Por, Request, Revocation, TemporalProfile,
 };
-use crate::{parse_rfc3339, rfc3339, PicResult, POR_TYPE, REVOCABLE_PROFILE, SIGNATURE_TYPE};
-use chrono::{DateTime, Duration, Utc};
-
-// Durations used by the illustrative profile (§6.1, §6.3).
-fn lineage_ttl() -> Duration {
-    Duration::hours(24)
-}
-fn hop_ttl() -> Duration {
-    Duration::minutes(5)
-}
-fn challenge_ttl() -> Duration {
-    Duration::minutes(5)
-}
+use crate::{
+    parse_rfc3339, rfc3339, PicError, PicResult, POR_TYPE, REFERENCE_AUTHORITY_PROFILE,
+    REVOCABLE_PROFILE,
+};
+use chrono::{DateTime, Utc};
 
 /// Computes the single outer signature over the PCA (proof excluded) and attaches
 /// it (§2.5).
-fn sign_pca(p: &mut Pca, signer: &Identity) {
-    let msg = p.signing_bytes();
-    p.proof = Some(Proof {
-        type_: SIGNATURE_TYPE.to_string(),
-        verification_method: signer.verification_method.clone(),
-        signature: signer.sign(&msg),
-    });
+fn sign_pca(p: &mut Pca, signer: &dyn Signer) -> PicResult<()> {
+    p.proof = Some(proof(signer, &p, &p.signing_bytes())?);
+    Ok(())
+}
+
+/// [`sign_pca`] through [`Signer::sign_async`].
+async fn sign_pca_async(p: &mut Pca, signer: &dyn Signer) -> PicResult<()> {
+    p.proof = Some(proof_async(signer, &p, &p.signing_bytes()).await?);
+    Ok(())
 }
 
 /// Returns a copy of `att` signed by its issuer (the conformance evidence of
 /// §1.6). The issuer key must be registered so a Verifier can check it.
-pub fn sign_attestation(mut att: Attestation, issuer: &Identity) -> Attestation {
-    att.issuer = issuer.id.clone();
-    let msg = att.signing_bytes();
-    att.proof = Some(Proof {
-        type_: SIGNATURE_TYPE.to_string(),
-        verification_method: issuer.verification_method.clone(),
-        signature: issuer.sign(&msg),
-    });
-    att
+pub fn sign_attestation(mut att: Attestation, issuer: &dyn Signer) -> PicResult<Attestation> {
+    att.issuer = issuer.id().to_string();
+    att.proof = Some(proof(issuer, &att, &att.signing_bytes())?);
+    Ok(att)
+}
+
+/// [`sign_attestation`] through [`Signer::sign_async`].
+pub async fn sign_attestation_async(
+    mut att: Attestation,
+    issuer: &dyn Signer,
+) -> PicResult<Attestation> {
+    att.issuer = issuer.id().to_string();
+    att.proof = Some(proof_async(issuer, &att, &att.signing_bytes()).await?);
+    Ok(att)
+}
+
+/// Returns a copy of `grant` signed by its grantor, who may later revoke it.
+/// The grantor key must be registered so a Verifier can check it.
+pub fn sign_grant(mut grant: Grant, grantor: &dyn Signer) -> PicResult<Grant> {
+    grant.grantor = grantor.id().to_string();
+    grant.proof = Some(proof(grantor, &grant, &grant.signing_bytes())?);
+    Ok(grant)
+}
+
+/// Returns a copy of `r` signed by its issuer. A GRANT revocation must be
+/// signed by the grantor (see [`crate::RevocationStore::revoke_grant`]).
+pub fn sign_revocation(mut r: Revocation, issuer: &dyn Signer) -> PicResult<Revocation> {
+    r.issuer = issuer.id().to_string();
+    r.proof = Some(proof(issuer, &r, &r.signing_bytes())?);
+    Ok(r)
+}
+
+/// The parts of an origin PCA beyond its issuer, invariants and grant. The
+/// defaults are [`mint_pca0`]'s: OS entropy, a single-use challenge open to
+/// any conforming successor, no explicit temporal profile (the default one
+/// applies), no local temporal ceilings, and the reference authority profile.
+#[derive(Clone)]
+pub struct OriginOptions<'a> {
+    /// Source of the origin nonce and challenge.
+    pub entropy: &'a dyn Entropy,
+    /// Mode of the emitted challenge.
+    pub mode: ContinuationMode,
+    /// The lineage's signed temporal profile.
+    pub temporal_profile: Option<TemporalProfile>,
+    /// The minter's temporal policy: its ceilings clamp the lineage and
+    /// challenge lifetimes, so a Verifier under the same policy accepts the
+    /// PCA0, and its skew applies to the grant's validity.
+    pub temporal: TemporalPolicy,
+    /// The successors the emitted challenge admits.
+    pub audience: Option<Audience>,
+    /// The authority profile the lineage is minted under, and its grant
+    /// checked under.
+    pub authority: &'a dyn AuthorityProfile,
+}
+
+impl Default for OriginOptions<'_> {
+    fn default() -> Self {
+        OriginOptions {
+            entropy: &OsEntropy,
+            mode: ContinuationMode::SingleUse,
+            temporal_profile: None,
+            temporal: TemporalPolicy::default(),
+            audience: None,
+            authority: &ReferenceProfile::DEFAULT,
+        }
+    }
 }
 
 /// Creates and signs an origin PCA for `issuer` (§1.8), stamped with the
 /// revocation coordinates of the revocable profile: a fresh originNonce, the
 /// derived lineageId, lineageCounter 0, the root branchId, and originIssuer =
-/// issuer. `grant_id` may be empty.
-pub fn mint_pca0(issuer: &Identity, inv: Invariants, grant_id: &str, now: DateTime<Utc>) -> Pca {
-    let nonce = random_b64(32);
-    let challenge = random_b64(32);
+/// issuer. Under a `grant`, the PCA0 carries its id and must be one the grant
+/// admits: `issuer` is the grantee, `now` is within the grant's validity, and
+/// `inv` attenuates the grant's invariants.
+pub fn mint_pca0(
+    issuer: &dyn Signer,
+    inv: Invariants,
+    grant: Option<&Grant>,
+    now: DateTime<Utc>,
+) -> PicResult<Pca> {
+    mint_pca0_with(issuer, inv, grant, now, &OriginOptions::default())
+}
+
+/// [`mint_pca0`] with the given [`OriginOptions`]. The lineage lives, and its
+/// challenge stays open, as long as its temporal profile allows.
+pub fn mint_pca0_with(
+    issuer: &dyn Signer,
+    inv: Invariants,
+    grant: Option<&Grant>,
+    now: DateTime<Utc>,
+    opts: &OriginOptions,
+) -> PicResult<Pca> {
+    let mut p = origin(issuer.id(), inv, grant, now, opts)?;
+    sign_pca(&mut p, issuer)?;
+    Ok(p)
+}
+
+/// [`mint_pca0_with`] through [`Signer::sign_async`]: the same options mint
+/// the same PCA0.
+pub async fn mint_pca0_async(
+    issuer: &dyn Signer,
+    inv: Invariants,
+    grant: Option<&Grant>,
+    now: DateTime<Utc>,
+    opts: &OriginOptions<'_>,
+) -> PicResult<Pca> {
+    let mut p = origin(issuer.id(), inv, grant, now, opts)?;
+    sign_pca_async(&mut p, issuer).await?;
+    Ok(p)
+}
+
+/// The unsigned origin PCA of [`mint_pca0_with`]: the nonce is drawn before
+/// the challenge.
+fn origin(
+    issuer: &str,
+    inv: Invariants,
+    grant: Option<&Grant>,
+    now: DateTime<Utc>,
+    opts: &OriginOptions,
+) -> PicResult<Pca> {
+    if let Some(grant) = grant {
+        grant::admits(grant, issuer, &inv, now, &opts.temporal, opts.authority)
+            .map_err(|e| PicError::Origin(OriginError::Grant(e)))?;
+    }
+    let nonce = random_b64_from(opts.entropy, 32);
+    let challenge = random_b64_from(opts.entropy, 32);
+    let profile = opts.temporal_profile.unwrap_or_default();
+    let expires = opts.temporal.lineage_expiry(now, &profile);
+    let challenge_expires = opts.temporal.challenge_expiry(now, &profile, expires);
     let mut p = Pca {
         profile: REVOCABLE_PROFILE.to_string(),
         lineage_counter: 0,
-        grant_id: grant_id.to_string(),
-        origin_issuer: issuer.id.clone(),
-        issuer: issuer.id.clone(),
+        grant_id: grant.map(|g| g.id.clone()).unwrap_or_default(),
+        origin_issuer: issuer.to_string(),
+        issuer: issuer.to_string(),
         origin_nonce: nonce,
+        authority_profile: recorded(opts.authority),
         invariants: inv,
-        continuation: Continuation {
+        continuation: emitted(
+            opts.mode,
             challenge,
-            mode: "single-use".to_string(),
-            max_uses: 1,
-            expires_at: rfc3339(now + challenge_ttl()),
-        },
+            challenge_expires,
+            opts.audience.clone(),
+        )?,
+        temporal_profile: opts.temporal_profile,
         issued_at: rfc3339(now),
-        expires_at: rfc3339(now + lineage_ttl()),
+        expires_at: rfc3339(expires),
         ..Default::default()
     };
     // Derive the lineage identity from the non-self-referential origin core, then
@@ -81,24 +192,114 @@ pub fn mint_pca0(issuer: &Identity, inv: Invariants, grant_id: &str, now: DateTi
     let lineage_id = derive_lineage_id(&p);
     p.branch_id = root_branch_id(&lineage_id);
     p.lineage_id = lineage_id;
-    sign_pca(&mut p, issuer);
-    p
+    Ok(p)
+}
+
+/// The `authorityProfile` a PCA0 minted under `authority` records: none for
+/// the reference profile, so reference lineages keep their identity.
+fn recorded(authority: &dyn AuthorityProfile) -> String {
+    match authority.id() {
+        REFERENCE_AUTHORITY_PROFILE => String::new(),
+        id => id.to_string(),
+    }
+}
+
+/// The continuation a new PCA emits: a fresh challenge in `mode`, which must
+/// be one a Verifier accepts, open to `audience`.
+fn emitted(
+    mode: ContinuationMode,
+    challenge: String,
+    expires: DateTime<Utc>,
+    audience: Option<Audience>,
+) -> PicResult<Continuation> {
+    let mut c = mode.continuation(challenge, rfc3339(expires));
+    c.audience = audience;
+    ContinuationMode::of(&c).map_err(|error| PicError::Continuation { hop: None, error })?;
+    Ok(c)
 }
 
 /// Constructs successor PCAs for one executor identity and its attestation.
+/// With `by_reference`, the PoR carries only the attestation's digest (see
+/// [`crate::attestation`]). Executor nonces and challenges come from
+/// `entropy`, the operating system's unless set; expiries are clamped to the
+/// hop window of `temporal`, which should match the Verifiers' policy. The
+/// challenges it emits are in `mode`, single-use unless set. Successors carry
+/// the predecessor's temporal profile, tightened to `temporal_profile` if set.
+/// When the executor knows its next hop, `audience` restricts who may answer
+/// the challenges it emits. Its self-check runs under `authority`, the
+/// reference profile unless set.
 pub struct Prover<'a> {
-    pub executor: &'a Identity,
+    pub executor: &'a dyn Signer,
     pub attestation: Attestation,
+    pub by_reference: bool,
+    pub entropy: &'a dyn Entropy,
+    pub temporal: TemporalPolicy,
+    pub mode: ContinuationMode,
+    pub temporal_profile: Option<TemporalProfile>,
+    pub audience: Option<Audience>,
+    pub authority: &'a dyn AuthorityProfile,
 }
 
 impl<'a> Prover<'a> {
-    pub fn new(executor: &'a Identity, attestation: Attestation) -> Prover<'a> {
+    pub fn new(executor: &'a dyn Signer, attestation: Attestation) -> Prover<'a> {
         Prover {
             executor,
             attestation,
+            by_reference: false,
+            entropy: &OsEntropy,
+            temporal: TemporalPolicy::default(),
+            mode: ContinuationMode::SingleUse,
+            temporal_profile: None,
+            audience: None,
+            authority: &ReferenceProfile::DEFAULT,
         }
     }
 
+    /// Admits only `audience` as successors of the PCAs it builds.
+    pub fn with_audience(mut self, audience: Audience) -> Prover<'a> {
+        self.audience = Some(audience);
+        self
+    }
+
+    /// Tightens the temporal profile of the successors it builds to at most
+    /// `profile`; a looser bound of `profile` leaves the lineage's in place.
+    pub fn with_temporal_profile(mut self, profile: TemporalProfile) -> Prover<'a> {
+        self.temporal_profile = Some(profile);
+        self
+    }
+
+    /// Emits challenges in `mode`, e.g. [`ContinuationMode::MultiUse`] for a
+    /// fan-out to several successors.
+    pub fn with_mode(mut self, mode: ContinuationMode) -> Prover<'a> {
+        self.mode = mode;
+        self
+    }
+
+    /// Clamps this Prover's expiries to `temporal`.
+    pub fn with_temporal(mut self, temporal: TemporalPolicy) -> Prover<'a> {
+        self.temporal = temporal;
+        self
+    }
+
+    /// Draws this Prover's nonces and challenges from `entropy`.
+    pub fn with_entropy(mut self, entropy: &'a dyn Entropy) -> Prover<'a> {
+        self.entropy = entropy;
+        self
+    }
+
+    /// Self-checks the successors it builds under `authority`, which should be
+    /// the profile the lineage was minted under.
+    pub fn with_authority(mut self, authority: &'a dyn AuthorityProfile) -> Prover<'a> {
+        self.authority = authority;
+        self
+    }
+
+    /// Switches this Prover to the attestation reference form.
+    pub fn by_reference(mut self) -> Prover<'a> {
+        self.by_reference = true;
+        self
+    }
+
     /// Builds and signs a successor PCA that continues `pred` with the given
     /// (attenuated) invariants and request binding (§2.1–§2.5). Performs the
     /// Prover self-check of §2.3/§2.4: a Prover MUST NOT emit an expansive
@@ -110,7 +311,52 @@ impl<'a> Prover<'a> {
         req: Request,
         now: DateTime<Utc>,
     ) -> PicResult<Pca> {
-        self.build(pred, inv, req, None, now, true)
+        let mut p = self.build(pred, inv, req, None, now, true)?;
+        sign_pca(&mut p, self.executor)?;
+        Ok(p)
+    }
+
+    /// [`Prover::continue_`] through [`Signer::sign_async`].
+    pub async fn continue_async(
+        &self,
+        pred: &Pca,
+        inv: Invariants,
+        req: Request,
+        now: DateTime<Utc>,
+    ) -> PicResult<Pca> {
+        let mut p = self.build(pred, inv, req, None, now, true)?;
+        sign_pca_async(&mut p, self.executor).await?;
+        Ok(p)
+    }
+
+    /// [`Prover::continue_`] opening a child branch of `pred`'s branch
+    /// (Revocation spec §2.4): the PoR carries a fresh `branchNonce` and the
+    /// successor's `branchId` is derived from it (see [`derive_branch_id`]).
+    /// Everything downstream stays on the child branch, so a BRANCH-SUFFIX
+    /// revocation of it strikes this sub-workflow and not its siblings.
+    pub fn branch(
+        &self,
+        pred: &Pca,
+        inv: Invariants,
+        req: Request,
+        now: DateTime<Utc>,
+    ) -> PicResult<Pca> {
+        let mut p = self.branched(pred, inv, req, now)?;
+        sign_pca(&mut p, self.executor)?;
+        Ok(p)
+    }
+
+    /// [`Prover::branch`] through [`Signer::sign_async`].
+    pub async fn branch_async(
+        &self,
+        pred: &Pca,
+        inv: Invariants,
+        req: Request,
+        now: DateTime<Utc>,
+    ) -> PicResult<Pca> {
+        let mut p = self.branched(pred, inv, req, now)?;
+        sign_pca_async(&mut p, self.executor).await?;
+        Ok(p)
     }
 
     /// Builds the next ordinary outer PCA of a Sandboxed Execution (PIC Sandboxed
@@ -125,7 +371,23 @@ impl<'a> Prover<'a> {
         ml: crate::sandboxed::MultiLineage,
         now: DateTime<Utc>,
     ) -> PicResult<Pca> {
-        self.build(pred, inv, req, Some(ml), now, true)
+        let mut p = self.build(pred, inv, req, Some(ml), now, true)?;
+        sign_pca(&mut p, self.executor)?;
+        Ok(p)
+    }
+
+    /// [`Prover::continue_enforce`] through [`Signer::sign_async`].
+    pub async fn continue_enforce_async(
+        &self,
+        pred: &Pca,
+        inv: Invariants,
+        req: Request,
+        ml: crate::sandboxed::MultiLineage,
+        now: DateTime<Utc>,
+    ) -> PicResult<Pca> {
+        let mut p = self.build(pred, inv, req, Some(ml), now, true)?;
+        sign_pca_async(&mut p, self.executor).await?;
+        Ok(p)
     }
 
     /// Builds a successor PCA *without* the Prover self-check, simulating a buggy
@@ -138,9 +400,30 @@ impl<'a> Prover<'a> {
         req: Request,
         now: DateTime<Utc>,
     ) -> PicResult<Pca> {
-        self.build(pred, inv, req, None, now, false)
+        let mut p = self.build(pred, inv, req, None, now, false)?;
+        sign_pca(&mut p, self.executor)?;
+        Ok(p)
+    }
+
+    /// The unsigned successor PCA of [`Prover::branch`], on a fresh child
+    /// branch.
+    fn branched(
+        &self,
+        pred: &Pca,
+        inv: Invariants,
+        req: Request,
+        now: DateTime<Utc>,
+    ) -> PicResult<Pca> {
+        let mut p = self.build(pred, inv, req, None, now, true)?;
+        if let Some(por) = p.proof_of_relationship.as_mut() {
+            por.branch_nonce = random_b64_from(self.entropy, 32);
+            p.branch_id =
+                derive_branch_id(&pred.branch_id, &por.previous_pca_hash, &por.branch_nonce);
+        }
+        Ok(p)
     }
 
+    /// The unsigned successor PCA.
     #[allow(clippy::too_many_arguments)]
     fn build(
         &self,
@@ -152,37 +435,50 @@ impl<'a> Prover<'a> {
         enforce: bool,
     ) -> PicResult<Pca> {
         if enforce {
-            attenuates(&inv, &pred.invariants)
-                .map_err(|e| format!("prover self-check failed: {e}"))?;
-            conforms(
-                &self.attestation.attributes,
-                &pred.invariants.execution_contract,
-            )
-            .map_err(|e| format!("prover self-check failed: {e}"))?;
+            self.authority
+                .attenuates(&inv, &pred.invariants)
+                .map_err(PicError::SelfCheck)?;
+            self.authority
+                .conforms(
+                    &self.attestation.attributes,
+                    &pred.invariants.execution_contract,
+                )
+                .map_err(PicError::SelfCheck)?;
         }
+        let lineage_counter = crate::verifier::successor(pred)
+            .map_err(|error| PicError::Coordinates { hop: None, error })?;
         let pred_digest = pred.digest();
-        let nonce = random_b64(32);
-        let challenge = random_b64(32);
+        let nonce = random_b64_from(self.entropy, 32);
+        let challenge = random_b64_from(self.entropy, 32);
 
         // Per-hop expiry is the tighter of the hop window and the lineage bound.
-        let pred_expires = parse_rfc3339(&pred.expires_at);
-        let mut expires = now + hop_ttl();
-        if expires > pred_expires {
-            expires = pred_expires;
-        }
-        let mut challenge_expiry = now + challenge_ttl();
-        if challenge_expiry > pred_expires {
-            challenge_expiry = pred_expires;
-        }
+        let pred_expires = parse_rfc3339(&pred.expires_at).map_err(|e| PicError::Temporal {
+            hop: None,
+            error: TemporalError::MalformedTime(e),
+        })?;
+        let temporal_profile = match self.temporal_profile {
+            Some(tighter) => Some(TemporalProfile::of(pred).meet(&tighter)),
+            None => pred.temporal_profile,
+        };
+        let profile = temporal_profile.unwrap_or_default();
+        let expires = self.temporal.hop_expiry(now, &profile, pred_expires);
+        let challenge_expiry = self.temporal.challenge_expiry(now, &profile, pred_expires);
 
-        let mut p = Pca {
+        let (executor_attestation, executor_attestation_digest) = if self.by_reference {
+            (None, self.attestation.digest())
+        } else {
+            (Some(self.attestation.clone()), String::new())
+        };
+
+        let p = Pca {
             // Revocation coordinates propagated unchanged, counter incremented.
             profile: pred.profile.clone(),
             lineage_id: pred.lineage_id.clone(),
-            lineage_counter: pred.lineage_counter + 1,
+            lineage_counter,
             branch_id: pred.branch_id.clone(),
             grant_id: pred.grant_id.clone(),
             origin_issuer: pred.origin_issuer.clone(),
+            authority_profile: pred.authority_profile.clone(),
             proof_of_relationship: Some(Por {
                 type_: POR_TYPE.to_string(),
                 previous_pca_hash: pred_digest,
@@ -190,23 +486,25 @@ impl<'a> Prover<'a> {
                     predecessor_challenge: pred.continuation.challenge.clone(),
                     executor_nonce: nonce,
                 },
-                executor: self.executor.id.clone(),
+                executor: self.executor.id().to_string(),
                 request: req,
-                executor_attestation: self.attestation.clone(),
+                executor_attestation,
+                executor_attestation_digest,
+                branch_nonce: String::new(),
             }),
             invariants: inv,
-            continuation: Continuation {
+            continuation: emitted(
+                self.mode,
                 challenge,
-                mode: "single-use".to_string(),
-                max_uses: 1,
-                expires_at: rfc3339(challenge_expiry),
-            },
+                challenge_expiry,
+                self.audience.clone(),
+            )?,
+            temporal_profile,
             multi_lineage: ml,
             issued_at: rfc3339(now),
             expires_at: rfc3339(expires),
             ..Default::default()
         };
-        sign_pca(&mut p, self.executor);
         Ok(p)
     }
 }
@@ -214,24 +512,46 @@ impl<'a> Prover<'a> {
 /// Produces the signed handoff envelope carrying `pred` and `current` together
 /// (§2.5). The forwarder signs the envelope; the two PCAs keep their own
 /// signatures.
-pub fn wrap_envelope(forwarder: &Identity, pred: &Pca, current: &Pca) -> Envelope {
-    let pred_digest = pred.digest();
-    let cur_digest = current.digest();
-    let mut env = Envelope {
+pub fn wrap_envelope(forwarder: &dyn Signer, pred: &Pca, current: &Pca) -> PicResult<Envelope> {
+    wrap_envelope_with(forwarder, pred, current, &[])
+}
+
+/// [`wrap_envelope`] with a side table of the attestations `current`
+/// references by digest.
+pub fn wrap_envelope_with(
+    forwarder: &dyn Signer,
+    pred: &Pca,
+    current: &Pca,
+    attestations: &[Attestation],
+) -> PicResult<Envelope> {
+    let mut env = envelope(forwarder.id(), pred, current, attestations);
+    env.proof = Some(proof(forwarder, &env, &env.signing_bytes())?);
+    Ok(env)
+}
+
+/// [`wrap_envelope_with`] through [`Signer::sign_async`].
+pub async fn wrap_envelope_async(
+    forwarder: &dyn Signer,
+    pred: &Pca,
+    current: &Pca,
+    attestations: &[Attestation],
+) -> PicResult<Envelope> {
+    let mut env = envelope(forwarder.id(), pred, current, attestations);
+    env.proof = Some(proof_async(forwarder, &env, &env.signing_bytes()).await?);
+    Ok(env)
+}
+
+/// The unsigned envelope of [`wrap_envelope_with`].
+fn envelope(forwarder: &str, pred: &Pca, current: &Pca, attestations: &[Attestation]) -> Envelope {
+    Envelope {
         envelope: EnvelopeBody {
-            forwarded_by: forwarder.id.clone(),
+            forwarded_by: forwarder.to_string(),
             predecessor: Some(pred.clone()),
-            predecessor_digest: pred_digest,
+            predecessor_digest: pred.digest(),
             current: Some(current.clone()),
-            current_digest: cur_digest,
+            current_digest: current.digest(),
+            attestations: attestations.to_vec(),
         },
         proof: None,
-    };
-    let msg = env.signing_bytes();
-    env.proof = Some(Proof {
-        type_: SIGNATURE_TYPE.to_string(),
-        verification_method: forwarder.verification_method.clone(),
-        signature: forwarder.sign(&msg),
-    });
-    env
+    }
 }
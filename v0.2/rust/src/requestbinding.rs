@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Request binding (§2.3, §3.3 check 8): the digests that pin a signed
+//! [`Request`] to the concrete request an executor sends, and the receiver's
+//! executed-vs-signed check.
+//!
+//! - `requestDigest = H("PIC-Request-v0" || 0x00 || canonical({operation,
+//!   target, params[, quantities]}))`, where `params` holds only the
+//!   parameters the sender chose to bind and `quantities`, present only when
+//!   some are declared, the quantities checked against the lineage's
+//!   constraints;
+//! - `payloadDigest = H("PIC-Payload-v0" || 0x00 || body)`, over the body bytes
+//!   exactly as sent.
+//!
+//! The Prover side calls [`ExecutedRequest::request`] for the `Request` it signs;
+//! the receiver rebuilds an [`ExecutedRequest`] from what arrived and calls
+//! [`ExecutedRequest::check`] against the tip's signed request.
+
+use crate::crypto::{canonical_json, hash_parts};
+use crate::error::RequestBindingError;
+use crate::types::{Quantity, Request};
+use crate::{PAYLOAD_DOMAIN, REQUEST_DOMAIN};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// A concrete request as executed: sent by a Prover, or received by the next
+/// service.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutedRequest {
+    pub operation: String,
+    pub target: String,
+    /// The bound parameters, by name.
+    pub params: BTreeMap<String, String>,
+    /// The declared quantities, by name.
+    pub quantities: BTreeMap<String, Quantity>,
+    pub body: Vec<u8>,
+}
+
+impl ExecutedRequest {
+    pub fn new(operation: &str, target: &str) -> ExecutedRequest {
+        ExecutedRequest {
+            operation: operation.to_string(),
+            target: target.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Binds the parameter `name`; parameters not bound here are not covered.
+    pub fn with_param(mut self, name: &str, value: &str) -> ExecutedRequest {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Declares the quantity `name`, bound like a parameter.
+    pub fn with_quantity(mut self, name: &str, q: Quantity) -> ExecutedRequest {
+        self.quantities.insert(name.to_string(), q);
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> ExecutedRequest {
+        self.body = body.into();
+        self
+    }
+
+    pub fn request_digest(&self) -> String {
+        let mut bound = json!({
+            "operation": self.operation,
+            "target": self.target,
+            "params": self.params,
+        });
+        if !self.quantities.is_empty() {
+            bound["quantities"] = json!(self.quantities);
+        }
+        let canonical = canonical_json(&bound);
+        hash_parts(&[REQUEST_DOMAIN.as_bytes(), &[0], &canonical])
+    }
+
+    pub fn payload_digest(&self) -> String {
+        hash_parts(&[PAYLOAD_DOMAIN.as_bytes(), &[0], &self.body])
+    }
+
+    /// The request binding a Prover signs for this request in
+    /// `security_domain`, with both digests filled.
+    pub fn request(&self, security_domain: &str) -> Request {
+        Request {
+            operation: self.operation.clone(),
+            target: self.target.clone(),
+            security_domain: security_domain.to_string(),
+            request_digest: self.request_digest(),
+            payload_digest: self.payload_digest(),
+            quantities: self.quantities.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Checks executed-vs-signed: `signed` must name this operation and
+    /// target, declare these quantities and carry both digests, and each must
+    /// equal the one recomputed from this request.
+    pub fn check(&self, signed: &Request) -> Result<(), RequestBindingError> {
+        let pairs = [
+            ("operation", &signed.operation, self.operation.clone()),
+            ("target", &signed.target, self.target.clone()),
+            (
+                "requestDigest",
+                &signed.request_digest,
+                self.request_digest(),
+            ),
+            (
+                "payloadDigest",
+                &signed.payload_digest,
+                self.payload_digest(),
+            ),
+        ];
+        for (field, signed, received) in pairs {
+            if signed.is_empty() {
+                return Err(RequestBindingError::Unbound { field });
+            }
+            if *signed != received {
+                return Err(RequestBindingError::Mismatch {
+                    field,
+                    signed: signed.clone(),
+                    received,
+                });
+            }
+        }
+        if signed.quantities != self.quantities {
+            return Err(RequestBindingError::Mismatch {
+                field: "quantities",
+                signed: json!(signed.quantities).to_string(),
+                received: json!(self.quantities).to_string(),
+            });
+        }
+        Ok(())
+    }
+}
@@ -3,15 +3,20 @@
 // Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
 // Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
 
-//! The origin-commitment derivation of `lineageId` and the root `branchId`, and
-//! the native causal cutoffs (LINEAGE-SUFFIX, BRANCH-SUFFIX, GRANT).
+//! The origin-commitment derivation of `lineageId`, the root and child
+//! `branchId`s, and the native causal cutoffs (LINEAGE-SUFFIX, BRANCH-SUFFIX,
+//! GRANT).
 
-use crate::crypto::hash_parts;
+use crate::crypto::{hash_parts, Registry};
+use crate::error::GrantError;
+use crate::grant::GrantRegistry;
 use crate::types::{OriginCore, Pca, Revocation};
 use crate::{
-    PicResult, BRANCH_ROOT_DOMAIN, LINEAGE_DOMAIN_SEP, STRATEGY_BRANCH_SUFFIX, STRATEGY_GRANT,
+    BRANCH_DOMAIN, BRANCH_ROOT_DOMAIN, LINEAGE_DOMAIN_SEP, STRATEGY_BRANCH_SUFFIX, STRATEGY_GRANT,
     STRATEGY_LINEAGE_SUFFIX,
 };
+use chrono::{DateTime, Utc};
+use std::fmt;
 
 /// Computes `lineageId = H("PIC-Lineage-v0" || 0x00 || canonical(originCore))`
 /// from a PCA0 (Revocation spec §2.1). Non-self-referential: it excludes the
@@ -23,6 +28,8 @@ pub fn derive_lineage_id(p: &Pca) -> String {
         origin_nonce: &p.origin_nonce,
         grant_id: &p.grant_id,
         invariants: &p.invariants,
+        temporal_profile: p.temporal_profile.as_ref(),
+        authority_profile: &p.authority_profile,
         issued_at: &p.issued_at,
         expires_at: &p.expires_at,
     };
@@ -36,7 +43,38 @@ pub fn root_branch_id(lineage_id: &str) -> String {
     hash_parts(&[BRANCH_ROOT_DOMAIN.as_bytes(), &[0u8], lineage_id.as_bytes()])
 }
 
+/// `branchId = H("PIC-Branch-v0" || 0x00 || parentBranchId || 0x00 ||
+/// previousPcaHash || 0x00 || branchNonce)`: the child branch a successor of
+/// `previous_pca_hash` opens under `parent_branch_id` (Revocation spec §2.4).
+/// Binding the predecessor digest ties the branch to one fork point; the
+/// nonce keeps sibling branches from the same fork point apart.
+pub fn derive_branch_id(
+    parent_branch_id: &str,
+    previous_pca_hash: &str,
+    branch_nonce: &str,
+) -> String {
+    hash_parts(&[
+        BRANCH_DOMAIN.as_bytes(),
+        &[0u8],
+        parent_branch_id.as_bytes(),
+        &[0u8],
+        previous_pca_hash.as_bytes(),
+        &[0u8],
+        branch_nonce.as_bytes(),
+    ])
+}
+
 impl Revocation {
+    /// An unsigned GRANT(grantId) cutoff, to be signed by the grantor with
+    /// [`crate::sign_revocation`].
+    pub fn grant(grant_id: &str) -> Revocation {
+        Revocation {
+            strategy: STRATEGY_GRANT.to_string(),
+            grant_id: grant_id.to_string(),
+            ..Default::default()
+        }
+    }
+
     /// Reports whether this revocation strikes the given PCA (§3.1).
     fn matches(&self, p: &Pca) -> bool {
         match self.strategy.as_str() {
@@ -55,7 +93,8 @@ impl Revocation {
 }
 
 /// An append-only, monotonic set of active revocations (Revocation spec §5.3,
-/// §5.4). In this prototype it is an in-memory list.
+/// §5.4). In this prototype it is an in-memory list. GRANT revocations enter
+/// only through [`RevocationStore::revoke_grant`].
 #[derive(Default)]
 pub struct RevocationStore {
     entries: Vec<Revocation>,
@@ -69,7 +108,7 @@ impl RevocationStore {
     }
 
     /// Appends a revocation (append-only: revocations only accumulate).
-    pub fn add(&mut self, r: Revocation) {
+    fn add(&mut self, r: Revocation) {
         self.entries.push(r);
     }
 
@@ -84,26 +123,99 @@ impl RevocationStore {
         });
     }
 
-    /// Returns an error if any active revocation strikes the PCA; `Ok(())`
+    /// Appends a BRANCH-SUFFIX(lineageId, branchId, fromCounter) cutoff: one
+    /// branch from `from_counter` on, leaving its siblings and the parent
+    /// branch untouched. Branches opened from a struck position fail with it,
+    /// since their chains pass through that position.
+    pub fn branch_suffix(
+        &mut self,
+        lineage_id: &str,
+        branch_id: &str,
+        from_counter: u64,
+        issuer: &str,
+    ) {
+        self.add(Revocation {
+            strategy: STRATEGY_BRANCH_SUFFIX.to_string(),
+            lineage_id: lineage_id.to_string(),
+            branch_id: branch_id.to_string(),
+            from_counter,
+            issuer: issuer.to_string(),
+            ..Default::default()
+        });
+    }
+
+    /// Appends a GRANT revocation, which strikes every lineage minted under
+    /// the grant. Only the grantor may revoke: `r` must name a grant in
+    /// `grants`, be issued by its grantor, and carry the grantor's signature
+    /// (checked through `reg` at `now`).
+    pub fn revoke_grant(
+        &mut self,
+        r: Revocation,
+        grants: &GrantRegistry,
+        reg: &Registry,
+        now: DateTime<Utc>,
+    ) -> Result<(), GrantError> {
+        if r.strategy != STRATEGY_GRANT {
+            return Err(GrantError::NotGrantRevocation {
+                strategy: r.strategy,
+            });
+        }
+        let grant = grants.get(&r.grant_id).ok_or_else(|| GrantError::Unknown {
+            grant_id: r.grant_id.clone(),
+        })?;
+        if r.issuer != grant.grantor {
+            return Err(GrantError::NotGrantor {
+                grant_id: grant.id.clone(),
+                grantor: grant.grantor.clone(),
+                issuer: r.issuer,
+            });
+        }
+        let proof = r.proof.as_ref().ok_or(GrantError::MissingSignature)?;
+        reg.verify_at(&grant.grantor, &r.signing_bytes(), proof, now)
+            .map_err(GrantError::Signature)?;
+        self.add(r);
+        Ok(())
+    }
+
+    /// Returns the first active revocation that strikes the PCA; `Ok(())`
     /// otherwise. The lookup is O(1) in lineage length.
-    pub fn check(&self, p: &Pca) -> PicResult<()> {
+    pub fn check(&self, p: &Pca) -> Result<(), Revoked> {
         for r in &self.entries {
             if r.matches(p) {
-                return Err(format!(
-                    "revoked by {}(lineage={}, branch={}, grant={}, fromCounter={}) at counter {}",
-                    r.strategy,
-                    short(&r.lineage_id),
-                    short(&r.branch_id),
-                    r.grant_id,
-                    r.from_counter,
-                    p.lineage_counter
-                ));
+                return Err(Revoked {
+                    revocation: Box::new(r.clone()),
+                    counter: p.lineage_counter,
+                });
             }
         }
         Ok(())
     }
 }
 
+/// A PCA struck by an active revocation: the matching cutoff and the position
+/// it struck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revoked {
+    pub revocation: Box<Revocation>,
+    pub counter: u64,
+}
+
+impl fmt::Display for Revoked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = &self.revocation;
+        write!(
+            f,
+            "revoked by {}(lineage={}, branch={}, grant={}, fromCounter={}) at counter {}",
+            r.strategy,
+            short(&r.lineage_id),
+            short(&r.branch_id),
+            r.grant_id,
+            r.from_counter,
+            self.counter
+        )
+    }
+}
+
 /// Truncates a digest for readable messages (matching Go's `short`).
 fn short(s: &str) -> String {
     if s.len() <= 14 {
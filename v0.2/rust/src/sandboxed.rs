@@ -12,11 +12,19 @@
 //! Faithful mirror of the Go `pic/sandboxed.go`; JSON shapes are identical.
 //! Non-normative.
 
+use crate::authority::{AuthorityProfile, ReferenceProfile};
 use crate::crypto::{canonical_json, hash_parts, Registry};
-use crate::prover::{mint_pca0, Prover};
-use crate::types::{Attestation, ExecutionContract, Invariants, Pca, Request};
+use crate::prover::{mint_pca0_with, OriginOptions, Prover};
+use crate::types::{
+    Attestation, ExecutionContract, Invariants, Pca, Request,
+};
 use crate::verifier::Verifier;
-use crate::{digest_of, random_b64, rfc3339, Identity, PicResult, RevocationStore};
+use crate::error::AcceptanceError;
+use crate::grant::GrantRegistry;
+use crate::signer::Signer;
+use crate::sources::{random_b64_from, Entropy, OsEntropy};
+use crate::temporal::TemporalPolicy;
+use crate::{digest_of, rfc3339, PicError, PicResult, RevocationStore};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -134,7 +142,8 @@ fn request_set_digest(m: &MultiLineageExecution) -> String {
 
 /// Binds semantic scopes to a carried lineage through its origin grantId (or
 /// origin issuer DID as a governance fallback). Not under the unilateral control
-/// of the evaluated executor. A scope adds no authority.
+/// of the evaluated executor. A scope adds no authority. A guardrail holding
+/// grants prefers the scopes its grantor signed into the grant.
 pub type ScopeBindings = HashMap<String, Vec<String>>;
 
 /// Resolves the semantic scopes of one carried lineage.
@@ -242,7 +251,7 @@ impl Pdp for LocalPdp {
 }
 
 /// Parses `participants.all(l, 'a' in l.scopes || 'b' in l.scopes)`.
-fn parse_all_scopes_condition(when: &str) -> PicResult<Vec<String>> {
+fn parse_all_scopes_condition(when: &str) -> Result<Vec<String>, String> {
     let s = when.trim();
     let prefix = "participants.all(l,";
     let Some(rest) = s.strip_prefix(prefix) else {
@@ -284,8 +293,18 @@ pub struct SandboxedExecution {
 impl SandboxedExecution {
     /// Mints PCA0-G: the ordinary origin of the outer ENFORCE lineage, signed by
     /// the authorized sandbox origin. Carries no PoR and no verdict.
-    pub fn originate(origin: &Identity, now: DateTime<Utc>) -> SandboxedExecution {
-        let pca0 = mint_pca0(
+    pub fn originate(origin: &dyn Signer, now: DateTime<Utc>) -> PicResult<SandboxedExecution> {
+        SandboxedExecution::originate_with(origin, now, &OsEntropy)
+    }
+
+    /// [`SandboxedExecution::originate`] drawing the origin nonce and
+    /// challenge from `entropy`.
+    pub fn originate_with(
+        origin: &dyn Signer,
+        now: DateTime<Utc>,
+        entropy: &dyn Entropy,
+    ) -> PicResult<SandboxedExecution> {
+        let pca0 = mint_pca0_with(
             origin,
             Invariants {
                 operations: vec![ENFORCE_OPERATION.to_string()],
@@ -293,11 +312,16 @@ impl SandboxedExecution {
                     role: "guardrail".to_string(),
                     ..Default::default()
                 },
+                ..Default::default()
             },
-            "",
+            None,
             now,
-        );
-        SandboxedExecution { chain: vec![pca0] }
+            &OriginOptions {
+                entropy,
+                ..Default::default()
+            },
+        )?;
+        Ok(SandboxedExecution { chain: vec![pca0] })
     }
 
     /// PCA0-G.
@@ -317,15 +341,23 @@ impl SandboxedExecution {
 
 /// An ordinary executor of a Sandboxed Execution: it verifies the outer
 /// continuation, verifies every carried lineage, applies the enforcement
-/// function, and — on permit — proves the next ordinary outer PCA.
+/// function, and — on permit — proves the next ordinary outer PCA. The
+/// crossing nonce and the outer PCA's nonce and challenge come from
+/// `entropy`, the operating system's unless set; times are judged and clamped
+/// under `temporal`. Carried lineages are verified under `authority`; the
+/// outer ENFORCE lineage is always the reference profile's.
 pub struct Guardrail<'a> {
-    pub identity: &'a Identity,
+    pub identity: &'a dyn Signer,
     pub attestation: Attestation,
     pub registry: &'a Registry,
     pub revocations: Option<&'a RevocationStore>,
     pub pdp: &'a dyn Pdp,
     pub policy: Policy,
     pub scopes: &'a ScopeBindings,
+    pub grants: Option<&'a GrantRegistry>,
+    pub entropy: &'a dyn Entropy,
+    pub temporal: TemporalPolicy,
+    pub authority: &'a dyn AuthorityProfile,
 }
 
 /// One carried lineage as seen by the guardrail.
@@ -374,7 +406,7 @@ pub struct EnforcementTrace {
 
 impl<'a> Guardrail<'a> {
     pub fn new(
-        identity: &'a Identity,
+        identity: &'a dyn Signer,
         attestation: Attestation,
         registry: &'a Registry,
         pdp: &'a dyn Pdp,
@@ -389,6 +421,45 @@ impl<'a> Guardrail<'a> {
             pdp,
             policy,
             scopes,
+            grants: None,
+            entropy: &OsEntropy,
+            temporal: TemporalPolicy::default(),
+            authority: &ReferenceProfile::DEFAULT,
+        }
+    }
+
+    /// Checks carried origins against `grants` and reads their scopes from
+    /// the signed grants.
+    pub fn with_grants(mut self, grants: &'a GrantRegistry) -> Guardrail<'a> {
+        self.grants = Some(grants);
+        self
+    }
+
+    /// Judges and clamps times under `temporal`.
+    pub fn with_temporal(mut self, temporal: TemporalPolicy) -> Guardrail<'a> {
+        self.temporal = temporal;
+        self
+    }
+
+    /// Draws this guardrail's nonces and challenges from `entropy`.
+    pub fn with_entropy(mut self, entropy: &'a dyn Entropy) -> Guardrail<'a> {
+        self.entropy = entropy;
+        self
+    }
+
+    /// Verifies carried lineages under `authority`.
+    pub fn with_authority(mut self, authority: &'a dyn AuthorityProfile) -> Guardrail<'a> {
+        self.authority = authority;
+        self
+    }
+
+    /// The semantic scopes of one carried lineage: its signed grant's, when
+    /// this guardrail holds the grant and it names any, else the bindings'.
+    fn scopes_of(&self, p: &Participant) -> Vec<String> {
+        let grant = self.grants.and_then(|g| g.get(&p.chain[0].grant_id));
+        match grant {
+            Some(g) if !g.scopes.is_empty() => g.scopes.clone(),
+            _ => scopes_of(self.scopes, p),
         }
     }
 
@@ -402,7 +473,7 @@ impl<'a> Guardrail<'a> {
         now: DateTime<Utc>,
     ) -> (Option<Pca>, EnforcementTrace) {
         let mut trace = EnforcementTrace {
-            guardrail_executor: self.identity.id.clone(),
+            guardrail_executor: self.identity.id().to_string(),
             outer_predecessor: se.tip().lineage_counter,
             enforced: "deny".into(),
             ..Default::default()
@@ -416,9 +487,20 @@ impl<'a> Guardrail<'a> {
             return (None, trace);
         }
 
+        // Every signature of the outer lineage and the carried lineages in one
+        // batch; the checks below then only re-verify on a batch failure.
+        let mut verifier =
+            Verifier::new(self.registry, self.revocations).with_temporal(self.temporal);
+        if let Some(grants) = self.grants {
+            verifier = verifier.with_grants(grants);
+        }
+        let chains: Vec<&[Pca]> = std::iter::once(se.chain.as_slice())
+            .chain(mle.participants.iter().map(|p| p.chain.as_slice()))
+            .collect();
+        verifier.batch_signatures(&chains, now);
+
         // 1. validate the outer predecessor lineage.
-        if let Err(e) = Verifier::new(self.registry, self.revocations).verify_full_chain(&se.chain, now)
-        {
+        if let Err(e) = verifier.verify_full_chain(&se.chain, now) {
             trace.decision = PdpDecision {
                 effect: "deny".into(),
                 reason: format!("invalid outer continuation: {e}"),
@@ -428,23 +510,22 @@ impl<'a> Guardrail<'a> {
         }
         trace.outer_valid = true;
 
-        // 2. validate every carried lineage.
+        // 2. validate every carried lineage, under the guardrail's profile.
+        let mut verifier = verifier.with_authority(self.authority);
         trace.carried_valid = true;
         for p in &mle.participants {
             let mut tp = TraceParticipant {
                 label: p.label.clone(),
                 grant_id: p.chain[0].grant_id.clone(),
-                scopes: scopes_of(self.scopes, p),
+                scopes: self.scopes_of(p),
                 authority: p.tip().invariants.operations.clone(),
                 chain_len: p.chain.len(),
                 valid: true,
                 ..Default::default()
             };
-            if let Err(e) =
-                Verifier::new(self.registry, self.revocations).verify_full_chain(&p.chain, now)
-            {
+            if let Err(e) = verifier.verify_full_chain(&p.chain, now) {
                 tp.valid = false;
-                tp.error = e;
+                tp.error = e.to_string();
                 trace.carried_valid = false;
             }
             trace.carried_lineages.push(tp);
@@ -479,18 +560,21 @@ impl<'a> Guardrail<'a> {
         }
 
         // 4. permit: build multiLineage, commit it, and prove the next outer PCA.
-        let ml = build_multi_lineage(mle, now);
+        let ml = build_multi_lineage(mle, now, self.entropy);
         let mld = multi_lineage_digest(&ml);
         let policy_commit = digest_of(&self.policy);
         let inputs_commit = digest_of(&req.participants);
         trace.pdp_request = Some(req);
         let pred = se.tip().clone();
         let outer = Prover::new(self.identity, self.attestation.clone())
+            .with_entropy(self.entropy)
+            .with_temporal(self.temporal)
             .continue_enforce(
                 &pred,
                 Invariants {
                     operations: vec![ENFORCE_OPERATION.to_string()],
                     execution_contract: pred.invariants.execution_contract.clone(),
+                    ..Default::default()
                 },
                 Request {
                     operation: ENFORCE_OPERATION.to_string(),
@@ -515,7 +599,11 @@ impl<'a> Guardrail<'a> {
 }
 
 /// Assembles the signed multiLineage field from the input carrier.
-fn build_multi_lineage(mle: &MultiLineageExecution, now: DateTime<Utc>) -> MultiLineage {
+fn build_multi_lineage(
+    mle: &MultiLineageExecution,
+    now: DateTime<Utc>,
+    entropy: &dyn Entropy,
+) -> MultiLineage {
     MultiLineage {
         carried_lineages: mle
             .participants
@@ -533,7 +621,7 @@ fn build_multi_lineage(mle: &MultiLineageExecution, now: DateTime<Utc>) -> Multi
             freshness: Freshness {
                 issued_at: rfc3339(now),
                 expires_at: rfc3339(now + Duration::minutes(5)),
-                nonce: random_b64(16),
+                nonce: random_b64_from(entropy, 16),
             },
         },
     }
@@ -545,35 +633,56 @@ fn build_multi_lineage(mle: &MultiLineageExecution, now: DateTime<Utc>) -> Multi
 
 /// What a conforming receiving hop runs: accept the outer continuation only when
 /// every condition holds. `accepted_origins` are the authorized sandbox origins
-/// (by DID). Recomputes every digest; never trusts a supplied one.
+/// (by DID). The outer and every carried lineage are checked by `verifier`,
+/// under its authority profile and temporal policy. Recomputes every digest;
+/// never trusts a supplied one.
 pub fn accept_guarded_crossing(
-    reg: &Registry,
-    rev: Option<&RevocationStore>,
+    verifier: &mut Verifier<'_>,
+    accepted_origins: &[String],
+    outer_chain: &[Pca],
+    now: DateTime<Utc>,
+) -> PicResult<()> {
+    let res = accept_checked(verifier, accepted_origins, outer_chain, now);
+    verifier.forget_batch();
+    res
+}
+
+fn accept_checked(
+    verifier: &mut Verifier<'_>,
     accepted_origins: &[String],
     outer_chain: &[Pca],
     now: DateTime<Utc>,
 ) -> PicResult<()> {
+    let reject = |e| Err(PicError::GuardedAcceptance(e));
     if outer_chain.is_empty() {
-        return Err("enforced acceptance: no Sandboxed Execution presented".into());
+        return reject(AcceptanceError::NoSandboxedExecution);
     }
+    // One batch over the outer and every carried lineage's signatures.
+    let carried = outer_chain
+        .last()
+        .and_then(|tip| tip.multi_lineage.as_ref())
+        .map_or(&[][..], |ml| ml.carried_lineages.as_slice());
+    let chains: Vec<&[Pca]> = std::iter::once(outer_chain)
+        .chain(carried.iter().map(|cl| cl.chain.as_slice()))
+        .collect();
+    verifier.batch_signatures(&chains, now);
     // ValidOuterPIC.
-    Verifier::new(reg, rev)
+    verifier
         .verify_full_chain(outer_chain, now)
-        .map_err(|e| format!("enforced acceptance: invalid outer continuation: {e}"))?;
+        .map_err(|e| PicError::GuardedAcceptance(AcceptanceError::OuterInvalid(Box::new(e))))?;
     // ValidSandboxOrigin.
     let origin = &outer_chain[0];
     if !origin.is_origin() {
-        return Err("enforced acceptance: outer chain does not start at PCA0-G".into());
+        return reject(AcceptanceError::NotStartedAtOrigin);
     }
     if !accepted_origins.contains(&origin.issuer) {
-        return Err(format!(
-            "enforced acceptance: sandbox origin {:?} is not authorized",
-            origin.issuer
-        ));
+        return reject(AcceptanceError::UnauthorizedOrigin {
+            origin: origin.issuer.clone(),
+        });
     }
     let tip = outer_chain.last().unwrap();
     let Some(por) = &tip.proof_of_relationship else {
-        return Err("enforced acceptance: no guardrail hop (PCA0-G is not a guardrail decision)".into());
+        return reject(AcceptanceError::NoGuardrailHop);
     };
     // ENFORCE authority and executed operation are separate checks.
     if !tip
@@ -582,36 +691,51 @@ pub fn accept_guarded_crossing(
         .iter()
         .any(|o| o == ENFORCE_OPERATION)
     {
-        return Err("enforced acceptance: ENFORCE not in outer authority context".into());
+        return reject(AcceptanceError::EnforceNotAuthorized);
     }
     if por.request.operation != ENFORCE_OPERATION {
-        return Err("enforced acceptance: executed request is not ENFORCE".into());
+        return reject(AcceptanceError::NotEnforceRequest {
+            operation: por.request.operation.clone(),
+        });
     }
     // multiLineage present and committed by the request.
     let Some(ml) = &tip.multi_lineage else {
-        return Err("enforced acceptance: no multiLineage".into());
+        return reject(AcceptanceError::NoMultiLineage);
     };
-    if por.request.multi_lineage_digest != multi_lineage_digest(ml) {
-        return Err(
-            "enforced acceptance: multiLineageDigest does not match recomputed digest".into(),
-        );
+    let ml_digest = multi_lineage_digest(ml);
+    if por.request.multi_lineage_digest != ml_digest {
+        return reject(AcceptanceError::MultiLineageDigestMismatch {
+            expected: ml_digest,
+            found: por.request.multi_lineage_digest.clone(),
+        });
     }
     // ValidMultiLineage: at least one carried lineage, each independently valid.
     if ml.carried_lineages.is_empty() {
-        return Err("enforced acceptance: empty carriedLineages".into());
+        return reject(AcceptanceError::EmptyCarriedLineages);
     }
     for cl in &ml.carried_lineages {
-        Verifier::new(reg, rev)
+        verifier
             .verify_full_chain(&cl.chain, now)
-            .map_err(|e| format!("enforced acceptance: carried lineage {:?} invalid: {e}", cl.label))?;
+            .map_err(|e| {
+                PicError::GuardedAcceptance(AcceptanceError::CarriedLineageInvalid {
+                    label: cl.label.clone(),
+                    error: Box::new(e),
+                })
+            })?;
     }
     // enforcementResult must be permit.
     if por.request.enforcement_result != "permit" {
-        return Err("enforced acceptance: enforcementResult is not permit".into());
+        return reject(AcceptanceError::NotPermitted {
+            result: por.request.enforcement_result.clone(),
+        });
     }
-    // freshness: the outer tip is within its window.
-    if now >= crate::parse_rfc3339(&tip.expires_at) {
-        return Err("enforced acceptance: outside the freshness window".into());
+    // freshness: the outer tip is within its window, up to the Verifier's skew.
+    let tip_expires = crate::parse_rfc3339(&tip.expires_at)
+        .map_err(|e| PicError::GuardedAcceptance(AcceptanceError::MalformedTime(e)))?;
+    if now - tip_expires >= verifier.temporal.backward_skew {
+        return reject(AcceptanceError::Stale {
+            expires_at: tip.expires_at.clone(),
+        });
     }
     Ok(())
 }
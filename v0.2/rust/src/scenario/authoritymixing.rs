@@ -15,7 +15,7 @@
 use super::World;
 use crate::prover::mint_pca0;
 use crate::types::{ExecutionContract, Invariants, Request};
-use crate::{PicResult, Prover};
+use crate::{PicError, PicResult, Prover};
 use chrono::{DateTime, Utc};
 
 fn summary_origin_invariants() -> Invariants {
@@ -25,6 +25,7 @@ fn summary_origin_invariants() -> Invariants {
             execution_model: "agentic".to_string(),
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -35,6 +36,7 @@ fn backup_origin_invariants() -> Invariants {
             execution_model: "deterministic".to_string(),
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -44,9 +46,9 @@ pub struct MixingResult {
     pub lineage_backup_authority: Vec<String>,
     pub lineage_summary_authority: Vec<String>,
     pub honest_accepted: bool,
-    pub honest_err: Option<String>,
+    pub honest_err: Option<PicError>,
     pub composed: bool,
-    pub compose_err: Option<String>,
+    pub compose_err: Option<PicError>,
 }
 
 impl World {
@@ -57,7 +59,7 @@ impl World {
         let mut res = MixingResult::default();
 
         // Lineage 2 (backup): {read-all, backup} -> {read-all}.
-        let backup0 = mint_pca0(self.id("alice"), backup_origin_invariants(), "", now);
+        let backup0 = mint_pca0(self.id("alice"), backup_origin_invariants(), None, now)?;
         let backup_req = Request {
             operation: "read-all".to_string(),
             target: "dataset/*".to_string(),
@@ -70,6 +72,7 @@ impl World {
                 Invariants {
                     operations: vec!["read-all".to_string()],
                     execution_contract: backup_origin_invariants().execution_contract,
+                    ..Default::default()
                 },
                 backup_req,
                 now,
@@ -80,7 +83,7 @@ impl World {
         res.lineage_backup_authority = inv_a.operations;
 
         // Lineage 1 (summary): {read-foo, share-files} -> {share-files}.
-        let summary0 = mint_pca0(self.id("alice"), summary_origin_invariants(), "", now);
+        let summary0 = mint_pca0(self.id("alice"), summary_origin_invariants(), None, now)?;
         let summary_req = Request {
             operation: "share-files".to_string(),
             target: "doc/foo".to_string(),
@@ -93,6 +96,7 @@ impl World {
                 Invariants {
                     operations: vec!["share-files".to_string()],
                     execution_contract: summary_origin_invariants().execution_contract,
+                    ..Default::default()
                 },
                 summary_req.clone(),
                 now,
@@ -110,6 +114,7 @@ impl World {
             Invariants {
                 operations: vec!["share-files".to_string()],
                 execution_contract: summary_origin_invariants().execution_contract,
+                ..Default::default()
             },
             summary_req.clone(),
             now,
@@ -127,6 +132,7 @@ impl World {
             Invariants {
                 operations: vec!["read-all".to_string(), "share-files".to_string()],
                 execution_contract: summary_origin_invariants().execution_contract,
+                ..Default::default()
             },
             summary_req,
             now,
@@ -8,6 +8,7 @@
 //! rejects. Backs the `picdemo flow` visualization.
 
 use super::World;
+use crate::diff::{diff, InvariantDiff, OperationChange};
 use crate::prover::mint_pca0;
 use crate::types::{ExecutionContract, Invariants, Pca, Request};
 use crate::{PicResult, Prover};
@@ -15,7 +16,8 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 /// One step of the execution flow: who acted, what they did, the authority they
-/// carry, what they dropped versus their predecessor, and the signed PCA.
+/// carry, how it differs from their predecessor's (and the operations that
+/// dropped out), and the signed PCA.
 #[derive(Serialize)]
 pub struct FlowHop {
     #[serde(rename = "hop")]
@@ -25,6 +27,8 @@ pub struct FlowHop {
     pub authority: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub dropped: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<InvariantDiff>,
     #[serde(rename = "lineageCounter")]
     pub lineage_counter: u64,
     #[serde(rename = "previousPcaHash", skip_serializing_if = "String::is_empty")]
@@ -32,11 +36,13 @@ pub struct FlowHop {
     pub generates: Pca,
 }
 
-/// A compromised executor trying to re-expand authority; PIC rejects it.
+/// A compromised executor trying to re-expand authority; PIC rejects it. The
+/// diff names every violation, the Verifier's reason the first.
 #[derive(Serialize)]
 pub struct RogueAttempt {
     pub actor: String,
     pub tried: Vec<String>,
+    pub diff: InvariantDiff,
     pub rejected: bool,
     pub reason: String,
 }
@@ -76,10 +82,11 @@ impl World {
             Invariants {
                 operations: ops0.clone(),
                 execution_contract: contract.clone(),
+                ..Default::default()
             },
-            "",
+            None,
             now,
-        );
+        )?;
         let mut chain = vec![pca0.clone()];
 
         let mut res = FlowResult {
@@ -90,6 +97,7 @@ impl World {
                 action: "mint origin PCA0".to_string(),
                 authority: ops0,
                 dropped: Vec::new(),
+                diff: None,
                 lineage_counter: 0,
                 previous_hash: String::new(),
                 generates: pca0,
@@ -143,18 +151,20 @@ impl World {
                 Invariants {
                     operations: s.ops.clone(),
                     execution_contract: contract.clone(),
+                    ..Default::default()
                 },
                 req,
                 now,
             )?;
             let ph = pred.digest();
-            let dropped = diff_ops(&pred.invariants.operations, &s.ops);
+            let d = diff(&next.invariants, &pred.invariants);
             res.hops.push(FlowHop {
                 index: i + 1,
                 actor: s.actor.to_string(),
                 action: s.action.to_string(),
                 authority: s.ops.clone(),
-                dropped,
+                dropped: d.operations_with(OperationChange::Removed),
+                diff: Some(d),
                 lineage_counter: next.lineage_counter,
                 previous_hash: ph,
                 generates: next.clone(),
@@ -183,6 +193,7 @@ impl World {
                 Invariants {
                     operations: tried.clone(),
                     execution_contract: contract.clone(),
+                    ..Default::default()
                 },
                 Request {
                     operation: "backup".to_string(),
@@ -196,14 +207,10 @@ impl World {
         res.rogue = Some(RogueAttempt {
             actor: "archive-service".to_string(),
             tried,
+            diff: diff(&rogue.invariants, &tip.invariants),
             rejected: rerr.is_some(),
-            reason: rerr.unwrap_or_default(),
+            reason: rerr.map(|e| e.to_string()).unwrap_or_default(),
         });
         Ok(res)
     }
 }
-
-/// Returns the operations present in `a` but not in `b`.
-fn diff_ops(a: &[String], b: &[String]) -> Vec<String> {
-    a.iter().filter(|x| !b.contains(x)).cloned().collect()
-}
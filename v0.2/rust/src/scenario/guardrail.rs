@@ -16,7 +16,7 @@ use crate::sandboxed::{
     Participant, SandboxedExecution,
 };
 use crate::types::{Attestation, ExecutionContract, Invariants, Pca, Request};
-use crate::{mint_pca0, Identity, PicResult, Prover};
+use crate::{mint_pca0, Identity, PicResult, Prover, Verifier};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -93,7 +93,8 @@ impl World {
             &pdp,
             self.set.policy.clone(),
             &self.set.scopes,
-        );
+        )
+        .with_grants(&self.set.grants);
         let origin = self.id("enforcement-origin");
         let agent = self.id("summary-service");
         let agent_att = self.att("summary-service");
@@ -105,15 +106,17 @@ impl World {
             Invariants {
                 operations: vec!["read-all".into(), "backup".into()],
                 execution_contract: contract.clone(),
+                ..Default::default()
             },
-            GRANT_USER_BACKUP,
+            Some(self.set.grant(GRANT_USER_BACKUP)),
             now,
-        );
+        )?;
         let pca1_a = Prover::new(agent, agent_att.clone()).continue_(
             &pca0_a,
             Invariants {
                 operations: vec!["backup".into()],
                 execution_contract: contract.clone(),
+                ..Default::default()
             },
             Request {
                 operation: "backup".into(),
@@ -141,7 +144,7 @@ impl World {
         )?;
 
         // Crossing 1 — PERMIT.
-        let mut se_permit = SandboxedExecution::originate(origin, now);
+        let mut se_permit = SandboxedExecution::originate(origin, now)?;
         let origin_pca = se_permit.origin().clone();
         let mle = MultiLineageExecution {
             participants: vec![
@@ -175,7 +178,7 @@ impl World {
             },
             now,
         )?;
-        let mut se_deny = SandboxedExecution::originate(origin, now);
+        let mut se_deny = SandboxedExecution::originate(origin, now)?;
         let mle_deny = MultiLineageExecution {
             participants: vec![
                 participant("A", chain_a.clone(), "user-backup"),
@@ -200,6 +203,7 @@ impl World {
             Invariants {
                 operations: vec!["write:s3/backups/*".into(), "delete:s3/*".into()],
                 execution_contract: contract.clone(),
+                ..Default::default()
             },
             Request {
                 operation: "delete".into(),
@@ -209,7 +213,7 @@ impl World {
             },
             now,
         )?;
-        let mut se_bad = SandboxedExecution::originate(origin, now);
+        let mut se_bad = SandboxedExecution::originate(origin, now)?;
         let mle_bad = MultiLineageExecution {
             participants: vec![
                 participant("A", chain_a, "user-backup"),
@@ -255,8 +259,9 @@ impl World {
         let inv = Invariants {
             operations: ops.iter().map(|s| s.to_string()).collect(),
             execution_contract: ExecutionContract::default(),
+            ..Default::default()
         };
-        let pca0 = mint_pca0(agent, inv.clone(), grant, now);
+        let pca0 = mint_pca0(agent, inv.clone(), Some(self.set.grant(grant)), now)?;
         let pca1 = Prover::new(agent, att.clone()).continue_(&pca0, inv, req, now)?;
         Ok(vec![pca0, pca1])
     }
@@ -266,14 +271,15 @@ impl World {
     pub fn receiver_checks(&self, outer_chain: &[Pca], now: DateTime<Utc>) -> ReceiverChecks {
         let mut rc = ReceiverChecks::default();
         let origins = self.accepted_origins();
+        let mut verifier = Verifier::new(&self.set.registry, None);
 
-        let verr = accept_guarded_crossing(&self.set.registry, None, &origins, outer_chain, now);
+        let verr = accept_guarded_crossing(&mut verifier, &origins, outer_chain, now);
         rc.accepted = !outer_chain.is_empty() && verr.is_ok();
-        rc.accept_err = verr.err().unwrap_or_default();
+        rc.accept_err = verr.err().map(|e| e.to_string()).unwrap_or_default();
 
-        let berr = accept_guarded_crossing(&self.set.registry, None, &origins, &[], now);
+        let berr = accept_guarded_crossing(&mut verifier, &origins, &[], now);
         rc.bypass_rejected = berr.is_err();
-        rc.bypass_reason = berr.err().unwrap_or_default();
+        rc.bypass_reason = berr.err().map(|e| e.to_string()).unwrap_or_default();
 
         if !outer_chain.is_empty() {
             let mut tampered = outer_chain.to_vec();
@@ -282,9 +288,9 @@ impl World {
                     ml.context.destination = "s3://attacker/exfil".into();
                 }
             }
-            let terr = accept_guarded_crossing(&self.set.registry, None, &origins, &tampered, now);
+            let terr = accept_guarded_crossing(&mut verifier, &origins, &tampered, now);
             rc.tamper_rejected = terr.is_err();
-            rc.tamper_reason = terr.err().unwrap_or_default();
+            rc.tamper_reason = terr.err().map(|e| e.to_string()).unwrap_or_default();
         }
         rc
     }
@@ -307,7 +313,8 @@ impl World {
             &pdp,
             self.set.policy.clone(),
             &self.set.scopes,
-        );
+        )
+        .with_grants(&self.set.grants);
         let agent = self.id("summary-service");
         let chain_b = self.agent_lineage(
             agent,
@@ -322,7 +329,7 @@ impl World {
             },
             now,
         )?;
-        let mut se = SandboxedExecution::originate(self.id("enforcement-origin"), now);
+        let mut se = SandboxedExecution::originate(self.id("enforcement-origin"), now)?;
         let mle = MultiLineageExecution {
             participants: vec![
                 participant("A", chain, "user-backup"),
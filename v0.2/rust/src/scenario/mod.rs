@@ -15,13 +15,15 @@ mod flow;
 pub use authoritymixing::MixingResult;
 pub use flow::{FlowHop, FlowResult, RogueAttempt};
 
+use crate::attestation::AttestationCache;
 use crate::authority::authorize;
 use crate::crypto::Identity;
 use crate::fixtureset::{self, Set};
 use crate::prover::mint_pca0;
+use crate::requestbinding::ExecutedRequest;
 use crate::types::{Attestation, ExecutionContract, Invariants, Pca, Request};
 use crate::verifier::Verifier;
-use crate::{PicResult, Prover};
+use crate::{PicError, PicResult, Prover};
 use chrono::{DateTime, Utc};
 
 const SYS_FILE: &str = "/sys/syslog.txt";
@@ -62,6 +64,7 @@ fn user_invariants() -> Invariants {
             execution_model: "deterministic".to_string(),
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -74,6 +77,7 @@ fn sys_invariants() -> Invariants {
             execution_model: "deterministic".to_string(),
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -81,7 +85,7 @@ fn sys_invariants() -> Invariants {
 #[derive(Default)]
 pub struct ProcessResult {
     pub verified: bool,
-    pub verify_err: Option<String>,
+    pub verify_err: Option<PicError>,
     pub authorized: bool,
     pub auth_err: Option<String>,
 }
@@ -97,7 +101,12 @@ impl World {
     /// The storage service (Carol) logic: verify the received chain, enforce
     /// executed-vs-signed on the tip request binding, and authorize the concrete
     /// request against the tip authority (§3.3 check 8, §4.3).
-    fn process(&self, chain: &[Pca], executed: &Request, now: DateTime<Utc>) -> ProcessResult {
+    fn process(
+        &self,
+        chain: &[Pca],
+        received: &ExecutedRequest,
+        now: DateTime<Utc>,
+    ) -> ProcessResult {
         let inv = match self.verifier().verify_full_chain(chain, now) {
             Ok(inv) => inv,
             Err(e) => {
@@ -114,18 +123,14 @@ impl World {
         };
         if let Some(tip) = chain.last() {
             if let Some(por) = &tip.proof_of_relationship {
-                let signed = &por.request;
-                if signed.operation != executed.operation || signed.target != executed.target {
-                    res.auth_err = Some(format!(
-                        "executed-vs-signed mismatch: signed {}:{}, executed {}:{}",
-                        signed.operation, signed.target, executed.operation, executed.target
-                    ));
+                if let Err(e) = received.check(&por.request) {
+                    res.auth_err = Some(PicError::RequestBinding(e).to_string());
                     return res;
                 }
             }
         }
-        if let Err(e) = authorize(&inv, executed) {
-            res.auth_err = Some(e);
+        if let Err(e) = authorize(&inv, &received.request("")) {
+            res.auth_err = Some(e.to_string());
             return res;
         }
         res.authorized = true;
@@ -135,15 +140,11 @@ impl World {
     /// The archive service's own transaction: it originates a system-scoped
     /// lineage and reads a system file. It is authorized.
     pub fn case1_legit(&self, now: DateTime<Utc>) -> PicResult<(Vec<Pca>, Request, ProcessResult)> {
-        let pca0 = mint_pca0(self.id("archive-service"), sys_invariants(), "", now);
+        let pca0 = mint_pca0(self.id("archive-service"), sys_invariants(), None, now)?;
         let chain = vec![pca0];
-        let req = Request {
-            operation: "read".to_string(),
-            target: SYS_FILE.to_string(),
-            security_domain: "sys".to_string(),
-            ..Default::default()
-        };
-        let res = self.process(&chain, &req, now);
+        let received = ExecutedRequest::new("read", SYS_FILE);
+        let req = received.request("sys");
+        let res = self.process(&chain, &received, now);
         Ok((chain, req, res))
     }
 
@@ -154,17 +155,13 @@ impl World {
         &self,
         now: DateTime<Utc>,
     ) -> PicResult<(Vec<Pca>, Request, ProcessResult)> {
-        let pca0 = mint_pca0(self.id("alice"), user_invariants(), "", now);
-        let req = Request {
-            operation: "read".to_string(),
-            target: SYS_FILE.to_string(),
-            security_domain: "sys".to_string(),
-            ..Default::default()
-        };
+        let pca0 = mint_pca0(self.id("alice"), user_invariants(), None, now)?;
+        let received = ExecutedRequest::new("read", SYS_FILE);
+        let req = received.request("sys");
         let archive = Prover::new(self.id("archive-service"), self.att("archive-service"));
         let pca1 = archive.continue_(&pca0, user_invariants(), req.clone(), now)?;
         let chain = vec![pca0, pca1];
-        let res = self.process(&chain, &req, now);
+        let res = self.process(&chain, &received, now);
         Ok((chain, req, res))
     }
 
@@ -174,13 +171,9 @@ impl World {
         &self,
         now: DateTime<Utc>,
     ) -> PicResult<(Vec<Pca>, Request, ProcessResult)> {
-        let pca0 = mint_pca0(self.id("alice"), user_invariants(), "", now);
-        let req = Request {
-            operation: "read".to_string(),
-            target: SYS_FILE.to_string(),
-            security_domain: "sys".to_string(),
-            ..Default::default()
-        };
+        let pca0 = mint_pca0(self.id("alice"), user_invariants(), None, now)?;
+        let received = ExecutedRequest::new("read", SYS_FILE);
+        let req = received.request("sys");
         let expanded = Invariants {
             operations: vec![
                 "read:/user/*".to_string(),
@@ -188,17 +181,39 @@ impl World {
                 "read:/sys/*".to_string(),
             ],
             execution_contract: user_invariants().execution_contract,
+            ..Default::default()
         };
         let archive = Prover::new(self.id("archive-service"), self.att("archive-service"));
         let pca1 = archive.continue_malicious(&pca0, expanded, req.clone(), now)?;
         let chain = vec![pca0, pca1];
-        let res = self.process(&chain, &req, now);
+        let res = self.process(&chain, &received, now);
         Ok((chain, req, res))
     }
 
     /// Creates a lineage of `hops` non-origin PCAs after PCA0 (hops+1 PCAs
     /// total), each produced by a real fixture executor cycled from the set.
     pub fn build_chain(&self, hops: usize, now: DateTime<Utc>) -> PicResult<Vec<Pca>> {
+        self.cycle_chain(hops, now, None)
+    }
+
+    /// [`World::build_chain`] with every PoR referencing its executor
+    /// attestation by digest; the returned cache resolves the references.
+    pub fn build_chain_by_reference(
+        &self,
+        hops: usize,
+        now: DateTime<Utc>,
+    ) -> PicResult<(Vec<Pca>, AttestationCache)> {
+        let mut cache = AttestationCache::new();
+        let chain = self.cycle_chain(hops, now, Some(&mut cache))?;
+        Ok((chain, cache))
+    }
+
+    fn cycle_chain(
+        &self,
+        hops: usize,
+        now: DateTime<Utc>,
+        mut cache: Option<&mut AttestationCache>,
+    ) -> PicResult<Vec<Pca>> {
         // The deterministic fixture executors cycled to build long chains.
         const CHAIN_EXECUTORS: [&str; 4] = [
             "gateway",
@@ -211,7 +226,7 @@ impl World {
             operations: vec!["read:/user/*".to_string()],
             ..Default::default()
         };
-        let pca0 = mint_pca0(self.id("alice"), inv.clone(), "", now);
+        let pca0 = mint_pca0(self.id("alice"), inv.clone(), None, now)?;
         let mut chain = vec![pca0];
         let req = Request {
             operation: "read".to_string(),
@@ -222,12 +237,12 @@ impl World {
         for i in 0..hops {
             let name = CHAIN_EXECUTORS[i % CHAIN_EXECUTORS.len()];
             let pred = chain.last().unwrap();
-            let next = Prover::new(self.id(name), self.att(name)).continue_(
-                pred,
-                inv.clone(),
-                req.clone(),
-                now,
-            )?;
+            let mut prover = Prover::new(self.id(name), self.att(name));
+            if let Some(cache) = cache.as_deref_mut() {
+                cache.insert(prover.attestation.clone());
+                prover = prover.by_reference();
+            }
+            let next = prover.continue_(pred, inv.clone(), req.clone(), now)?;
             chain.push(next);
         }
         Ok(chain)
@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Signers: whatever holds the private key behind a PCA, attestation,
+//! envelope or snapshot signature. Minting, proving, issuing and COSE sealing
+//! take a [`Signer`] rather than an [`Identity`], so an executor can keep its
+//! key in an HSM, a KMS or a sidecar process and never hold it in memory.
+//!
+//! [`Identity`] is the in-memory signer. [`RemoteSigner`] is the client of a
+//! minimal signing service over a Unix socket: one connection per request,
+//! one JSON [`SignerRequest`] line answered by one JSON [`SignerResponse`]
+//! line.
+//!
+//! - `{"op":"describe","keyId":K}` → `{"id","verificationMethod","type","publicKey"}`
+//! - `{"op":"sign","keyId":K,"message":B64}` → `{"signature":B64}`
+//!
+//! Either answer may instead be `{"error":REASON}`. `src/bin/mocksigner.rs`
+//! serves the fixture identities over this protocol for the tests.
+
+use crate::crypto::Identity;
+use crate::error::{CodecError, SignerError};
+use crate::suite::{PublicKey, SignatureSuite};
+use crate::types::Proof;
+use crate::{PicError, PicResult};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+
+/// The future of an asynchronous signature.
+pub type SignFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, SignerError>> + Send + 'a>>;
+
+/// A key that signs on behalf of an identifier, synchronously or not.
+pub trait Signer: Send + Sync {
+    /// The identifier (DID) the signatures are made for.
+    fn id(&self) -> &str;
+    /// The verification method a Verifier resolves the key by.
+    fn verification_method(&self) -> &str;
+    /// The signature suite of the key.
+    fn suite(&self) -> SignatureSuite {
+        self.public_key().suite()
+    }
+    fn public_key(&self) -> PublicKey;
+    /// The raw signature over `msg`, in the key's suite.
+    fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>, SignerError>;
+    /// [`Signer::try_sign`] without blocking the caller. The default signs
+    /// when first polled; remote signers override it.
+    fn sign_async<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(async move { self.try_sign(msg) })
+    }
+}
+
+impl Signer for Identity {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn verification_method(&self) -> &str {
+        &self.verification_method
+    }
+
+    fn suite(&self) -> SignatureSuite {
+        Identity::suite(self)
+    }
+
+    fn public_key(&self) -> PublicKey {
+        Identity::public_key(self)
+    }
+
+    fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>, SignerError> {
+        Ok(self.sign_raw(msg))
+    }
+}
+
+fn to_proof(signer: &dyn Signer, raw: &[u8]) -> Proof {
+    Proof {
+        type_: signer.suite().proof_type().to_string(),
+        verification_method: signer.verification_method().to_string(),
+        signature: crate::crypto::b64_encode(raw),
+    }
+}
+
+/// The proof `signer` makes over `msg`, the signing bytes of `doc`. A
+/// document with a number its canonical form would round is refused: the
+/// signature would not pin it.
+pub(crate) fn proof<T: Serialize>(signer: &dyn Signer, doc: &T, msg: &[u8]) -> PicResult<Proof> {
+    signable(doc)?;
+    let raw = signer.try_sign(msg).map_err(PicError::Signer)?;
+    Ok(to_proof(signer, &raw))
+}
+
+/// [`proof`] through [`Signer::sign_async`].
+pub(crate) async fn proof_async<T: Serialize>(
+    signer: &dyn Signer,
+    doc: &T,
+    msg: &[u8],
+) -> PicResult<Proof> {
+    signable(doc)?;
+    let raw = signer.sign_async(msg).await.map_err(PicError::Signer)?;
+    Ok(to_proof(signer, &raw))
+}
+
+fn signable<T: Serialize>(doc: &T) -> PicResult<()> {
+    let value = serde_json::to_value(doc).map_err(|e| {
+        PicError::Codec(CodecError::Json {
+            reason: e.to_string(),
+        })
+    })?;
+    crate::jcs::exact_numbers(&value).map_err(PicError::Codec)
+}
+
+/// One request line of the remote signing protocol.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerRequest {
+    /// `describe` or `sign`.
+    pub op: String,
+    pub key_id: String,
+    /// The base64url message to sign (`sign` only).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+/// One answer line of the remote signing protocol; only the members of the
+/// answered operation are set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerResponse {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub verification_method: String,
+    /// The proof type of the key's suite.
+    #[serde(rename = "type", default, skip_serializing_if = "String::is_empty")]
+    pub type_: String,
+    /// The base64url raw public key (see [`PublicKey::to_bytes`]).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub public_key: String,
+    /// The base64url raw signature.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub error: String,
+}
+
+#[cfg(unix)]
+pub use remote::RemoteSigner;
+
+#[cfg(unix)]
+mod remote {
+    use super::{SignFuture, Signer, SignerRequest, SignerResponse};
+    use crate::crypto::{b64_decode, b64_encode};
+    use crate::error::SignerError;
+    use crate::suite::{PublicKey, SignatureSuite};
+    use crate::{PicError, PicResult};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// How long one request may take end to end.
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    /// The longest answer line read from the service.
+    const MAX_RESPONSE: u64 = 64 * 1024;
+
+    /// A [`Signer`] whose key lives in a signing service on a Unix socket.
+    /// The public key is fetched once, on connect, and every returned
+    /// signature is checked against it before use.
+    #[derive(Debug, Clone)]
+    pub struct RemoteSigner {
+        inner: Arc<Remote>,
+    }
+
+    #[derive(Debug)]
+    struct Remote {
+        socket: PathBuf,
+        key_id: String,
+        id: String,
+        verification_method: String,
+        public_key: PublicKey,
+    }
+
+    impl RemoteSigner {
+        /// Connects to the service at `socket` and describes key `key_id`.
+        pub fn connect(socket: impl AsRef<Path>, key_id: &str) -> PicResult<RemoteSigner> {
+            let socket = socket.as_ref().to_path_buf();
+            let resp = call(
+                &socket,
+                &SignerRequest {
+                    op: "describe".to_string(),
+                    key_id: key_id.to_string(),
+                    ..Default::default()
+                },
+            )
+            .map_err(PicError::Signer)?;
+            let protocol = |reason: String| PicError::Signer(SignerError::Protocol { reason });
+            let suite = SignatureSuite::from_proof_type(&resp.type_)
+                .map_err(|e| protocol(e.to_string()))?;
+            let public_key = b64_decode(&resp.public_key)
+                .ok()
+                .and_then(|raw| PublicKey::from_bytes(suite, &raw))
+                .ok_or_else(|| protocol(format!("invalid public key for {key_id:?}")))?;
+            if resp.id.is_empty() || resp.verification_method.is_empty() {
+                return Err(protocol(format!("incomplete description of {key_id:?}")));
+            }
+            Ok(RemoteSigner {
+                inner: Arc::new(Remote {
+                    socket,
+                    key_id: key_id.to_string(),
+                    id: resp.id,
+                    verification_method: resp.verification_method,
+                    public_key,
+                }),
+            })
+        }
+    }
+
+    impl Remote {
+        fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, SignerError> {
+            let resp = call(
+                &self.socket,
+                &SignerRequest {
+                    op: "sign".to_string(),
+                    key_id: self.key_id.clone(),
+                    message: b64_encode(msg),
+                },
+            )?;
+            let raw = b64_decode(&resp.signature).map_err(|e| SignerError::Protocol {
+                reason: format!("signature: {e}"),
+            })?;
+            self.public_key
+                .verify(&self.verification_method, msg, &raw)
+                .map_err(|e| SignerError::Protocol {
+                    reason: e.to_string(),
+                })?;
+            Ok(raw)
+        }
+    }
+
+    /// Sends one request on a fresh connection and reads its answer.
+    fn call(socket: &Path, req: &SignerRequest) -> Result<SignerResponse, SignerError> {
+        let unavailable = |e: std::io::Error| SignerError::Unavailable {
+            reason: e.to_string(),
+        };
+        let mut stream = UnixStream::connect(socket).map_err(unavailable)?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .map_err(unavailable)?;
+        stream
+            .set_write_timeout(Some(TIMEOUT))
+            .map_err(unavailable)?;
+        let mut line = serde_json::to_vec(req).expect("request to_vec");
+        line.push(b'\n');
+        stream.write_all(&line).map_err(unavailable)?;
+        let mut answer = String::new();
+        BufReader::new(stream.take(MAX_RESPONSE))
+            .read_line(&mut answer)
+            .map_err(unavailable)?;
+        if answer.is_empty() {
+            return Err(SignerError::Unavailable {
+                reason: "connection closed without an answer".to_string(),
+            });
+        }
+        let resp: SignerResponse =
+            serde_json::from_str(&answer).map_err(|e| SignerError::Protocol {
+                reason: e.to_string(),
+            })?;
+        if !resp.error.is_empty() {
+            return Err(SignerError::Refused { reason: resp.error });
+        }
+        Ok(resp)
+    }
+
+    impl Signer for RemoteSigner {
+        fn id(&self) -> &str {
+            &self.inner.id
+        }
+
+        fn verification_method(&self) -> &str {
+            &self.inner.verification_method
+        }
+
+        fn public_key(&self) -> PublicKey {
+            self.inner.public_key.clone()
+        }
+
+        fn try_sign(&self, msg: &[u8]) -> Result<Vec<u8>, SignerError> {
+            self.inner.sign(msg)
+        }
+
+        /// Runs the request on its own thread; the future completes when the
+        /// service answers.
+        fn sign_async<'a>(&'a self, msg: &'a [u8]) -> SignFuture<'a> {
+            let inner = Arc::clone(&self.inner);
+            let msg = msg.to_vec();
+            let (tx, rx) = futures_channel::oneshot::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(inner.sign(&msg));
+            });
+            Box::pin(async move {
+                rx.await.unwrap_or_else(|_| {
+                    Err(SignerError::Unavailable {
+                        reason: "signing thread ended without an answer".to_string(),
+                    })
+                })
+            })
+        }
+    }
+}
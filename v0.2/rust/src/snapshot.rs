@@ -7,53 +7,77 @@
 //! validates the chain up to some `PCA[k]` and signs a snapshot committing to its
 //! content id; a downstream Verifier then validates only the hops after it.
 
+use crate::authority::{profile_of, AuthorityProfile};
 use crate::crypto::Registry;
-use crate::types::{Invariants, Pca, Proof, Snapshot};
-use crate::verifier::Verifier;
-use crate::{parse_rfc3339, rfc3339, PicResult, SIGNATURE_TYPE};
-use chrono::{DateTime, Duration, Utc};
-
-fn lineage_ttl() -> Duration {
-    Duration::hours(24)
-}
+use crate::error::{Checkpoint, SnapshotError};
+use crate::signer::{proof, proof_async, Signer};
+use crate::types::{Invariants, Pca, Snapshot};
+use crate::verifier::{signed_at, Verifier};
+use crate::{rfc3339, PicError, PicResult};
+use chrono::{DateTime, Utc};
 
 /// Has a trusted snapshot issuer validate `chain[0..=through_index]` (full-chain)
-/// and, only if valid, sign a snapshot committing to `PCA[through_index]` as the
-/// valid tip. It refuses to attest an invalid chain.
+/// under `authority`, the profile the lineage was minted under, and, only if
+/// valid, sign a snapshot committing to `PCA[through_index]` as the valid tip.
+/// It refuses to attest an invalid chain. The snapshot expires with the
+/// lineage, as its PCA0's temporal profile set it.
 pub fn issue_snapshot(
-    issuer: &crate::Identity,
+    issuer: &dyn Signer,
     reg: &Registry,
     chain: &[Pca],
     through_index: usize,
     now: DateTime<Utc>,
+    authority: &dyn AuthorityProfile,
+) -> PicResult<Snapshot> {
+    let mut s = snapshot(issuer.id(), reg, chain, through_index, now, authority)?;
+    s.proof = Some(proof(issuer, &s, &s.signing_bytes())?);
+    Ok(s)
+}
+
+/// [`issue_snapshot`] through [`Signer::sign_async`].
+pub async fn issue_snapshot_async(
+    issuer: &dyn Signer,
+    reg: &Registry,
+    chain: &[Pca],
+    through_index: usize,
+    now: DateTime<Utc>,
+    authority: &dyn AuthorityProfile,
+) -> PicResult<Snapshot> {
+    let mut s = snapshot(issuer.id(), reg, chain, through_index, now, authority)?;
+    s.proof = Some(proof_async(issuer, &s, &s.signing_bytes()).await?);
+    Ok(s)
+}
+
+/// The unsigned snapshot of [`issue_snapshot`], once the prefix validates.
+fn snapshot(
+    issuer: &str,
+    reg: &Registry,
+    chain: &[Pca],
+    through_index: usize,
+    now: DateTime<Utc>,
+    authority: &dyn AuthorityProfile,
 ) -> PicResult<Snapshot> {
     if through_index >= chain.len() {
-        return Err(format!(
-            "snapshot: throughIndex {through_index} out of range"
-        ));
+        return Err(PicError::Snapshot(SnapshotError::ThroughIndexOutOfRange {
+            index: through_index,
+            len: chain.len(),
+        }));
     }
     // The issuer re-validates the whole prefix it vouches for.
     Verifier::new(reg, None)
+        .with_authority(authority)
         .verify_full_chain(&chain[..=through_index], now)
-        .map_err(|e| format!("snapshot: refusing to attest an invalid chain: {e}"))?;
+        .map_err(|e| PicError::Snapshot(SnapshotError::InvalidChain(Box::new(e))))?;
     let tip = &chain[through_index];
-    let dig = tip.digest();
-    let mut s = Snapshot {
+    Ok(Snapshot {
         lineage_id: tip.lineage_id.clone(),
         through_counter: tip.lineage_counter,
-        through_pca_hash: dig,
-        issuer: issuer.id.clone(),
+        through_pca_hash: tip.digest(),
+        issuer: issuer.to_string(),
         issued_at: rfc3339(now),
-        expires_at: rfc3339(now + lineage_ttl()),
+        expires_at: chain[0].expires_at.clone(),
         proof: None,
-    };
-    let msg = s.signing_bytes();
-    s.proof = Some(Proof {
-        type_: SIGNATURE_TYPE.to_string(),
-        verification_method: issuer.verification_method.clone(),
-        signature: issuer.sign(&msg),
-    });
-    Ok(s)
+    })
 }
 
 impl Verifier<'_> {
@@ -69,53 +93,51 @@ impl Verifier<'_> {
         now: DateTime<Utc>,
     ) -> PicResult<Invariants> {
         if tail.is_empty() {
-            return Err("snapshot verify: empty tail".to_string());
+            return Err(PicError::Snapshot(SnapshotError::EmptyTail));
         }
         let proof = snap
             .proof
             .as_ref()
-            .ok_or("snapshot verify: missing signature")?;
+            .ok_or(PicError::Snapshot(SnapshotError::MissingSignature))?;
         let msg = snap.signing_bytes();
         self.registry
-            .verify(&snap.issuer, &msg, &proof.signature)
-            .map_err(|e| format!("snapshot verify: {e}"))?;
-        if let Err(e) = within(&snap.issued_at, &snap.expires_at, now) {
-            return Err(format!("snapshot verify: {e}"));
-        }
+            .verify_at(&snap.issuer, &msg, proof, signed_at(&snap.issued_at, now))
+            .map_err(|e| PicError::Snapshot(SnapshotError::Signature(e)))?;
+        self.temporal
+            .within(&snap.issued_at, &snap.expires_at, now)
+            .map_err(|e| PicError::Snapshot(SnapshotError::Validity(e)))?;
 
         let tip = &tail[0];
         let dig = tip.digest();
         if dig != snap.through_pca_hash {
-            return Err(
-                "snapshot verify: tip digest does not match snapshot commitment".to_string(),
-            );
+            return Err(PicError::Snapshot(SnapshotError::TipDigestMismatch {
+                expected: snap.through_pca_hash.clone(),
+                found: dig,
+            }));
         }
         if tip.lineage_id != snap.lineage_id || tip.lineage_counter != snap.through_counter {
-            return Err("snapshot verify: tip coordinates do not match snapshot".to_string());
+            return Err(PicError::Snapshot(SnapshotError::TipCoordinatesMismatch));
+        }
+        if profile_of(tip) != self.authority.id() {
+            return Err(PicError::Snapshot(SnapshotError::UnknownAuthorityProfile {
+                expected: self.authority.id().to_string(),
+                found: profile_of(tip).to_string(),
+            }));
         }
         // The tip is trusted as a valid chain tip via the snapshot; still honor a
         // revocation that strikes it or its future.
         if let Some(rev) = self.revocations {
-            rev.check(tip)
-                .map_err(|e| format!("snapshot verify tip: {e}"))?;
+            rev.check(tip).map_err(|revoked| PicError::Revocation {
+                hop: None,
+                checkpoint: Checkpoint::SnapshotTip,
+                revoked,
+            })?;
         }
         // Validate only the hops after the snapshot: O(hops since snapshot).
         for i in 1..tail.len() {
             self.verify_hop(&tail[i], &tail[i - 1], now, false)
-                .map_err(|e| format!("post-snapshot hop {i}: {e}"))?;
+                .map_err(|e| PicError::Snapshot(SnapshotError::Hop(Box::new(e.at_hop(i)))))?;
         }
         Ok(tail[tail.len() - 1].invariants.clone())
     }
 }
-
-fn within(issued_at: &str, expires_at: &str, now: DateTime<Utc>) -> PicResult<()> {
-    let issued = parse_rfc3339(issued_at);
-    let expires = parse_rfc3339(expires_at);
-    if now < issued {
-        return Err("not yet valid".to_string());
-    }
-    if now >= expires {
-        return Err("expired".to_string());
-    }
-    Ok(())
-}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Randomness and clock sources. Minting, proving and sandboxed execution
+//! draw their nonces and challenges from an [`Entropy`]; callers take the
+//! `now` they pass in from a [`Clock`]. The defaults are the operating
+//! system's; the seeded and fixed sources make every artifact reproducible,
+//! byte for byte, in any implementation (see [`crate::vectors`]).
+//!
+//! [`SeededEntropy`] is a SHA-256 counter stream: block `i` (from 0) is
+//! `SHA-256("PIC-v0.2-entropy" ‖ seed ‖ u64be(i))`, and successive draws
+//! take successive bytes of the concatenated blocks.
+
+use crate::crypto::b64_encode;
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::{Mutex, PoisonError};
+
+/// The domain separator of [`SeededEntropy`] blocks.
+pub const ENTROPY_DOMAIN: &[u8] = b"PIC-v0.2-entropy";
+
+/// A source of the random bytes in nonces and challenges.
+pub trait Entropy: Send + Sync {
+    /// Fills `buf` with the next bytes of the source.
+    fn fill(&self, buf: &mut [u8]);
+}
+
+/// The operating system's randomness.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEntropy;
+
+impl Entropy for OsEntropy {
+    fn fill(&self, buf: &mut [u8]) {
+        rand::rngs::OsRng.fill_bytes(buf);
+    }
+}
+
+/// A deterministic stream derived from a seed; for tests and test vectors
+/// only, since anyone who knows the seed predicts every nonce.
+pub struct SeededEntropy {
+    seed: Vec<u8>,
+    state: Mutex<Stream>,
+}
+
+#[derive(Default)]
+struct Stream {
+    counter: u64,
+    block: [u8; 32],
+    /// Bytes of `block` already drawn; 32 when a new block is needed.
+    used: usize,
+}
+
+impl SeededEntropy {
+    pub fn new(seed: &[u8]) -> SeededEntropy {
+        SeededEntropy {
+            seed: seed.to_vec(),
+            state: Mutex::new(Stream {
+                used: 32,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl Entropy for SeededEntropy {
+    fn fill(&self, buf: &mut [u8]) {
+        let mut s = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        for b in buf {
+            if s.used == 32 {
+                let mut h = Sha256::new();
+                h.update(ENTROPY_DOMAIN);
+                h.update(&self.seed);
+                h.update(s.counter.to_be_bytes());
+                s.block = h.finalize().into();
+                s.counter += 1;
+                s.used = 0;
+            }
+            *b = s.block[s.used];
+            s.used += 1;
+        }
+    }
+}
+
+/// `n` bytes of `entropy`, base64url-encoded.
+pub fn random_b64_from(entropy: &dyn Entropy, n: usize) -> String {
+    let mut buf = vec![0u8; n];
+    entropy.fill(&mut buf);
+    b64_encode(&buf)
+}
+
+/// A source of the current instant.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that reads a set instant until it is moved.
+pub struct FixedClock {
+    at: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn new(at: DateTime<Utc>) -> FixedClock {
+        FixedClock { at: Mutex::new(at) }
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.at.lock().unwrap_or_else(PoisonError::into_inner) = at;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.at.lock().unwrap_or_else(PoisonError::into_inner) += by;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.at.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Signature suites: the algorithms a `Proof.type_` may name, and the keys of
+//! each. A Verifier dispatches on the proof type and checks it against the
+//! suite of the resolved key; a proof type this build does not support fails
+//! closed.
+//!
+//! Ed25519 is the default suite. ECDSA P-256 signatures are the 64-byte
+//! `r || s` form with a low `s` (so a signature, and with it every digest over
+//! a signed document, is unique). Ed448 is the pure (context-free) variant of
+//! RFC 8032 and needs the `ed448` feature.
+//!
+//! Ed25519 signatures are checked with the cofactored equation, singly and in
+//! a batch alike, so that batching never changes a verdict; a small-order key
+//! or `R` is refused outright. Honest signatures verify the same under either
+//! equation; only one whose key holder crafted it with a small-order
+//! component can tell them apart.
+
+use crate::error::SignatureError;
+use coset::iana;
+use ed25519_dalek::{Signer as _, Verifier as _};
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+/// A supported signature algorithm, named on the wire by its proof type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureSuite {
+    Ed25519,
+    EcdsaP256,
+    #[cfg(feature = "ed448")]
+    Ed448,
+}
+
+impl SignatureSuite {
+    /// The suites compiled into this build.
+    pub const ALL: &'static [SignatureSuite] = &[
+        SignatureSuite::Ed25519,
+        SignatureSuite::EcdsaP256,
+        #[cfg(feature = "ed448")]
+        SignatureSuite::Ed448,
+    ];
+
+    /// The `Proof.type_` naming this suite.
+    pub fn proof_type(self) -> &'static str {
+        match self {
+            SignatureSuite::Ed25519 => crate::SIGNATURE_TYPE,
+            SignatureSuite::EcdsaP256 => "EcdsaSecp256r1Signature2019",
+            #[cfg(feature = "ed448")]
+            SignatureSuite::Ed448 => "Ed448Signature2022",
+        }
+    }
+
+    /// The suite a proof type names; an unsupported name is an error.
+    pub fn from_proof_type(proof_type: &str) -> Result<SignatureSuite, SignatureError> {
+        SignatureSuite::ALL
+            .iter()
+            .copied()
+            .find(|s| s.proof_type() == proof_type)
+            .ok_or_else(|| SignatureError::UnsupportedSuite {
+                proof_type: proof_type.to_string(),
+            })
+    }
+
+    /// The JWK curve (`crv`) of this suite's keys.
+    pub fn jwk_curve(self) -> &'static str {
+        match self {
+            SignatureSuite::Ed25519 => "Ed25519",
+            SignatureSuite::EcdsaP256 => "P-256",
+            #[cfg(feature = "ed448")]
+            SignatureSuite::Ed448 => "Ed448",
+        }
+    }
+
+    /// The suite whose keys are on JWK curve `crv`, if supported.
+    pub fn from_jwk_curve(crv: &str) -> Option<SignatureSuite> {
+        SignatureSuite::ALL
+            .iter()
+            .copied()
+            .find(|s| s.jwk_curve() == crv)
+    }
+
+    /// The COSE algorithm of this suite (RFC 9053): EdDSA covers both Edwards
+    /// curves, the key telling them apart.
+    pub fn cose_algorithm(self) -> iana::Algorithm {
+        match self {
+            SignatureSuite::Ed25519 => iana::Algorithm::EdDSA,
+            SignatureSuite::EcdsaP256 => iana::Algorithm::ES256,
+            #[cfg(feature = "ed448")]
+            SignatureSuite::Ed448 => iana::Algorithm::EdDSA,
+        }
+    }
+}
+
+/// A private key of some suite. Each variant wipes its key when dropped
+/// (OpenSSL clears Ed448 keys on free).
+pub(crate) enum SecretKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    EcdsaP256(p256::ecdsa::SigningKey),
+    #[cfg(feature = "ed448")]
+    Ed448(openssl::pkey::PKey<openssl::pkey::Private>),
+}
+
+// The in-memory keys must stay zeroize-on-drop across dependency upgrades.
+const _: fn() = || {
+    fn wiped_on_drop<T: zeroize::ZeroizeOnDrop>() {}
+    wiped_on_drop::<ed25519_dalek::SigningKey>();
+    wiped_on_drop::<p256::ecdsa::SigningKey>();
+};
+
+impl SecretKey {
+    pub(crate) fn generate(suite: SignatureSuite) -> SecretKey {
+        match suite {
+            SignatureSuite::Ed25519 => {
+                let mut seed = Zeroizing::new([0u8; 32]);
+                rand::rngs::OsRng.fill_bytes(seed.as_mut());
+                SecretKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&seed))
+            }
+            SignatureSuite::EcdsaP256 => {
+                SecretKey::EcdsaP256(p256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng))
+            }
+            #[cfg(feature = "ed448")]
+            SignatureSuite::Ed448 => {
+                SecretKey::Ed448(openssl::pkey::PKey::generate_ed448().expect("ed448 keygen"))
+            }
+        }
+    }
+
+    /// Loads a key from its raw secret: the 32-byte Ed25519 seed, the 32-byte
+    /// P-256 scalar, or the 57-byte Ed448 seed.
+    pub(crate) fn from_bytes(suite: SignatureSuite, secret: &[u8]) -> Result<SecretKey, String> {
+        match suite {
+            SignatureSuite::Ed25519 => {
+                let seed: Zeroizing<[u8; 32]> = Zeroizing::new(
+                    secret
+                        .try_into()
+                        .map_err(|_| format!("seed must be 32 bytes, got {}", secret.len()))?,
+                );
+                Ok(SecretKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(
+                    &seed,
+                )))
+            }
+            SignatureSuite::EcdsaP256 => p256::ecdsa::SigningKey::from_slice(secret)
+                .map(SecretKey::EcdsaP256)
+                .map_err(|_| "invalid P-256 scalar".to_string()),
+            #[cfg(feature = "ed448")]
+            SignatureSuite::Ed448 => {
+                openssl::pkey::PKey::private_key_from_raw_bytes(secret, openssl::pkey::Id::ED448)
+                    .map(SecretKey::Ed448)
+                    .map_err(|e| format!("invalid Ed448 seed: {e}"))
+            }
+        }
+    }
+
+    pub(crate) fn suite(&self) -> SignatureSuite {
+        match self {
+            SecretKey::Ed25519(_) => SignatureSuite::Ed25519,
+            SecretKey::EcdsaP256(_) => SignatureSuite::EcdsaP256,
+            #[cfg(feature = "ed448")]
+            SecretKey::Ed448(_) => SignatureSuite::Ed448,
+        }
+    }
+
+    pub(crate) fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            SecretKey::Ed25519(k) => Zeroizing::new(k.to_bytes()).to_vec(),
+            SecretKey::EcdsaP256(k) => Zeroizing::new(k.to_bytes()).to_vec(),
+            #[cfg(feature = "ed448")]
+            SecretKey::Ed448(k) => k.raw_private_key().expect("ed448 raw private key"),
+        })
+    }
+
+    pub(crate) fn public_key(&self) -> PublicKey {
+        match self {
+            SecretKey::Ed25519(k) => PublicKey::Ed25519(k.verifying_key()),
+            SecretKey::EcdsaP256(k) => PublicKey::EcdsaP256(*k.verifying_key()),
+            #[cfg(feature = "ed448")]
+            SecretKey::Ed448(k) => {
+                PublicKey::Ed448(k.raw_public_key().expect("ed448 raw public key"))
+            }
+        }
+    }
+
+    pub(crate) fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        match self {
+            SecretKey::Ed25519(k) => k.sign(msg).to_bytes().to_vec(),
+            SecretKey::EcdsaP256(k) => {
+                let sig: p256::ecdsa::Signature = k.sign(msg);
+                sig.normalize_s().unwrap_or(sig).to_bytes().to_vec()
+            }
+            #[cfg(feature = "ed448")]
+            SecretKey::Ed448(k) => openssl::sign::Signer::new_without_digest(k)
+                .and_then(|mut s| s.sign_oneshot_to_vec(msg))
+                .expect("ed448 sign"),
+        }
+    }
+}
+
+/// A public key of some suite, as held by a [`crate::Registry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    Ed25519(ed25519_dalek::VerifyingKey),
+    EcdsaP256(p256::ecdsa::VerifyingKey),
+    /// The raw 57-byte public key.
+    #[cfg(feature = "ed448")]
+    Ed448(Vec<u8>),
+}
+
+impl PublicKey {
+    /// Parses a raw public key: 32 bytes for Ed25519, a SEC1 point for P-256,
+    /// 57 bytes for Ed448.
+    pub fn from_bytes(suite: SignatureSuite, bytes: &[u8]) -> Option<PublicKey> {
+        match suite {
+            SignatureSuite::Ed25519 => {
+                let raw: [u8; 32] = bytes.try_into().ok()?;
+                ed25519_dalek::VerifyingKey::from_bytes(&raw)
+                    .ok()
+                    .map(PublicKey::Ed25519)
+            }
+            SignatureSuite::EcdsaP256 => p256::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+                .ok()
+                .map(PublicKey::EcdsaP256),
+            #[cfg(feature = "ed448")]
+            SignatureSuite::Ed448 => (bytes.len() == 57).then(|| PublicKey::Ed448(bytes.to_vec())),
+        }
+    }
+
+    pub fn suite(&self) -> SignatureSuite {
+        match self {
+            PublicKey::Ed25519(_) => SignatureSuite::Ed25519,
+            PublicKey::EcdsaP256(_) => SignatureSuite::EcdsaP256,
+            #[cfg(feature = "ed448")]
+            PublicKey::Ed448(_) => SignatureSuite::Ed448,
+        }
+    }
+
+    /// The raw public key: 32 bytes for Ed25519, the compressed SEC1 point for
+    /// P-256, 57 bytes for Ed448.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            PublicKey::Ed25519(k) => k.to_bytes().to_vec(),
+            PublicKey::EcdsaP256(k) => k.to_encoded_point(true).as_bytes().to_vec(),
+            #[cfg(feature = "ed448")]
+            PublicKey::Ed448(k) => k.clone(),
+        }
+    }
+
+    /// Checks a raw signature over `msg`; `reference` names the key in errors.
+    pub(crate) fn verify(
+        &self,
+        reference: &str,
+        msg: &[u8],
+        raw: &[u8],
+    ) -> Result<(), SignatureError> {
+        let malformed = |reason: &str| SignatureError::Malformed {
+            reason: reason.to_string(),
+        };
+        let ok = match self {
+            PublicKey::Ed25519(k) => {
+                let bytes: [u8; 64] = raw.try_into().map_err(|_| malformed("wrong length"))?;
+                ed25519_verify(
+                    &[msg],
+                    &[ed25519_dalek::Signature::from_bytes(&bytes)],
+                    &[*k],
+                )
+            }
+            PublicKey::EcdsaP256(k) => {
+                let sig = p256::ecdsa::Signature::from_slice(raw)
+                    .map_err(|_| malformed("not a P-256 r || s signature"))?;
+                if sig.normalize_s().is_some() {
+                    return Err(malformed("high-S ECDSA signature"));
+                }
+                k.verify(msg, &sig).is_ok()
+            }
+            #[cfg(feature = "ed448")]
+            PublicKey::Ed448(k) => {
+                if raw.len() != 114 {
+                    return Err(malformed("wrong length"));
+                }
+                openssl::pkey::PKey::public_key_from_raw_bytes(k, openssl::pkey::Id::ED448)
+                    .and_then(|pk| {
+                        openssl::sign::Verifier::new_without_digest(&pk)?.verify_oneshot(raw, msg)
+                    })
+                    .unwrap_or(false)
+            }
+        };
+        if !ok {
+            return Err(SignatureError::Invalid {
+                reference: reference.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks Ed25519 signatures, `msgs[i]` under `keys[i]`, with the cofactored
+/// batch equation; a single signature is a batch of one. Fails if any key or
+/// any `R` is of small order.
+pub(crate) fn ed25519_verify(
+    msgs: &[&[u8]],
+    sigs: &[ed25519_dalek::Signature],
+    keys: &[ed25519_dalek::VerifyingKey],
+) -> bool {
+    let small_order = |sig: &ed25519_dalek::Signature| {
+        curve25519_dalek::edwards::CompressedEdwardsY(*sig.r_bytes())
+            .decompress()
+            .is_none_or(|r| r.is_small_order())
+    };
+    if keys.iter().any(|k| k.is_weak()) || sigs.iter().any(small_order) {
+        return false;
+    }
+    ed25519_dalek::verify_batch(msgs, sigs, keys).is_ok()
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! The temporal policy shared by the Verifier and the Prover: how far the
+//! clocks of two parties may disagree, and the local ceilings on how long a
+//! hop window, a challenge and a whole lineage may last (§6.3).
+//!
+//! Skew is applied where a document's time meets a reader's `now`: a document
+//! issued up to `forward_skew` ahead of the reader's clock is already valid,
+//! and one is still valid up to `backward_skew` past its expiry. The window
+//! bounds are applied to the signed times alone, so they hold on every clock;
+//! each is the tighter of the local ceiling and the lineage's signed
+//! [`TemporalProfile`]. A check that fails names the [`TemporalBound`] it
+//! crossed and by how much.
+
+use crate::error::{TemporalBound, ValidityError};
+use crate::parse_rfc3339;
+use crate::types::TemporalProfile;
+use chrono::{DateTime, Duration, Utc};
+
+/// Temporal tolerances and bounds. Every bound is an inclusive maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemporalPolicy {
+    /// How far a document's `issuedAt` may lie ahead of the reader's clock.
+    pub forward_skew: Duration,
+    /// How far the reader's clock may lie past a document's `expiresAt` (or a
+    /// challenge's), and a successor's `issuedAt` before its predecessor's.
+    pub backward_skew: Duration,
+    /// `expiresAt - issuedAt` of a successor PCA, whatever its profile allows.
+    pub max_hop_window: Duration,
+    /// `expiresAt - issuedAt` of a PCA0, which bounds the whole lineage,
+    /// whatever its profile allows.
+    pub max_lineage_lifetime: Duration,
+    /// A PCA's challenge `expiresAt` less its `issuedAt`, whatever its profile
+    /// allows.
+    pub max_challenge_lifetime: Duration,
+}
+
+impl Default for TemporalPolicy {
+    /// No skew and no local ceilings: each lineage's signed profile governs.
+    fn default() -> TemporalPolicy {
+        TemporalPolicy {
+            forward_skew: Duration::zero(),
+            backward_skew: Duration::zero(),
+            max_hop_window: Duration::MAX,
+            max_lineage_lifetime: Duration::MAX,
+            max_challenge_lifetime: Duration::MAX,
+        }
+    }
+}
+
+impl TemporalPolicy {
+    /// The same windows, tolerating `skew` in both directions.
+    pub fn with_skew(mut self, skew: Duration) -> TemporalPolicy {
+        self.forward_skew = skew;
+        self.backward_skew = skew;
+        self
+    }
+
+    /// Checks that `now` falls inside `[issuedAt, expiresAt)`, widened by the
+    /// skew tolerances.
+    pub fn within(
+        &self,
+        issued_at: &str,
+        expires_at: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), ValidityError> {
+        let issued = parse_rfc3339(issued_at).map_err(ValidityError::MalformedTime)?;
+        let expires = parse_rfc3339(expires_at).map_err(ValidityError::MalformedTime)?;
+        if issued - now > self.forward_skew {
+            return Err(ValidityError::NotYetValid {
+                issued_at: issued_at.to_string(),
+                skew: self.forward_skew,
+                by: issued - now - self.forward_skew,
+            });
+        }
+        if now - expires >= self.backward_skew {
+            return Err(ValidityError::Expired {
+                expires_at: expires_at.to_string(),
+                skew: self.backward_skew,
+                by: now - expires - self.backward_skew,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that `[issuedAt, expiresAt)` is no longer than the window
+    /// `bound` names under `profile` and this policy's ceiling:
+    /// [`TemporalBound::HopWindow`], [`TemporalBound::ChallengeLifetime`]
+    /// (from the PCA's `issuedAt` to its challenge's `expiresAt`) or
+    /// [`TemporalBound::LineageLifetime`].
+    pub fn window(
+        &self,
+        bound: TemporalBound,
+        profile: &TemporalProfile,
+        issued_at: &str,
+        expires_at: &str,
+    ) -> Result<(), ValidityError> {
+        let issued = parse_rfc3339(issued_at).map_err(ValidityError::MalformedTime)?;
+        let expires = parse_rfc3339(expires_at).map_err(ValidityError::MalformedTime)?;
+        let max = match bound {
+            TemporalBound::LineageLifetime => {
+                profile.lineage_lifetime().min(self.max_lineage_lifetime)
+            }
+            TemporalBound::ChallengeLifetime => profile
+                .max_challenge_lifetime()
+                .min(self.max_challenge_lifetime),
+            _ => profile.max_hop_window().min(self.max_hop_window),
+        };
+        if expires - issued > max {
+            return Err(ValidityError::WindowTooLong {
+                bound,
+                max,
+                by: expires - issued - max,
+            });
+        }
+        Ok(())
+    }
+
+    /// The expiry a Prover stamps on a hop issued at `now`: the hop window of
+    /// `profile` and of this policy, clamped to the predecessor's expiry.
+    pub fn hop_expiry(
+        &self,
+        now: DateTime<Utc>,
+        profile: &TemporalProfile,
+        pred_expires: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        after(now, profile.max_hop_window().min(self.max_hop_window)).min(pred_expires)
+    }
+
+    /// The expiry a minter stamps on a PCA0 issued at `now`: the lineage
+    /// lifetime of `profile` and of this policy.
+    pub fn lineage_expiry(&self, now: DateTime<Utc>, profile: &TemporalProfile) -> DateTime<Utc> {
+        after(
+            now,
+            profile.lineage_lifetime().min(self.max_lineage_lifetime),
+        )
+    }
+
+    /// The expiry of the challenge a PCA issued at `now` emits: the challenge
+    /// lifetime of `profile` and of this policy, clamped to the PCA's own
+    /// lineage bound `expires`.
+    pub fn challenge_expiry(
+        &self,
+        now: DateTime<Utc>,
+        profile: &TemporalProfile,
+        expires: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        after(
+            now,
+            profile
+                .max_challenge_lifetime()
+                .min(self.max_challenge_lifetime),
+        )
+        .min(expires)
+    }
+}
+
+/// `at + d`, saturating at the latest representable instant.
+pub(crate) fn after(at: DateTime<Utc>, d: Duration) -> DateTime<Utc> {
+    at.checked_add_signed(d).unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
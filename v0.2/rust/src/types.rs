@@ -12,8 +12,13 @@
 //! a `skip_serializing_if`; time fields are RFC3339 `String`s emitted verbatim.
 
 use crate::crypto::canonical_json;
-use crate::digest_of;
+use crate::error::ContinuationError;
+use crate::untrusted::{from_untrusted_bytes, Limits};
+use crate::{digest_of, jcs, PicResult};
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 
 fn is_zero_i64(n: &i64) -> bool {
     *n == 0
@@ -24,7 +29,9 @@ fn is_zero_u64(n: &u64) -> bool {
 }
 
 /// The reference-profile execution contract: the constraints a successor
-/// executor must satisfy (Prover/Verifier spec §1.8, §4.2).
+/// executor must satisfy (Prover/Verifier spec §1.8, §4.2). Empty
+/// `allowed_regions` / `allowed_environments` leave the attested region /
+/// environment unconstrained.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionContract {
     #[serde(default, skip_serializing_if = "String::is_empty")]
@@ -37,15 +44,163 @@ pub struct ExecutionContract {
         skip_serializing_if = "String::is_empty"
     )]
     pub execution_model: String,
+    #[serde(
+        default,
+        rename = "allowedRegions",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_regions: Vec<String>,
+    #[serde(
+        default,
+        rename = "allowedEnvironments",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_environments: Vec<String>,
 }
 
 /// The signed, non-expansive state of a lineage: the authority that may continue
-/// (operations) and the contract executors must satisfy.
+/// (operations), the contract executors must satisfy, and the bounds on the
+/// quantities a request may declare, by name.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Invariants {
     pub operations: Vec<String>,
     #[serde(rename = "executionContract")]
     pub execution_contract: ExecutionContract,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub constraints: BTreeMap<String, Constraint>,
+}
+
+impl Invariants {
+    /// Adds the constraint `c` on the quantity `name`.
+    pub fn with_constraint(mut self, name: &str, c: Constraint) -> Invariants {
+        self.constraints.insert(name.to_string(), c);
+        self
+    }
+}
+
+/// A typed bound on one named quantity of a request. A successor keeps or
+/// tightens each bound (see [`crate::attenuates`]); a request must declare
+/// every constrained quantity within its bound (see [`crate::authorize`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Constraint {
+    /// A numeric upper bound, inclusive.
+    Max(u64),
+    /// An enumerated set of permitted values.
+    OneOf(BTreeSet<String>),
+    /// A window of UTC time of day.
+    TimeOfDay(TimeOfDay),
+}
+
+impl Constraint {
+    pub fn one_of(values: &[&str]) -> Constraint {
+        Constraint::OneOf(values.iter().map(|v| v.to_string()).collect())
+    }
+
+    /// Reports whether the declared quantity `q` is within this bound. A
+    /// quantity of the wrong type, or a malformed time, is not.
+    pub fn admits(&self, q: &Quantity) -> bool {
+        match (self, q) {
+            (Constraint::Max(max), Quantity::Number(n)) => n <= max,
+            (Constraint::OneOf(values), Quantity::Text(v)) => values.contains(v),
+            (Constraint::TimeOfDay(w), Quantity::Text(t)) => {
+                minute_of_day(t).is_some_and(|m| w.contains(m))
+            }
+            _ => false,
+        }
+    }
+
+    /// Reports whether this bound keeps or tightens `predecessor`: every
+    /// quantity it admits, `predecessor` admits too. Bounds of different
+    /// types never do.
+    pub fn within(&self, predecessor: &Constraint) -> bool {
+        match (self, predecessor) {
+            (Constraint::Max(cur), Constraint::Max(pred)) => cur <= pred,
+            (Constraint::OneOf(cur), Constraint::OneOf(pred)) => cur.is_subset(pred),
+            (Constraint::TimeOfDay(cur), Constraint::TimeOfDay(pred)) => {
+                cur.is_valid()
+                    && pred.is_valid()
+                    && (0..MINUTES_PER_DAY).all(|m| !cur.contains(m) || pred.contains(m))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Max(max) => write!(f, "max {max}"),
+            Constraint::OneOf(values) => {
+                let values: Vec<&str> = values.iter().map(String::as_str).collect();
+                write!(f, "one of [{}]", values.join(" "))
+            }
+            Constraint::TimeOfDay(w) => write!(f, "between {} and {} UTC", w.from, w.to),
+        }
+    }
+}
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// A window of UTC time of day, `HH:MM` from (inclusive) to (exclusive). A
+/// window whose `from` is later than its `to` wraps past midnight; equal
+/// bounds admit nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeOfDay {
+    pub from: String,
+    pub to: String,
+}
+
+impl TimeOfDay {
+    pub fn new(from: &str, to: &str) -> TimeOfDay {
+        TimeOfDay {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        minute_of_day(&self.from).is_some() && minute_of_day(&self.to).is_some()
+    }
+
+    /// Reports whether the window holds the minute of day `m`; a malformed
+    /// window holds none.
+    fn contains(&self, m: u32) -> bool {
+        match (minute_of_day(&self.from), minute_of_day(&self.to)) {
+            (Some(from), Some(to)) if from <= to => from <= m && m < to,
+            (Some(from), Some(to)) => m >= from || m < to,
+            _ => false,
+        }
+    }
+}
+
+/// Parses `HH:MM` into minutes since midnight.
+fn minute_of_day(t: &str) -> Option<u32> {
+    let (h, m) = t.split_once(':')?;
+    if h.len() != 2 || m.len() != 2 {
+        return None;
+    }
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+/// A quantity a request declares: a number for a [`Constraint::Max`], a value
+/// for a [`Constraint::OneOf`], or an `HH:MM` UTC time for a
+/// [`Constraint::TimeOfDay`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Quantity {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Quantity::Number(n) => write!(f, "{n}"),
+            Quantity::Text(t) => write!(f, "{t:?}"),
+        }
+    }
 }
 
 /// The freshness challenge a PCA emits for its next hop (§6.1).
@@ -57,6 +212,172 @@ pub struct Continuation {
     pub max_uses: i64,
     #[serde(rename = "expiresAt")]
     pub expires_at: String,
+    /// The successors allowed to answer the challenge; anyone conforming if
+    /// None.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<Audience>,
+}
+
+/// The successors a [`Continuation`] admits: executor DIDs, attested roles,
+/// or both. An executor is admitted if it matches either list; an empty
+/// audience admits no one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Audience {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
+
+impl Audience {
+    /// Admits exactly the executor `did`.
+    pub fn executor(did: &str) -> Audience {
+        Audience {
+            executors: vec![did.to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// Admits any executor attested in `role`.
+    pub fn role(role: &str) -> Audience {
+        Audience {
+            roles: vec![role.to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// Reports whether the executor `did`, attested in `role`, may continue.
+    pub fn admits(&self, did: &str, role: &str) -> bool {
+        self.executors.iter().any(|e| e == did)
+            || (!role.is_empty() && self.roles.iter().any(|r| r == role))
+    }
+}
+
+/// How many successors may answer a [`Continuation`] challenge (§6.1): a
+/// single-use challenge is consumed by its first successor, a multi-use one
+/// by up to `maxUses`. Any other `mode` is rejected, never read as unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContinuationMode {
+    #[default]
+    SingleUse,
+    MultiUse(u32),
+}
+
+impl ContinuationMode {
+    pub const SINGLE_USE: &'static str = "single-use";
+    pub const MULTI_USE: &'static str = "multi-use";
+
+    /// The mode a continuation declares, checked against its `maxUses`:
+    /// exactly 1 for single-use, at least 1 for multi-use.
+    pub fn of(c: &Continuation) -> Result<ContinuationMode, ContinuationError> {
+        let invalid = || ContinuationError::InvalidMaxUses {
+            mode: c.mode.clone(),
+            max_uses: c.max_uses,
+        };
+        match c.mode.as_str() {
+            Self::SINGLE_USE if c.max_uses == 1 => Ok(ContinuationMode::SingleUse),
+            Self::MULTI_USE if c.max_uses >= 1 => u32::try_from(c.max_uses)
+                .map(ContinuationMode::MultiUse)
+                .map_err(|_| invalid()),
+            Self::SINGLE_USE | Self::MULTI_USE => Err(invalid()),
+            _ => Err(ContinuationError::UnknownMode {
+                mode: c.mode.clone(),
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContinuationMode::SingleUse => Self::SINGLE_USE,
+            ContinuationMode::MultiUse(_) => Self::MULTI_USE,
+        }
+    }
+
+    /// How many successors may consume the challenge.
+    pub fn max_uses(&self) -> u32 {
+        match self {
+            ContinuationMode::SingleUse => 1,
+            ContinuationMode::MultiUse(n) => *n,
+        }
+    }
+
+    /// An unanswered continuation in this mode.
+    pub(crate) fn continuation(&self, challenge: String, expires_at: String) -> Continuation {
+        Continuation {
+            challenge,
+            mode: self.name().to_string(),
+            max_uses: i64::from(self.max_uses()),
+            expires_at,
+            audience: None,
+        }
+    }
+}
+
+/// The signed temporal bounds of a lineage (§6.3), in whole seconds: how long
+/// one hop may last, how long an emitted challenge may stay open, and how long
+/// the lineage lives from PCA0. A successor may only tighten them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemporalProfile {
+    #[serde(rename = "maxHopWindowSeconds")]
+    pub max_hop_window_seconds: u64,
+    #[serde(rename = "maxChallengeLifetimeSeconds")]
+    pub max_challenge_lifetime_seconds: u64,
+    #[serde(rename = "lineageLifetimeSeconds")]
+    pub lineage_lifetime_seconds: u64,
+}
+
+impl Default for TemporalProfile {
+    /// The illustrative profile: five-minute hops and challenges, a 24-hour
+    /// lineage.
+    fn default() -> TemporalProfile {
+        TemporalProfile {
+            max_hop_window_seconds: 5 * 60,
+            max_challenge_lifetime_seconds: 5 * 60,
+            lineage_lifetime_seconds: 24 * 60 * 60,
+        }
+    }
+}
+
+impl TemporalProfile {
+    /// The profile a PCA is held to: its own, or the default.
+    pub fn of(p: &Pca) -> TemporalProfile {
+        p.temporal_profile.unwrap_or_default()
+    }
+
+    pub fn max_hop_window(&self) -> Duration {
+        seconds(self.max_hop_window_seconds)
+    }
+
+    pub fn max_challenge_lifetime(&self) -> Duration {
+        seconds(self.max_challenge_lifetime_seconds)
+    }
+
+    pub fn lineage_lifetime(&self) -> Duration {
+        seconds(self.lineage_lifetime_seconds)
+    }
+
+    /// The tighter of two profiles, bound by bound.
+    pub fn meet(&self, other: &TemporalProfile) -> TemporalProfile {
+        TemporalProfile {
+            max_hop_window_seconds: self
+                .max_hop_window_seconds
+                .min(other.max_hop_window_seconds),
+            max_challenge_lifetime_seconds: self
+                .max_challenge_lifetime_seconds
+                .min(other.max_challenge_lifetime_seconds),
+            lineage_lifetime_seconds: self
+                .lineage_lifetime_seconds
+                .min(other.lineage_lifetime_seconds),
+        }
+    }
+}
+
+/// `n` seconds, saturating at the longest representable duration.
+fn seconds(n: u64) -> Duration {
+    i64::try_from(n)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .unwrap_or(Duration::MAX)
 }
 
 /// The attested attributes checked against an execution contract by the
@@ -95,6 +416,12 @@ pub struct Attestation {
 }
 
 impl Attestation {
+    /// The content-addressed id of the complete, signed attestation: the value
+    /// a PoR carries in `executorAttestationDigest` (see [`crate::attestation`]).
+    pub fn digest(&self) -> String {
+        digest_of(self)
+    }
+
     /// The canonical bytes the issuer signature covers: the attestation without
     /// its own proof.
     pub fn signing_bytes(&self) -> Vec<u8> {
@@ -102,7 +429,7 @@ impl Attestation {
         if let Some(obj) = value.as_object_mut() {
             obj.remove("proof");
         }
-        serde_json::to_vec(&value).expect("attestation to_vec")
+        jcs::to_vec(&value)
     }
 }
 
@@ -126,6 +453,10 @@ pub struct Request {
         skip_serializing_if = "String::is_empty"
     )]
     pub payload_digest: String,
+    /// The quantities this request declares, checked against the
+    /// constraints of the authority it runs under.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub quantities: BTreeMap<String, Quantity>,
 
     // Sandboxed Execution profile (outer ENFORCE PCA only).
     #[serde(
@@ -181,12 +512,33 @@ pub struct Por {
     pub continuation_response: ContinuationResponse,
     pub executor: String,
     pub request: Request,
-    #[serde(rename = "executorAttestation")]
-    pub executor_attestation: Attestation,
+    /// The executor attestation embedded in full; None in the reference form.
+    #[serde(
+        default,
+        rename = "executorAttestation",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub executor_attestation: Option<Attestation>,
+    /// The reference form: the content digest of the executor attestation,
+    /// resolved by the Verifier (see [`crate::attestation`]).
+    #[serde(
+        default,
+        rename = "executorAttestationDigest",
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub executor_attestation_digest: String,
+    /// Set only on a branch-creation transition: the fresh nonce the child
+    /// `branchId` is derived from (see [`crate::derive_branch_id`]).
+    #[serde(
+        default,
+        rename = "branchNonce",
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub branch_nonce: String,
 }
 
 /// A single detached signature covering a document as a whole.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Proof {
     #[serde(rename = "type")]
     pub type_: String,
@@ -220,6 +572,15 @@ pub struct Pca {
         skip_serializing_if = "String::is_empty"
     )]
     pub origin_issuer: String,
+    /// The authority profile the lineage was minted under, carried unchanged
+    /// on every hop; empty is the reference profile (see
+    /// [`crate::authority::profile_of`]).
+    #[serde(
+        default,
+        rename = "authorityProfile",
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub authority_profile: String,
 
     // Origin (PCA0) only.
     #[serde(default, skip_serializing_if = "String::is_empty")]
@@ -241,6 +602,14 @@ pub struct Pca {
 
     pub invariants: Invariants,
     pub continuation: Continuation,
+    /// The lineage's temporal profile, set on PCA0 and carried, equal or
+    /// tighter, by every successor; None follows the default profile.
+    #[serde(
+        default,
+        rename = "temporalProfile",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub temporal_profile: Option<TemporalProfile>,
 
     /// The signed Sandboxed Execution profile field carried by an outer ENFORCE
     /// PCA (§2.4): the inner Multi-Lineage Execution being governed. Covered by
@@ -281,7 +650,13 @@ impl Pca {
         if let Some(obj) = value.as_object_mut() {
             obj.remove("proof");
         }
-        serde_json::to_vec(&value).expect("pca to_vec")
+        jcs::to_vec(&value)
+    }
+
+    /// Decodes a JSON PCA received from an untrusted party, enforcing `limits`
+    /// (see [`crate::untrusted`]).
+    pub fn from_untrusted_bytes(bytes: &[u8], limits: &Limits) -> PicResult<Pca> {
+        from_untrusted_bytes(bytes, limits)
     }
 }
 
@@ -300,7 +675,13 @@ impl Envelope {
         if let Some(obj) = value.as_object_mut() {
             obj.remove("proof");
         }
-        serde_json::to_vec(&value).expect("envelope to_vec")
+        jcs::to_vec(&value)
+    }
+
+    /// Decodes a JSON envelope received from an untrusted party, enforcing
+    /// `limits` (see [`crate::untrusted`]).
+    pub fn from_untrusted_bytes(bytes: &[u8], limits: &Limits) -> PicResult<Envelope> {
+        from_untrusted_bytes(bytes, limits)
     }
 }
 
@@ -315,6 +696,9 @@ pub struct EnvelopeBody {
     pub current: Option<Pca>,
     #[serde(rename = "currentDigest")]
     pub current_digest: String,
+    /// Side table of the attestations referenced by digest from `current`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attestations: Vec<Attestation>,
 }
 
 /// A signed attestation from a trusted issuer that a lineage's chain is valid up
@@ -342,12 +726,12 @@ impl Snapshot {
         if let Some(obj) = value.as_object_mut() {
             obj.remove("proof");
         }
-        serde_json::to_vec(&value).expect("snapshot to_vec")
+        jcs::to_vec(&value)
     }
 }
 
 /// One native causal cutoff. Only the fields relevant to its strategy are set.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Revocation {
     pub strategy: String,
     #[serde(
@@ -364,6 +748,50 @@ pub struct Revocation {
     pub from_counter: u64,
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub issuer: String,
+    /// The issuer's signature; required of a GRANT revocation, which only the
+    /// grantor may issue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Proof>,
+}
+
+impl Revocation {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut value = serde_json::to_value(self).expect("revocation to_value");
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("proof");
+        }
+        jcs::to_vec(&value)
+    }
+}
+
+/// A signed grant: the maximal authority `grantor` delegates to the `grantee`
+/// principal, which PCA0s issued by the grantee reference by `grantId`. The
+/// semantic scopes it binds inform guardrail policy (see
+/// [`crate::grant::GrantRegistry`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grant {
+    pub id: String,
+    pub grantor: String,
+    pub grantee: String,
+    pub invariants: Invariants,
+    #[serde(rename = "issuedAt")]
+    pub issued_at: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Proof>,
+}
+
+impl Grant {
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut value = serde_json::to_value(self).expect("grant to_value");
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("proof");
+        }
+        jcs::to_vec(&value)
+    }
 }
 
 /// The profile-defined canonical projection of a PCA0 that excludes `lineageId`
@@ -378,6 +806,13 @@ pub(crate) struct OriginCore<'a> {
     #[serde(rename = "grantId")]
     pub grant_id: &'a str,
     pub invariants: &'a Invariants,
+    /// Omitted when absent, so lineages minted without a profile keep their
+    /// identity.
+    #[serde(rename = "temporalProfile", skip_serializing_if = "Option::is_none")]
+    pub temporal_profile: Option<&'a TemporalProfile>,
+    /// Omitted when empty (the reference profile), likewise.
+    #[serde(rename = "authorityProfile", skip_serializing_if = "str::is_empty")]
+    pub authority_profile: &'a str,
     #[serde(rename = "issuedAt")]
     pub issued_at: &'a str,
     #[serde(rename = "expiresAt")]
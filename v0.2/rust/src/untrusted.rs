@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Hardened decoding of untrusted PIC artifacts.
+//!
+//! A verifying service decodes attacker-supplied bytes before any signature
+//! has been checked, so decoding must be cheap to reject and must never panic.
+//! The input size is bounded before parsing; nesting depth, chain lengths,
+//! carried-lineage counts and string lengths are bounded before a document is
+//! materialized; in strict mode a field the profile does not define is an
+//! error rather than silently dropped from the signed bytes; and a number the
+//! canonical form would round (beyond ±2^53) is refused, since the signature
+//! would not pin it.
+//!
+//! [`from_untrusted_bytes`] covers the JSON encoding; COSE_Sign1 input goes
+//! through [`crate::Verifier::decode`], which applies the Verifier's limits to
+//! the sealed payload the same way.
+
+use crate::error::{CodecError, Limit};
+use crate::jcs;
+use crate::{PicError, PicResult};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// The resource bounds applied to an untrusted document. Every bound is an
+/// inclusive maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Encoded size in bytes.
+    pub max_bytes: usize,
+    /// Nesting depth of objects and arrays.
+    pub max_depth: usize,
+    /// PCAs in one chain: a carried lineage, or a chain handed to the Verifier.
+    pub max_chain_len: usize,
+    /// Carried lineages in one `multiLineage`.
+    pub max_carried_lineages: usize,
+    /// Length in bytes of any string, member names included.
+    pub max_string_len: usize,
+    /// Reject fields the profile does not define.
+    pub strict: bool,
+}
+
+impl Default for Limits {
+    /// Generous for every document this profile produces (an envelope around
+    /// an ENFORCE PCA nests about a dozen levels), strict on unknown fields.
+    fn default() -> Limits {
+        Limits {
+            max_bytes: 1 << 20,
+            max_depth: 32,
+            max_chain_len: 128,
+            max_carried_lineages: 16,
+            max_string_len: 4096,
+            strict: true,
+        }
+    }
+}
+
+/// Decodes a JSON-encoded document received from an untrusted party,
+/// enforcing `limits`. No input makes it panic.
+pub fn from_untrusted_bytes<T: DeserializeOwned>(bytes: &[u8], limits: &Limits) -> PicResult<T> {
+    let json = |reason: String| CodecError::Json { reason };
+    check_size(bytes.len(), limits).map_err(PicError::Codec)?;
+    let value: Value =
+        serde_json::from_slice(bytes).map_err(|e| PicError::Codec(json(e.to_string())))?;
+    admit(value, limits, json).map_err(PicError::Codec)
+}
+
+pub(crate) fn check_size(len: usize, limits: &Limits) -> Result<(), CodecError> {
+    bounded(Limit::Bytes, limits.max_bytes, len)
+}
+
+/// Checks a parsed document against `limits` and materializes it; `malformed`
+/// wraps a data-model error in the caller's encoding.
+pub(crate) fn admit<T: DeserializeOwned>(
+    value: Value,
+    limits: &Limits,
+    malformed: fn(String) -> CodecError,
+) -> Result<T, CodecError> {
+    check(&value, limits, 0)?;
+    if !limits.strict {
+        return serde_json::from_value(value).map_err(|e| malformed(e.to_string()));
+    }
+    let mut unknown = None;
+    let doc = serde_ignored::deserialize(value, |path| {
+        // Report the JSON member path; `?` marks an `Option` layer, not a member.
+        unknown.get_or_insert_with(|| {
+            let path = path.to_string();
+            path.split('.')
+                .filter(|s| *s != "?")
+                .collect::<Vec<_>>()
+                .join(".")
+        });
+    })
+    .map_err(|e| malformed(e.to_string()))?;
+    match unknown {
+        Some(path) => Err(CodecError::UnknownField { path }),
+        None => Ok(doc),
+    }
+}
+
+fn bounded(limit: Limit, max: usize, found: usize) -> Result<(), CodecError> {
+    if found > max {
+        return Err(CodecError::Limit { limit, max, found });
+    }
+    Ok(())
+}
+
+fn check(v: &Value, limits: &Limits, depth: usize) -> Result<(), CodecError> {
+    match v {
+        Value::String(s) => bounded(Limit::StringLength, limits.max_string_len, s.len()),
+        Value::Array(items) => {
+            bounded(Limit::Depth, limits.max_depth, depth + 1)?;
+            items.iter().try_for_each(|i| check(i, limits, depth + 1))
+        }
+        Value::Object(members) => {
+            bounded(Limit::Depth, limits.max_depth, depth + 1)?;
+            for (name, item) in members {
+                bounded(Limit::StringLength, limits.max_string_len, name.len())?;
+                let count = item.as_array().map_or(0, Vec::len);
+                match name.as_str() {
+                    "chain" => bounded(Limit::ChainLength, limits.max_chain_len, count)?,
+                    "carriedLineages" => {
+                        bounded(Limit::CarriedLineages, limits.max_carried_lineages, count)?
+                    }
+                    _ => {}
+                }
+                check(item, limits, depth + 1)?;
+            }
+            Ok(())
+        }
+        Value::Number(_) => jcs::exact_numbers(v),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,451 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Cross-implementation test vectors: a versioned JSON [`Corpus`] of chains
+//! built from the fixture cast with a [`SeededEntropy`] and a [`FixedClock`],
+//! with the exact bytes, digests and coordinates they must produce and the
+//! verdict a Verifier must reach. Any implementation that holds the fixture
+//! keys can rebuild each chain from its inputs and check it against the
+//! expected values; [`replay`] does so for this one. The corpus is committed
+//! as `v0.2/fixtures/vectors/corpus.json` (`pic-vectors` writes and checks it).
+//!
+//! A vector is built as follows:
+//!
+//! 1. A non-empty `inputs.origin.grantId` names a grant org-authority signs
+//!    for this origin: the origin identity is the grantee, the origin
+//!    invariants the granted ones, valid for a day from `inputs.issuedAt`.
+//!    The grant itself is not part of the vector.
+//! 2. The entropy stream is seeded with the UTF-8 bytes of `inputs.seed`
+//!    (see [`crate::sources`]). The origin draws its nonce, then its
+//!    challenge (32 bytes each); every hop then draws its executor nonce,
+//!    then its challenge, in order.
+//! 3. The clock reads `inputs.issuedAt` for the origin and advances one
+//!    second before each hop.
+//! 4. A hop with `unchecked` skips the Prover self-check (a buggy or
+//!    compromised executor).
+//! 5. `inputs.tamper`, if set, replaces the member at the JSON Pointer
+//!    `pointer` of the signed PCA at index `pca` with `value`.
+//! 6. The chain is fully verified at `inputs.verifyAt` against the public
+//!    keys of the cast, without revocations.
+//!
+//! Each PCA's `bytes` are the base64url of its JCS encoding, proof included,
+//! and `digest` is [`Pca::digest`].
+
+use crate::crypto::{b64_decode, b64_encode, canonical_json, Registry};
+use crate::fixtureset::Set;
+use crate::prover::{mint_pca0_with, sign_grant, OriginOptions, Prover};
+use crate::sources::{Clock, FixedClock, SeededEntropy};
+use crate::types::{ExecutionContract, Grant, Invariants, Pca, Request};
+use crate::verifier::Verifier;
+use crate::{parse_rfc3339, rfc3339, PicError, PicResult};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// The corpus format version.
+pub const CORPUS_VERSION: u32 = 1;
+
+/// A versioned set of test vectors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Corpus {
+    pub version: u32,
+    pub description: String,
+    pub vectors: Vec<Vector>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vector {
+    pub name: String,
+    pub description: String,
+    pub inputs: Inputs,
+    pub expected: Expected,
+}
+
+/// What a vector's chain is built from; identities and attestations are
+/// fixture names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inputs {
+    pub seed: String,
+    pub issued_at: String,
+    pub origin: OriginInput,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hops: Vec<HopInput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tamper: Option<Tamper>,
+    pub verify_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginInput {
+    pub identity: String,
+    pub invariants: Value,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub grant_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HopInput {
+    pub identity: String,
+    pub attestation: String,
+    pub invariants: Value,
+    pub request: Value,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unchecked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tamper {
+    pub pca: usize,
+    pub pointer: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Expected {
+    pub pcas: Vec<ExpectedPca>,
+    pub lineage_id: String,
+    pub branch_id: String,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedPca {
+    pub bytes: String,
+    pub digest: String,
+}
+
+/// The outcome of full-chain verification: the stable error code and the
+/// hop it names when the chain is rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verdict {
+    pub valid: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hop: Option<usize>,
+}
+
+/// One value [`replay`] found different from the corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub vector: String,
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: expected {}, got {}",
+            self.vector, self.field, self.expected, self.actual
+        )
+    }
+}
+
+fn input_error(e: impl fmt::Display) -> PicError {
+    PicError::Fixture(format!("vector input: {e}"))
+}
+
+/// Builds the chain of `inputs` from the fixture cast (steps 1–5 above).
+pub fn build(inputs: &Inputs, set: &Set) -> PicResult<Vec<Pca>> {
+    let entropy = SeededEntropy::new(inputs.seed.as_bytes());
+    let clock = FixedClock::new(parse_rfc3339(&inputs.issued_at).map_err(input_error)?);
+    let invariants = |v: &Value| serde_json::from_value::<Invariants>(v.clone());
+    let origin = set.identity(&inputs.origin.identity);
+    let origin_invariants = invariants(&inputs.origin.invariants).map_err(input_error)?;
+    let grant = match inputs.origin.grant_id.as_str() {
+        "" => None,
+        id => Some(sign_grant(
+            Grant {
+                id: id.to_string(),
+                grantee: origin.id.clone(),
+                invariants: origin_invariants.clone(),
+                issued_at: inputs.issued_at.clone(),
+                expires_at: rfc3339(clock.now() + Duration::days(1)),
+                ..Default::default()
+            },
+            set.identity("org-authority"),
+        )?),
+    };
+    let mut chain = vec![mint_pca0_with(
+        origin,
+        origin_invariants,
+        grant.as_ref(),
+        clock.now(),
+        &OriginOptions {
+            entropy: &entropy,
+            ..Default::default()
+        },
+    )?];
+    for hop in &inputs.hops {
+        clock.advance(Duration::seconds(1));
+        let prover = Prover::new(
+            set.identity(&hop.identity),
+            set.attestation(&hop.attestation),
+        )
+        .with_entropy(&entropy);
+        let inv = invariants(&hop.invariants).map_err(input_error)?;
+        let req: Request = serde_json::from_value(hop.request.clone()).map_err(input_error)?;
+        let pred = chain.last().expect("chain has an origin");
+        let next = match hop.unchecked {
+            true => prover.continue_malicious(pred, inv, req, clock.now())?,
+            false => prover.continue_(pred, inv, req, clock.now())?,
+        };
+        chain.push(next);
+    }
+    if let Some(t) = &inputs.tamper {
+        let pca = chain
+            .get_mut(t.pca)
+            .ok_or_else(|| input_error(format!("no PCA {}", t.pca)))?;
+        let mut doc = serde_json::to_value(&*pca).expect("pca to_value");
+        *doc.pointer_mut(&t.pointer)
+            .ok_or_else(|| input_error(format!("no member {}", t.pointer)))? = t.value.clone();
+        *pca = serde_json::from_value(doc).map_err(input_error)?;
+    }
+    Ok(chain)
+}
+
+/// Fully verifies `chain` at `verify_at` (step 6 above).
+pub fn verdict(chain: &[Pca], reg: &Registry, verify_at: &str) -> PicResult<Verdict> {
+    let at = parse_rfc3339(verify_at).map_err(input_error)?;
+    Ok(
+        match Verifier::new(reg, None).verify_full_chain(chain, at) {
+            Ok(_) => Verdict {
+                valid: true,
+                code: String::new(),
+                hop: None,
+            },
+            Err(e) => Verdict {
+                valid: false,
+                code: e.code().to_string(),
+                hop: e.hop(),
+            },
+        },
+    )
+}
+
+/// The expected values of `chain` and its verdict.
+pub fn expected(chain: &[Pca], verdict: Verdict) -> Expected {
+    Expected {
+        pcas: chain
+            .iter()
+            .map(|p| ExpectedPca {
+                bytes: b64_encode(&canonical_json(p)),
+                digest: p.digest(),
+            })
+            .collect(),
+        lineage_id: chain[0].lineage_id.clone(),
+        branch_id: chain[0].branch_id.clone(),
+        verdict,
+    }
+}
+
+/// Builds the corpus from its built-in inputs.
+pub fn generate(set: &Set) -> PicResult<Corpus> {
+    let vectors = cases()
+        .into_iter()
+        .map(|(name, description, inputs)| {
+            let chain = build(&inputs, set)?;
+            let verdict = verdict(&chain, &set.registry, &inputs.verify_at)?;
+            Ok(Vector {
+                name: name.to_string(),
+                description: description.to_string(),
+                expected: expected(&chain, verdict),
+                inputs,
+            })
+        })
+        .collect::<PicResult<_>>()?;
+    Ok(Corpus {
+        version: CORPUS_VERSION,
+        description: "PIC v0.2 test vectors over the v0.2/fixtures cast; \
+                      see the pic::vectors documentation for how each chain is built."
+            .to_string(),
+        vectors,
+    })
+}
+
+/// Rebuilds and re-verifies every vector of `corpus`, and verifies the
+/// expected PCAs as given, returning every value that differs. An empty
+/// result means this implementation agrees with the corpus.
+pub fn replay(corpus: &Corpus, set: &Set) -> Vec<Divergence> {
+    let mut out = Vec::new();
+    if corpus.version != CORPUS_VERSION {
+        out.push(Divergence {
+            vector: String::new(),
+            field: "version".to_string(),
+            expected: CORPUS_VERSION.to_string(),
+            actual: corpus.version.to_string(),
+        });
+        return out;
+    }
+    for v in &corpus.vectors {
+        let mut diverge = |field: &str, expected: &dyn fmt::Debug, actual: &dyn fmt::Debug| {
+            out.push(Divergence {
+                vector: v.name.clone(),
+                field: field.to_string(),
+                expected: format!("{expected:?}"),
+                actual: format!("{actual:?}"),
+            })
+        };
+
+        // The expected PCAs, as another implementation produced them, must
+        // reach the expected verdict here.
+        let given: Result<Vec<Pca>, String> = v
+            .expected
+            .pcas
+            .iter()
+            .map(|p| {
+                let raw = b64_decode(&p.bytes).map_err(|e| e.to_string())?;
+                serde_json::from_slice(&raw).map_err(|e| e.to_string())
+            })
+            .collect();
+        match given.map(|chain| verdict(&chain, &set.registry, &v.inputs.verify_at)) {
+            Ok(Ok(got)) if got == v.expected.verdict => {}
+            Ok(Ok(got)) => diverge("verdict", &v.expected.verdict, &got),
+            Ok(Err(e)) => diverge("verdict", &v.expected.verdict, &e.to_string()),
+            Err(e) => diverge("pcas", &"decodable PCAs", &e),
+        }
+
+        // Rebuilding from the inputs must reproduce them byte for byte.
+        let chain = match build(&v.inputs, set) {
+            Ok(chain) => chain,
+            Err(e) => {
+                diverge("build", &"a chain", &e.to_string());
+                continue;
+            }
+        };
+        let got = expected(&chain, v.expected.verdict.clone());
+        if got.pcas.len() != v.expected.pcas.len() {
+            diverge("pcas.len", &v.expected.pcas.len(), &got.pcas.len());
+        }
+        for (i, (want, have)) in v.expected.pcas.iter().zip(&got.pcas).enumerate() {
+            if want.bytes != have.bytes {
+                diverge(&format!("pcas[{i}].bytes"), &want.bytes, &have.bytes);
+            }
+            if want.digest != have.digest {
+                diverge(&format!("pcas[{i}].digest"), &want.digest, &have.digest);
+            }
+        }
+        if got.lineage_id != v.expected.lineage_id {
+            diverge("lineageId", &v.expected.lineage_id, &got.lineage_id);
+        }
+        if got.branch_id != v.expected.branch_id {
+            diverge("branchId", &v.expected.branch_id, &got.branch_id);
+        }
+    }
+    out
+}
+
+fn inv(operations: &[&str]) -> Value {
+    serde_json::to_value(Invariants {
+        operations: operations.iter().map(|s| s.to_string()).collect(),
+        execution_contract: ExecutionContract::default(),
+        ..Default::default()
+    })
+    .expect("invariants to_value")
+}
+
+fn read(target: &str) -> Value {
+    serde_json::to_value(Request {
+        operation: "read".to_string(),
+        target: target.to_string(),
+        security_domain: "tenant-42".to_string(),
+        ..Default::default()
+    })
+    .expect("request to_value")
+}
+
+/// The built-in vector inputs.
+fn cases() -> Vec<(&'static str, &'static str, Inputs)> {
+    const ISSUED: &str = "2026-06-01T12:00:00Z";
+    const CHECKED: &str = "2026-06-01T12:01:00Z";
+    let hop = |identity: &str, operations: &[&str]| HopInput {
+        identity: identity.to_string(),
+        attestation: identity.to_string(),
+        invariants: inv(operations),
+        request: read("/user/report.txt"),
+        unchecked: false,
+    };
+    let two_hops = Inputs {
+        seed: "two-hops".to_string(),
+        issued_at: ISSUED.to_string(),
+        origin: OriginInput {
+            identity: "alice".to_string(),
+            invariants: inv(&["read:/user/*", "write:/user/*"]),
+            grant_id: String::new(),
+        },
+        hops: vec![
+            hop("gateway", &["read:/user/*"]),
+            hop("storage-service", &["read:/user/*"]),
+        ],
+        tamper: None,
+        verify_at: CHECKED.to_string(),
+    };
+    vec![
+        (
+            "origin",
+            "A lone PCA0 with a grant.",
+            Inputs {
+                seed: "origin".to_string(),
+                hops: Vec::new(),
+                origin: OriginInput {
+                    grant_id: "grant-42".to_string(),
+                    ..two_hops.origin.clone()
+                },
+                ..two_hops.clone()
+            },
+        ),
+        (
+            "two-hops",
+            "PCA0 by alice continued by the gateway and the storage service, attenuating to read.",
+            two_hops.clone(),
+        ),
+        (
+            "expired",
+            "The two-hop chain verified after its hops have expired.",
+            Inputs {
+                verify_at: "2026-06-02T13:00:00Z".to_string(),
+                ..two_hops.clone()
+            },
+        ),
+        (
+            "tampered",
+            "The first hop's operations widened after it was signed.",
+            Inputs {
+                seed: "tampered".to_string(),
+                tamper: Some(Tamper {
+                    pca: 1,
+                    pointer: "/invariants/operations/0".to_string(),
+                    value: "write:/user/*".into(),
+                }),
+                ..two_hops.clone()
+            },
+        ),
+        (
+            "expansive",
+            "A hop that skips its self-check and claims authority its predecessor lacks.",
+            Inputs {
+                seed: "expansive".to_string(),
+                hops: vec![HopInput {
+                    unchecked: true,
+                    ..hop("gateway", &["read:/user/*", "read:/sys/*"])
+                }],
+                ..two_hops
+            },
+        ),
+    ]
+}
@@ -4,24 +4,54 @@
 // Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
 
 //! The Verifier: origin validation, ordered per-hop checks (§3.3), full-chain and
-//! envelope validation, plus the revocation-coordinate continuity of the
-//! Revocation spec §2.3.
-
-use crate::authority::{attenuates, conforms};
-use crate::crypto::Registry;
-use crate::revocation::{derive_lineage_id, root_branch_id};
-use crate::types::{Envelope, Invariants, Pca, Por};
-use crate::{parse_rfc3339, PicResult, RevocationStore, REVOCABLE_PROFILE};
+//! envelope validation over either wire encoding, plus the revocation-coordinate
+//! continuity of the Revocation spec §2.3.
+
+use crate::attestation::AttestationCache;
+use crate::authority::{profile_of, AuthorityProfile, ReferenceProfile};
+use crate::codec::{self, Document};
+use crate::crypto::{hash_parts, Registry};
+use crate::error::{
+    AttestationError, BindingError, Checkpoint, CodecError, ContinuationError, CoordinateError,
+    EnvelopeError, GrantError, IntegrityError, Limit, OriginError, SignatureError, TemporalBound,
+    TemporalError,
+};
+use crate::grant::{self, GrantRegistry};
+use crate::revocation::{derive_branch_id, derive_lineage_id, root_branch_id};
+use crate::sources::{Clock, SystemClock};
+use crate::suite::SignatureSuite;
+use crate::temporal::TemporalPolicy;
+use crate::types::{
+    Attestation, ContinuationMode, Envelope, Invariants, Pca, Por, Proof, TemporalProfile,
+};
+use crate::untrusted::Limits;
+use crate::{parse_rfc3339, PicError, PicResult, RevocationStore, REVOCABLE_PROFILE};
 use chrono::{DateTime, Utc};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Validates PCAs before any authority is exercised (Prover/Verifier spec §3). It
 /// resolves keys through a Registry, consults an optional RevocationStore, and
-/// keeps per-Verifier state for single-use challenges.
+/// keeps per-Verifier counts of consumed challenges. Documents it decodes
+/// from bytes are held to `limits`; times are judged under `temporal`, and
+/// live acceptance reads `now` from `clock`. With `grants`, a PCA0's `grantId`
+/// must name a trusted grant that admits it. Hops are checked under
+/// `authority`, and only lineages minted under it are accepted.
 pub struct Verifier<'a> {
     pub registry: &'a Registry,
     pub revocations: Option<&'a RevocationStore>,
-    used: HashSet<String>,
+    /// Resolves attestations a PoR references by digest when the document at
+    /// hand carries no side table entry for them.
+    pub attestations: Option<&'a AttestationCache>,
+    pub grants: Option<&'a GrantRegistry>,
+    pub limits: Limits,
+    pub temporal: TemporalPolicy,
+    pub clock: &'a dyn Clock,
+    pub authority: &'a dyn AuthorityProfile,
+    /// Consumptions per challenge, bounded by its mode's `maxUses`.
+    uses: HashMap<String, u32>,
+    /// Signature checks already passed in a batch (see
+    /// [`Verifier::verify_full_chain_batched`]), keyed by [`batch_key`].
+    batched: HashSet<String>,
 }
 
 impl<'a> Verifier<'a> {
@@ -31,60 +61,123 @@ impl<'a> Verifier<'a> {
         Verifier {
             registry,
             revocations,
-            used: HashSet::new(),
+            attestations: None,
+            grants: None,
+            limits: Limits::default(),
+            temporal: TemporalPolicy::default(),
+            clock: &SystemClock,
+            authority: &ReferenceProfile::DEFAULT,
+            uses: HashMap::new(),
+            batched: HashSet::new(),
         }
     }
 
+    /// Judges times under `temporal`.
+    pub fn with_temporal(mut self, temporal: TemporalPolicy) -> Verifier<'a> {
+        self.temporal = temporal;
+        self
+    }
+
+    /// Checks every PCA0 grant against `grants`.
+    pub fn with_grants(mut self, grants: &'a GrantRegistry) -> Verifier<'a> {
+        self.grants = Some(grants);
+        self
+    }
+
+    /// Reads the instant of live acceptance from `clock`.
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Verifier<'a> {
+        self.clock = clock;
+        self
+    }
+
+    /// Checks hops under `authority` and accepts only lineages minted under it.
+    pub fn with_authority(mut self, authority: &'a dyn AuthorityProfile) -> Verifier<'a> {
+        self.authority = authority;
+        self
+    }
+
     /// Validates a PCA0 (§3.2) and the revocation-coordinate derivation
     /// (Revocation spec §2.1, §2.4).
     pub fn verify_origin(&self, p: &Pca, now: DateTime<Utc>) -> PicResult<()> {
         if !p.is_origin() {
-            return Err("origin validation: PCA carries a Proof of Relationship".to_string());
+            return Err(PicError::Origin(OriginError::HasProofOfRelationship));
         }
         let proof = p
             .proof
             .as_ref()
-            .ok_or("origin validation: missing signature")?;
+            .ok_or(PicError::Origin(OriginError::MissingSignature))?;
         let msg = p.signing_bytes();
-        self.registry
-            .verify(&p.issuer, &msg, &proof.signature)
-            .map_err(|e| format!("origin validation: {e}"))?;
-        within_validity(&p.issued_at, &p.expires_at, now)
-            .map_err(|e| format!("origin validation: {e}"))?;
+        self.check_signature(&p.issuer, &msg, proof, signed_at(&p.issued_at, now))
+            .map_err(|e| PicError::Origin(OriginError::Signature(e)))?;
+        self.temporal
+            .within(&p.issued_at, &p.expires_at, now)
+            .and_then(|()| {
+                let profile = TemporalProfile::of(p);
+                self.temporal.window(
+                    TemporalBound::LineageLifetime,
+                    &profile,
+                    &p.issued_at,
+                    &p.expires_at,
+                )?;
+                self.temporal.window(
+                    TemporalBound::ChallengeLifetime,
+                    &profile,
+                    &p.issued_at,
+                    &p.continuation.expires_at,
+                )
+            })
+            .map_err(|e| PicError::Origin(OriginError::Validity(e)))?;
         if p.profile != REVOCABLE_PROFILE {
-            return Err(format!(
-                "origin validation: unknown profile {:?}",
-                p.profile
-            ));
+            return Err(PicError::Origin(OriginError::UnknownProfile {
+                profile: p.profile.clone(),
+            }));
         }
+        if profile_of(p) != self.authority.id() {
+            return Err(PicError::Origin(OriginError::UnknownAuthorityProfile {
+                profile: profile_of(p).to_string(),
+                implemented: self.authority.id().to_string(),
+            }));
+        }
+        ContinuationMode::of(&p.continuation)
+            .map_err(|e| PicError::Origin(OriginError::Continuation(e)))?;
         if p.lineage_counter != 0 {
-            return Err(format!(
-                "origin validation: lineageCounter must be 0, got {}",
-                p.lineage_counter
-            ));
+            return Err(PicError::Origin(OriginError::CounterNotZero {
+                counter: p.lineage_counter,
+            }));
         }
         let want_lineage = derive_lineage_id(p);
         if p.lineage_id != want_lineage {
-            return Err(
-                "origin validation: lineageId does not match origin commitment".to_string(),
-            );
+            return Err(PicError::Origin(OriginError::LineageIdMismatch {
+                expected: want_lineage,
+                found: p.lineage_id.clone(),
+            }));
+        }
+        let want_branch = root_branch_id(&p.lineage_id);
+        if p.branch_id != want_branch {
+            return Err(PicError::Origin(OriginError::BranchIdMismatch {
+                expected: want_branch,
+                found: p.branch_id.clone(),
+            }));
         }
-        if p.branch_id != root_branch_id(&p.lineage_id) {
-            return Err(
-                "origin validation: branchId is not the derived root branch id".to_string(),
-            );
+        if let Some(grants) = self.grants.filter(|_| !p.grant_id.is_empty()) {
+            self.verify_grant(grants, p, now)
+                .map_err(|e| PicError::Origin(OriginError::Grant(e)))?;
         }
         if let Some(rev) = self.revocations {
-            rev.check(p)
-                .map_err(|e| format!("origin validation: {e}"))?;
+            rev.check(p).map_err(|revoked| PicError::Revocation {
+                hop: None,
+                checkpoint: Checkpoint::Origin,
+                revoked,
+            })?;
         }
         Ok(())
     }
 
     /// Validates a non-origin PCA against its already-validated predecessor,
     /// performing the ordered checks of §3.3 plus revocation-coordinate
-    /// continuity. If `consume` is true, single-use challenges are marked
-    /// consumed (live acceptance); re-validation of history passes false.
+    /// continuity. If `consume` is true, the predecessor's challenge is counted
+    /// against its `maxUses` (live acceptance); re-validation of history passes
+    /// false.
     pub fn verify_hop(
         &mut self,
         cur: &Pca,
@@ -92,215 +185,615 @@ impl<'a> Verifier<'a> {
         now: DateTime<Utc>,
         consume: bool,
     ) -> PicResult<()> {
-        if cur.is_origin() {
-            return Err("hop validation: PCA carries no Proof of Relationship".to_string());
-        }
-        let por = cur.proof_of_relationship.as_ref().unwrap();
+        self.verify_hop_with(cur, pred, now, consume, &[])
+    }
+
+    /// [`Verifier::verify_hop`] resolving a referenced attestation from `side`
+    /// (an envelope's side table) before the Verifier's cache.
+    fn verify_hop_with(
+        &mut self,
+        cur: &Pca,
+        pred: &Pca,
+        now: DateTime<Utc>,
+        consume: bool,
+        side: &[Attestation],
+    ) -> PicResult<()> {
+        let Some(por) = cur.proof_of_relationship.as_ref() else {
+            return Err(PicError::Binding {
+                hop: None,
+                error: BindingError::NoProofOfRelationship,
+            });
+        };
 
         // 1. integrity — single signature over the whole PCA.
-        let proof = cur.proof.as_ref().ok_or("hop: missing signature")?;
+        let proof = cur.proof.as_ref().ok_or(PicError::Integrity {
+            hop: None,
+            error: IntegrityError::MissingSignature,
+        })?;
         let msg = cur.signing_bytes();
-        self.registry
-            .verify(&proof.verification_method, &msg, &proof.signature)
-            .map_err(|e| format!("hop integrity: {e}"))?;
+        let at = signed_at(&cur.issued_at, now);
+        self.check_signature(&proof.verification_method, &msg, proof, at)
+            .map_err(|e| PicError::Integrity {
+                hop: None,
+                error: IntegrityError::Signature(e),
+            })?;
 
         // 2. predecessor binding — previousPcaHash equals the presented predecessor.
         let pred_digest = pred.digest();
         if por.previous_pca_hash != pred_digest {
-            return Err(
-                "hop binding: previousPcaHash does not match the presented predecessor".to_string(),
-            );
+            return Err(PicError::Binding {
+                hop: None,
+                error: BindingError::PreviousHashMismatch {
+                    expected: pred_digest,
+                    found: por.previous_pca_hash.clone(),
+                },
+            });
         }
 
         // Revocation-coordinate continuity (Revocation spec §2.3).
-        coordinate_continuity(cur, pred).map_err(|e| format!("hop coordinates: {e}"))?;
+        coordinate_continuity(cur, pred, por)
+            .and_then(|()| {
+                // The lineage is attenuated only under the profile it was minted
+                // under, wherever the Verifier joins it (PCA0, envelope, snapshot).
+                if profile_of(cur) != self.authority.id() {
+                    return Err(CoordinateError::UnknownAuthorityProfile {
+                        profile: profile_of(cur).to_string(),
+                        implemented: self.authority.id().to_string(),
+                    });
+                }
+                Ok(())
+            })
+            .map_err(|error| PicError::Coordinates { hop: None, error })?;
 
         // 3. continuation — response carries the predecessor challenge, unexpired,
-        //    and (single-use) not already consumed.
+        //    not consumed up to its mode's bound, and the emitted challenge in a
+        //    known mode.
+        let continuation = |error| PicError::Continuation { hop: None, error };
         if por.continuation_response.predecessor_challenge != pred.continuation.challenge {
-            return Err(
-                "hop continuation: response does not answer the predecessor challenge".to_string(),
-            );
+            return Err(continuation(ContinuationError::ChallengeMismatch {
+                expected: pred.continuation.challenge.clone(),
+                found: por.continuation_response.predecessor_challenge.clone(),
+            }));
         }
-        if now >= parse_rfc3339(&pred.continuation.expires_at) {
-            return Err("hop continuation: predecessor challenge expired".to_string());
+        let challenge_expires = parse_rfc3339(&pred.continuation.expires_at)
+            .map_err(|e| continuation(ContinuationError::MalformedTime(e)))?;
+        if now - challenge_expires >= self.temporal.backward_skew {
+            return Err(continuation(ContinuationError::Expired {
+                expires_at: pred.continuation.expires_at.clone(),
+                skew: self.temporal.backward_skew,
+                by: now - challenge_expires - self.temporal.backward_skew,
+            }));
         }
-        if consume
-            && pred.continuation.mode == "single-use"
-            && self.used.contains(&pred.continuation.challenge)
-        {
-            return Err(
-                "hop continuation: single-use challenge already consumed (replay)".to_string(),
-            );
+        let mode = ContinuationMode::of(&pred.continuation).map_err(continuation)?;
+        ContinuationMode::of(&cur.continuation).map_err(continuation)?;
+        let challenge = &pred.continuation.challenge;
+        if consume && self.uses.get(challenge).copied().unwrap_or(0) >= mode.max_uses() {
+            return Err(continuation(match mode {
+                ContinuationMode::SingleUse => ContinuationError::Replayed {
+                    challenge: challenge.clone(),
+                },
+                ContinuationMode::MultiUse(max_uses) => ContinuationError::Exhausted {
+                    challenge: challenge.clone(),
+                    max_uses,
+                },
+            }));
         }
 
-        // 4. attestation — embedded issuer signature valid, within validity,
-        //    subject matches the executor, which matches the PCA signing key.
-        self.verify_attestation(por, &proof.verification_method, now)
-            .map_err(|e| format!("hop attestation: {e}"))?;
+        // 4. attestation — embedded or referenced; issuer signature valid,
+        //    within validity, subject matches the executor, which matches the
+        //    PCA signing key.
+        let att = self
+            .resolve_attestation(por, side)
+            .and_then(|att| {
+                self.verify_attestation(att, &por.executor, &proof.verification_method, now)?;
+                Ok(att)
+            })
+            .map_err(|error| PicError::Attestation { hop: None, error })?;
+        if let Some(audience) = &pred.continuation.audience {
+            // The predecessor named its successors: the attested executor is one.
+            if !audience.admits(&por.executor, &att.attributes.role) {
+                return Err(continuation(ContinuationError::NotInAudience {
+                    executor: por.executor.clone(),
+                    role: att.attributes.role.clone(),
+                }));
+            }
+        }
 
         // 5. conformance — attested attributes satisfy the predecessor contract.
-        conforms(
-            &por.executor_attestation.attributes,
-            &pred.invariants.execution_contract,
-        )
-        .map_err(|e| format!("hop conformance: {e}"))?;
+        self.authority
+            .conforms(&att.attributes, &pred.invariants.execution_contract)
+            .map_err(|violation| PicError::Conformance {
+                hop: None,
+                violation,
+            })?;
 
         // 6. non-expansion — invariants are equal to or more restrictive.
-        attenuates(&cur.invariants, &pred.invariants)
-            .map_err(|e| format!("hop non-expansion: {e}"))?;
+        self.authority
+            .attenuates(&cur.invariants, &pred.invariants)
+            .map_err(|violation| PicError::NonExpansion {
+                hop: None,
+                violation,
+            })?;
 
         // 7. temporal — hop window contained in the predecessor's (§6.3).
-        temporal_check(cur, pred, now).map_err(|e| format!("hop temporal: {e}"))?;
+        temporal_check(&self.temporal, cur, pred, now)
+            .map_err(|error| PicError::Temporal { hop: None, error })?;
 
         // revocation state — is this position cut off?
         if let Some(rev) = self.revocations {
-            rev.check(cur).map_err(|e| format!("hop revocation: {e}"))?;
+            rev.check(cur).map_err(|revoked| PicError::Revocation {
+                hop: None,
+                checkpoint: Checkpoint::Hop,
+                revoked,
+            })?;
         }
 
-        if consume && pred.continuation.mode == "single-use" {
-            self.used.insert(pred.continuation.challenge.clone());
+        if consume {
+            *self.uses.entry(challenge.clone()).or_default() += 1;
         }
         Ok(())
     }
 
-    fn verify_attestation(&self, por: &Por, pca_vm: &str, now: DateTime<Utc>) -> PicResult<()> {
-        let att = &por.executor_attestation;
+    /// Checks one signature, unless a batch already passed this exact check.
+    fn check_signature(
+        &self,
+        reference: &str,
+        msg: &[u8],
+        proof: &Proof,
+        at: DateTime<Utc>,
+    ) -> Result<(), SignatureError> {
+        if !self.batched.is_empty() && self.batched.contains(&batch_key(reference, msg, proof)) {
+            return Ok(());
+        }
+        self.registry.verify_at(reference, msg, proof, at)
+    }
+
+    /// Batch-verifies the Ed25519 signatures of `chains` (each PCA and each
+    /// embedded or cached executor attestation) and records them as checked.
+    /// Signatures of other suites, or ones that cannot be located, are left to
+    /// the per-hop checks; so is everything if the batch fails, so that the
+    /// failing hop is still reported precisely.
+    pub(crate) fn batch_signatures(&mut self, chains: &[&[Pca]], now: DateTime<Utc>) {
+        let mut checks = Vec::new();
+        for chain in chains {
+            for (i, p) in chain.iter().enumerate() {
+                let Some(proof) = &p.proof else { continue };
+                let reference = if i == 0 {
+                    &p.issuer
+                } else {
+                    &proof.verification_method
+                };
+                let at = signed_at(&p.issued_at, now);
+                checks.push((reference.as_str(), p.signing_bytes(), proof, at));
+                let att = p
+                    .proof_of_relationship
+                    .as_ref()
+                    .and_then(|por| self.resolve_attestation(por, &[]).ok());
+                if let Some((att, proof)) = att.and_then(|a| Some((a, a.proof.as_ref()?))) {
+                    let at = signed_at(&att.issued_at, now);
+                    checks.push((att.issuer.as_str(), att.signing_bytes(), proof, at));
+                }
+            }
+        }
+        checks.retain(|(reference, _, proof, at)| {
+            proof.type_ == SignatureSuite::Ed25519.proof_type()
+                && self.registry.batch_key(reference, proof, *at).is_some()
+        });
+        let batch: Vec<_> = checks
+            .iter()
+            .map(|(reference, msg, proof, at)| (*reference, msg.as_slice(), *proof, *at))
+            .collect();
+        if batch.len() < 2 || !self.registry.verify_batch(&batch) {
+            return;
+        }
+        let keys: Vec<_> = batch
+            .iter()
+            .map(|(reference, msg, proof, _)| batch_key(reference, msg, proof))
+            .collect();
+        self.batched.extend(keys);
+    }
+
+    /// Drops the checks a batch passed, once the verification they were
+    /// batched for is done.
+    pub(crate) fn forget_batch(&mut self) {
+        self.batched.clear();
+    }
+
+    /// The executor attestation a PoR embeds, or the one its digest names in
+    /// `side` or the Verifier's cache. A digest binds the complete signed
+    /// attestation, so a resolved entry is exactly the one the executor signed
+    /// over.
+    fn resolve_attestation<'b>(
+        &'b self,
+        por: &'b Por,
+        side: &'b [Attestation],
+    ) -> Result<&'b Attestation, AttestationError> {
+        match (
+            &por.executor_attestation,
+            por.executor_attestation_digest.as_str(),
+        ) {
+            (Some(att), "") => Ok(att),
+            (Some(_), _) => Err(AttestationError::BothForms),
+            (None, "") => Err(AttestationError::Missing),
+            (None, digest) => side
+                .iter()
+                .find(|a| a.digest() == digest)
+                .or_else(|| self.attestations.and_then(|c| c.get(digest)))
+                .ok_or_else(|| AttestationError::Unresolved {
+                    digest: digest.to_string(),
+                }),
+        }
+    }
+
+    /// Checks that the trusted grant a PCA0 names is signed by its grantor
+    /// and admits the PCA0, still at `now`.
+    fn verify_grant(
+        &self,
+        grants: &GrantRegistry,
+        p: &Pca,
+        now: DateTime<Utc>,
+    ) -> Result<(), GrantError> {
+        let g = grants.get(&p.grant_id).ok_or_else(|| GrantError::Unknown {
+            grant_id: p.grant_id.clone(),
+        })?;
+        let proof = g.proof.as_ref().ok_or(GrantError::MissingSignature)?;
+        self.check_signature(
+            &g.grantor,
+            &g.signing_bytes(),
+            proof,
+            signed_at(&g.issued_at, now),
+        )
+        .map_err(GrantError::Signature)?;
+        grant::admits(
+            g,
+            &p.issuer,
+            &p.invariants,
+            now,
+            &self.temporal,
+            self.authority,
+        )
+    }
+
+    fn verify_attestation(
+        &self,
+        att: &Attestation,
+        executor: &str,
+        pca_vm: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), AttestationError> {
         let proof = att
             .proof
             .as_ref()
-            .ok_or("attestation missing issuer signature")?;
+            .ok_or(AttestationError::MissingSignature)?;
         let msg = att.signing_bytes();
-        self.registry
-            .verify(&att.issuer, &msg, &proof.signature)
-            .map_err(|e| format!("issuer signature: {e}"))?;
-        within_validity(&att.issued_at, &att.expires_at, now)
-            .map_err(|e| format!("attestation validity: {e}"))?;
-        if att.subject != por.executor {
-            return Err(format!(
-                "attestation subject {:?} does not match executor {:?}",
-                att.subject, por.executor
-            ));
+        self.check_signature(&att.issuer, &msg, proof, signed_at(&att.issued_at, now))
+            .map_err(AttestationError::IssuerSignature)?;
+        self.temporal
+            .within(&att.issued_at, &att.expires_at, now)
+            .map_err(AttestationError::Validity)?;
+        if att.subject != executor {
+            return Err(AttestationError::SubjectMismatch {
+                subject: att.subject.clone(),
+                executor: executor.to_string(),
+            });
         }
         // the key that signed the PCA must belong to the executor.
-        if !pca_vm.starts_with(&por.executor) {
-            return Err(format!(
-                "PCA signing key {pca_vm:?} does not belong to executor {:?}",
-                por.executor
-            ));
+        if !pca_vm.starts_with(executor) {
+            return Err(AttestationError::KeyNotExecutors {
+                verification_method: pca_vm.to_string(),
+                executor: executor.to_string(),
+            });
         }
         Ok(())
     }
 
     /// Validates a whole chain from PCA0 to the tip (Full Hash Chain profile,
     /// §5.1): cost O(n). Returns the invariants authorized at the tip. History
-    /// re-validation does not consume single-use challenges.
+    /// re-validation does not consume challenges.
     pub fn verify_full_chain(
         &mut self,
         chain: &[Pca],
         now: DateTime<Utc>,
     ) -> PicResult<Invariants> {
         if chain.is_empty() {
-            return Err("empty chain".to_string());
+            return Err(PicError::EmptyChain);
         }
         self.verify_origin(&chain[0], now)?;
         for i in 1..chain.len() {
             self.verify_hop(&chain[i], &chain[i - 1], now, false)
-                .map_err(|e| format!("hop {i}: {e}"))?;
+                .map_err(|e| e.at_hop(i))?;
         }
         Ok(chain[chain.len() - 1].invariants.clone())
     }
 
+    /// [`Verifier::verify_full_chain`] with every Ed25519 signature of the
+    /// chain checked in one batch first. Same result, since a batch and a
+    /// single check use one equation, and, should a signature be bad, the
+    /// same error (at the same hop).
+    pub fn verify_full_chain_batched(
+        &mut self,
+        chain: &[Pca],
+        now: DateTime<Utc>,
+    ) -> PicResult<Invariants> {
+        self.batch_signatures(&[chain], now);
+        let res = self.verify_full_chain(chain, now);
+        self.forget_batch();
+        res
+    }
+
     /// Validates one incremental transition carried in an envelope (§6.8):
     /// envelope signature and digests, then the single hop cur-against-pred,
-    /// consuming the predecessor's single-use challenge.
+    /// consuming one use of the predecessor's challenge.
     pub fn verify_envelope(&mut self, env: &Envelope, now: DateTime<Utc>) -> PicResult<Invariants> {
         let body = &env.envelope;
         let (pred, cur) = match (&body.predecessor, &body.current) {
             (Some(p), Some(c)) => (p, c),
-            _ => return Err("envelope: missing predecessor or current".to_string()),
+            _ => return Err(PicError::Envelope(EnvelopeError::MissingPca)),
         };
-        let proof = env.proof.as_ref().ok_or("envelope: missing signature")?;
+        let proof = env
+            .proof
+            .as_ref()
+            .ok_or(PicError::Envelope(EnvelopeError::MissingSignature))?;
         let msg = env.signing_bytes();
+        // An envelope carries no signing time of its own: it is signed now.
         self.registry
-            .verify(&proof.verification_method, &msg, &proof.signature)
-            .map_err(|e| format!("envelope signature: {e}"))?;
+            .verify_at(&proof.verification_method, &msg, proof, now)
+            .map_err(|e| PicError::Envelope(EnvelopeError::Signature(e)))?;
         // digests are convenience, not trusted input: recompute and cross-check.
         let pred_digest = pred.digest();
         let cur_digest = cur.digest();
         if body.predecessor_digest != pred_digest || body.current_digest != cur_digest {
-            return Err("envelope: supplied digest does not match recomputed digest".to_string());
+            return Err(PicError::Envelope(EnvelopeError::DigestMismatch {
+                predecessor_digest: body.predecessor_digest.clone(),
+                current_digest: body.current_digest.clone(),
+            }));
         }
         let por = cur
             .proof_of_relationship
             .as_ref()
-            .ok_or("envelope: current carries no Proof of Relationship")?;
+            .ok_or(PicError::Envelope(EnvelopeError::CurrentIsOrigin))?;
         if por.previous_pca_hash != pred_digest {
-            return Err(
-                "envelope: current.previousPcaHash does not equal predecessorDigest".to_string(),
-            );
+            return Err(PicError::Envelope(
+                EnvelopeError::PredecessorDigestMismatch {
+                    previous_pca_hash: por.previous_pca_hash.clone(),
+                    predecessor_digest: pred_digest,
+                },
+            ));
         }
         if let Some(rev) = self.revocations {
-            rev.check(pred)
-                .map_err(|e| format!("envelope predecessor: {e}"))?;
+            rev.check(pred).map_err(|revoked| PicError::Revocation {
+                hop: None,
+                checkpoint: Checkpoint::EnvelopePredecessor,
+                revoked,
+            })?;
         }
-        self.verify_hop(cur, pred, now, true)?;
+        self.verify_hop_with(cur, pred, now, true, &body.attestations)?;
         Ok(cur.invariants.clone())
     }
+
+    /// Live acceptance of an envelope: [`Verifier::verify_envelope`] at the
+    /// instant the Verifier's clock reads.
+    pub fn accept_envelope(&mut self, env: &Envelope) -> PicResult<Invariants> {
+        let now = self.clock.now();
+        self.verify_envelope(env, now)
+    }
+
+    /// [`Verifier::accept_envelope`] over an envelope in either wire encoding.
+    pub fn accept_envelope_bytes(&mut self, bytes: &[u8]) -> PicResult<Invariants> {
+        let now = self.clock.now();
+        self.verify_envelope_bytes(bytes, now)
+    }
+
+    /// Decodes a document received in either wire encoding, JSON or
+    /// COSE_Sign1 (see [`crate::codec`]), under the Verifier's limits; a COSE
+    /// seal is checked against the registry's keys at `now` before the
+    /// payload is trusted.
+    pub fn decode<T: Document>(&self, bytes: &[u8], now: DateTime<Utc>) -> PicResult<T> {
+        codec::decode_with(self.registry, bytes, now, &self.limits)
+    }
+
+    /// [`Verifier::verify_envelope`] over an envelope in either wire encoding.
+    pub fn verify_envelope_bytes(
+        &mut self,
+        bytes: &[u8],
+        now: DateTime<Utc>,
+    ) -> PicResult<Invariants> {
+        let env: Envelope = self.decode(bytes, now)?;
+        self.verify_envelope(&env, now)
+    }
+
+    /// [`Verifier::verify_full_chain`] over PCAs in either wire encoding; the
+    /// encodings may be mixed within one chain.
+    pub fn verify_full_chain_bytes<B: AsRef<[u8]>>(
+        &mut self,
+        chain: &[B],
+        now: DateTime<Utc>,
+    ) -> PicResult<Invariants> {
+        if chain.len() > self.limits.max_chain_len {
+            return Err(PicError::Codec(CodecError::Limit {
+                limit: Limit::ChainLength,
+                max: self.limits.max_chain_len,
+                found: chain.len(),
+            }));
+        }
+        let chain = chain
+            .iter()
+            .map(|b| self.decode(b.as_ref(), now))
+            .collect::<PicResult<Vec<Pca>>>()?;
+        self.verify_full_chain(&chain, now)
+    }
+}
+
+/// Identifies one signature check: the key reference, the signed bytes and the
+/// proof.
+fn batch_key(reference: &str, msg: &[u8], proof: &Proof) -> String {
+    hash_parts(&[
+        reference.as_bytes(),
+        &[0],
+        proof.type_.as_bytes(),
+        &[0],
+        proof.signature.as_bytes(),
+        &[0],
+        msg,
+    ])
 }
 
 /// Enforces the hop-by-hop continuity of the revocation coordinates (Revocation
-/// spec §2.3).
-fn coordinate_continuity(cur: &Pca, pred: &Pca) -> PicResult<()> {
+/// spec §2.3). The branch is kept, or changed by exactly the authorized
+/// branch-creation transition: a `branchNonce` in the PoR and the child
+/// `branchId` derived from it.
+fn coordinate_continuity(cur: &Pca, pred: &Pca, por: &Por) -> Result<(), CoordinateError> {
     if cur.profile != pred.profile {
-        return Err("profile changed".to_string());
+        return Err(CoordinateError::ProfileChanged {
+            predecessor: pred.profile.clone(),
+            current: cur.profile.clone(),
+        });
     }
     if cur.lineage_id != pred.lineage_id {
-        return Err("lineageId changed".to_string());
+        return Err(CoordinateError::LineageIdChanged {
+            predecessor: pred.lineage_id.clone(),
+            current: cur.lineage_id.clone(),
+        });
     }
     if (cur.grant_id.is_empty()) != (pred.grant_id.is_empty()) || cur.grant_id != pred.grant_id {
-        return Err("grantId presence/value changed".to_string());
+        return Err(CoordinateError::GrantIdChanged {
+            predecessor: pred.grant_id.clone(),
+            current: cur.grant_id.clone(),
+        });
     }
     if cur.origin_issuer != pred.origin_issuer {
-        return Err("originIssuer changed".to_string());
+        return Err(CoordinateError::OriginIssuerChanged {
+            predecessor: pred.origin_issuer.clone(),
+            current: cur.origin_issuer.clone(),
+        });
     }
-    if cur.branch_id != pred.branch_id {
-        return Err(
-            "branchId changed without an authorized branch-creation transition".to_string(),
-        );
+    if cur.authority_profile != pred.authority_profile {
+        return Err(CoordinateError::AuthorityProfileChanged {
+            predecessor: pred.authority_profile.clone(),
+            current: cur.authority_profile.clone(),
+        });
     }
-    if cur.lineage_counter != pred.lineage_counter + 1 {
-        return Err(format!(
-            "lineageCounter must be predecessor+1 ({}), got {}",
-            pred.lineage_counter + 1,
-            cur.lineage_counter
-        ));
+    match (cur.branch_id == pred.branch_id, por.branch_nonce.is_empty()) {
+        (true, true) => {}
+        (true, false) => return Err(CoordinateError::BranchNonceWithoutBranch),
+        (false, true) => {
+            return Err(CoordinateError::BranchIdChanged {
+                predecessor: pred.branch_id.clone(),
+                current: cur.branch_id.clone(),
+            })
+        }
+        (false, false) => {
+            let expected =
+                derive_branch_id(&pred.branch_id, &por.previous_pca_hash, &por.branch_nonce);
+            if cur.branch_id != expected {
+                return Err(CoordinateError::BranchIdNotDerived {
+                    expected,
+                    found: cur.branch_id.clone(),
+                });
+            }
+        }
+    }
+    // A predecessor at u64::MAX has no successor; never overflow on it.
+    let expected = successor(pred)?;
+    if expected != cur.lineage_counter {
+        return Err(CoordinateError::CounterNotSuccessor {
+            expected,
+            found: cur.lineage_counter,
+        });
     }
     Ok(())
 }
 
-fn within_validity(issued_at: &str, expires_at: &str, now: DateTime<Utc>) -> PicResult<()> {
-    let issued = parse_rfc3339(issued_at);
-    let expires = parse_rfc3339(expires_at);
-    if now < issued {
-        return Err("not yet valid".to_string());
-    }
-    if now >= expires {
-        return Err("expired".to_string());
-    }
-    Ok(())
+/// The `lineageCounter` of `pred`'s successor.
+pub(crate) fn successor(pred: &Pca) -> Result<u64, CoordinateError> {
+    pred.lineage_counter
+        .checked_add(1)
+        .ok_or(CoordinateError::CounterExhausted {
+            predecessor: pred.lineage_counter,
+        })
+}
+
+/// The instant a document claims to have been signed, which selects the key
+/// version it is checked under. A malformed time falls back to `now`; the
+/// validity check that follows the signature check reports it.
+pub(crate) fn signed_at(issued_at: &str, now: DateTime<Utc>) -> DateTime<Utc> {
+    parse_rfc3339(issued_at).unwrap_or(now)
 }
 
-fn temporal_check(cur: &Pca, pred: &Pca, now: DateTime<Utc>) -> PicResult<()> {
-    if parse_rfc3339(&cur.issued_at) < parse_rfc3339(&pred.issued_at) {
-        return Err("issuedAt precedes predecessor".to_string());
+fn temporal_check(
+    policy: &TemporalPolicy,
+    cur: &Pca,
+    pred: &Pca,
+    now: DateTime<Utc>,
+) -> Result<(), TemporalError> {
+    let time = |s: &str| parse_rfc3339(s).map_err(TemporalError::MalformedTime);
+    let (issued, pred_issued) = (time(&cur.issued_at)?, time(&pred.issued_at)?);
+    if pred_issued - issued > policy.backward_skew {
+        return Err(TemporalError::IssuedBeforePredecessor {
+            issued_at: cur.issued_at.clone(),
+            predecessor_issued_at: pred.issued_at.clone(),
+            skew: policy.backward_skew,
+            by: pred_issued - issued - policy.backward_skew,
+        });
     }
-    if parse_rfc3339(&cur.expires_at) > parse_rfc3339(&pred.expires_at) {
-        return Err("expiresAt exceeds predecessor".to_string());
+    let (expires, pred_expires) = (time(&cur.expires_at)?, time(&pred.expires_at)?);
+    if expires > pred_expires {
+        return Err(TemporalError::ExpiresAfterPredecessor {
+            expires_at: cur.expires_at.clone(),
+            predecessor_expires_at: pred.expires_at.clone(),
+            by: expires - pred_expires,
+        });
+    }
+    let (profile, pred_profile) = (TemporalProfile::of(cur), TemporalProfile::of(pred));
+    for (bound, max, found) in [
+        (
+            TemporalBound::HopWindow,
+            pred_profile.max_hop_window(),
+            profile.max_hop_window(),
+        ),
+        (
+            TemporalBound::ChallengeLifetime,
+            pred_profile.max_challenge_lifetime(),
+            profile.max_challenge_lifetime(),
+        ),
+        (
+            TemporalBound::LineageLifetime,
+            pred_profile.lineage_lifetime(),
+            profile.lineage_lifetime(),
+        ),
+    ] {
+        if found > max {
+            return Err(TemporalError::ProfileRelaxed {
+                bound,
+                max,
+                by: found - max,
+            });
+        }
     }
-    within_validity(&cur.issued_at, &cur.expires_at, now)?;
-    if parse_rfc3339(&cur.continuation.expires_at) > parse_rfc3339(&pred.expires_at) {
-        return Err("emitted challenge outlives the lineage".to_string());
+    policy
+        .within(&cur.issued_at, &cur.expires_at, now)
+        .and_then(|()| {
+            policy.window(
+                TemporalBound::HopWindow,
+                &profile,
+                &cur.issued_at,
+                &cur.expires_at,
+            )?;
+            policy.window(
+                TemporalBound::ChallengeLifetime,
+                &profile,
+                &cur.issued_at,
+                &cur.continuation.expires_at,
+            )
+        })
+        .map_err(TemporalError::Validity)?;
+    let challenge_expires = time(&cur.continuation.expires_at)?;
+    if challenge_expires > pred_expires {
+        return Err(TemporalError::ChallengeOutlivesLineage {
+            challenge_expires_at: cur.continuation.expires_at.clone(),
+            lineage_expires_at: pred.expires_at.clone(),
+            by: challenge_expires - pred_expires,
+        });
     }
     Ok(())
 }
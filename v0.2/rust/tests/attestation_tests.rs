@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Attestation-by-reference tests on the real shared fixtures: a PoR carrying
+//! only the attestation digest verifies when the attestation is resolvable
+//! (envelope side table or Verifier cache), fails closed when it is not, and
+//! gets exactly the same attestation, non-expansion and conformance checks as
+//! the embedded form.
+
+use chrono::{DateTime, Utc};
+use pic::prover::mint_pca0;
+use pic::scenario::World;
+use pic::types::{ExecutionContract, Invariants, Request};
+use pic::{
+    wrap_envelope_with, AttestationCache, AttestationError, Pca, PicError, Prover, Verifier,
+};
+
+fn read_user() -> Request {
+    Request {
+        operation: "read".to_string(),
+        target: "/user/file".to_string(),
+        security_domain: "tenant-42".to_string(),
+        ..Default::default()
+    }
+}
+
+/// PCA0 -> gateway successor with `inv`, skipping the Prover self-check so a
+/// bad successor reaches the Verifier. Returns the two PCAs in each form.
+fn hop_both_forms(w: &World, origin: Invariants, inv: Invariants, now: DateTime<Utc>) -> [Pca; 3] {
+    let pca0 = mint_pca0(w.set.identity("alice"), origin, None, now).expect("mint");
+    let gateway = w.set.identity("gateway");
+    let att = w.set.attestation("gateway");
+    let embedded = Prover::new(gateway, att.clone())
+        .continue_malicious(&pca0, inv.clone(), read_user(), now)
+        .expect("embedded");
+    let referenced = Prover::new(gateway, att)
+        .by_reference()
+        .continue_malicious(&pca0, inv, read_user(), now)
+        .expect("referenced");
+    [pca0, embedded, referenced]
+}
+
+fn json_len<T: serde::Serialize>(v: &T) -> usize {
+    serde_json::to_vec(v).unwrap().len()
+}
+
+#[test]
+fn reference_chain_verifies_with_cache_only() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let (chain, cache) = w.build_chain_by_reference(8, now).expect("chain");
+    assert_eq!(cache.len(), 4, "four cycled executors");
+    for p in &chain[1..] {
+        let por = p.proof_of_relationship.as_ref().unwrap();
+        assert!(por.executor_attestation.is_none());
+        assert!(cache.get(&por.executor_attestation_digest).is_some());
+    }
+
+    let mut v = Verifier::new(reg, None);
+    v.attestations = Some(&cache);
+    v.verify_full_chain(&chain, now).expect("resolved chain");
+
+    let err = Verifier::new(reg, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.4-ATTESTATION");
+    assert!(matches!(
+        err,
+        PicError::Attestation {
+            hop: Some(1),
+            error: AttestationError::Unresolved { .. }
+        }
+    ));
+
+    // A chain's side table is just a cache built from it.
+    let side: AttestationCache = cache.iter().cloned().collect();
+    let mut v = Verifier::new(reg, None);
+    v.attestations = Some(&side);
+    v.verify_full_chain(&chain, now).expect("side-table chain");
+}
+
+#[test]
+fn envelope_side_table_resolves_and_is_bound() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let (chain, _) = w.build_chain_by_reference(1, now).expect("chain");
+    let att = w.set.attestation("gateway");
+
+    let env = wrap_envelope_with(gateway, &chain[0], &chain[1], std::slice::from_ref(&att))
+        .expect("wrap");
+    Verifier::new(reg, None)
+        .verify_envelope(&env, now)
+        .expect("side table resolves");
+
+    // A side-table entry that is not the attestation the PCA committed to does
+    // not resolve, even though it still carries a valid issuer signature.
+    let mut other = att;
+    other.expires_at = "2034-01-01T00:00:00Z".to_string();
+    let env = wrap_envelope_with(gateway, &chain[0], &chain[1], &[other]).expect("wrap");
+    let err = Verifier::new(reg, None)
+        .verify_envelope(&env, now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Attestation {
+            error: AttestationError::Unresolved { .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn checks_are_identical_in_both_forms() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let mut cache = AttestationCache::new();
+    cache.insert(w.set.attestation("gateway"));
+    let verify = |pred: &Pca, cur: &Pca| {
+        let mut v = Verifier::new(reg, None);
+        v.attestations = Some(&cache);
+        v.verify_hop(cur, pred, now, false)
+    };
+
+    let user = |ops: &[&str], contract: ExecutionContract| Invariants {
+        operations: ops.iter().map(|s| s.to_string()).collect(),
+        execution_contract: contract,
+        ..Default::default()
+    };
+
+    // Expansion.
+    let [pca0, embedded, referenced] = hop_both_forms(
+        &w,
+        user(&["read:/user/*"], ExecutionContract::default()),
+        user(
+            &["read:/user/*", "read:/sys/*"],
+            ExecutionContract::default(),
+        ),
+        now,
+    );
+    let a = verify(&pca0, &embedded).unwrap_err();
+    assert_eq!(a.code(), "PIC-PV-3.3.6-NON-EXPANSION");
+    assert_eq!(a, verify(&pca0, &referenced).unwrap_err());
+
+    // Contract the gateway attestation does not satisfy.
+    let contract = ExecutionContract {
+        role: "archive".to_string(),
+        ..Default::default()
+    };
+    let [pca0, embedded, referenced] = hop_both_forms(
+        &w,
+        user(&["read:/user/*"], contract.clone()),
+        user(&["read:/user/*"], contract),
+        now,
+    );
+    let a = verify(&pca0, &embedded).unwrap_err();
+    assert_eq!(a.code(), "PIC-PV-3.3.5-CONFORMANCE");
+    assert_eq!(a, verify(&pca0, &referenced).unwrap_err());
+}
+
+#[test]
+fn por_must_carry_exactly_one_form() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let att = w.set.attestation("gateway");
+    let pca0 = mint_pca0(
+        w.set.identity("alice"),
+        Invariants {
+            operations: vec!["read:/user/*".to_string()],
+            ..Default::default()
+        },
+        None,
+        now,
+    )
+    .expect("mint");
+    let inv = pca0.invariants.clone();
+    let resign = |p: &mut Pca| {
+        p.proof = None;
+        let signature = gateway.sign(&p.signing_bytes());
+        p.proof = Some(pic::Proof {
+            type_: pic::SIGNATURE_TYPE.to_string(),
+            verification_method: gateway.verification_method.clone(),
+            signature,
+        });
+    };
+
+    let mut both = Prover::new(gateway, att.clone())
+        .continue_(&pca0, inv.clone(), read_user(), now)
+        .expect("pca1");
+    both.proof_of_relationship
+        .as_mut()
+        .unwrap()
+        .executor_attestation_digest = att.digest();
+    resign(&mut both);
+    let err = Verifier::new(reg, None)
+        .verify_hop(&both, &pca0, now, false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Attestation {
+            error: AttestationError::BothForms,
+            ..
+        }
+    ));
+
+    let mut neither = Prover::new(gateway, att)
+        .continue_(&pca0, inv, read_user(), now)
+        .expect("pca1");
+    neither
+        .proof_of_relationship
+        .as_mut()
+        .unwrap()
+        .executor_attestation = None;
+    resign(&mut neither);
+    let err = Verifier::new(reg, None)
+        .verify_hop(&neither, &pca0, now, false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Attestation {
+            error: AttestationError::Missing,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn reference_form_shrinks_long_chains() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let embedded = w.build_chain(64, now).expect("chain");
+    let (referenced, cache) = w.build_chain_by_reference(64, now).expect("chain");
+    let side: Vec<_> = cache.iter().collect();
+    let embedded_len = json_len(&embedded);
+    let referenced_len = json_len(&referenced) + json_len(&side);
+    assert!(
+        referenced_len * 10 < embedded_len * 9,
+        "by reference {referenced_len} B vs embedded {embedded_len} B"
+    );
+}
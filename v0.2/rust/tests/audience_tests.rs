@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Audience tests: a PCA whose continuation names its successors, by DID or
+//! attested role, can be continued only by them; an intercepted PCA is not
+//! continuable by another conforming workload.
+
+mod common;
+
+use chrono::Utc;
+use common::{inv, req, resign};
+use pic::{
+    fixtureset, mint_pca0, mint_pca0_with, Audience, ContinuationError, OriginOptions, PicError,
+    Prover, Verifier,
+};
+
+fn continuation_error(err: PicError) -> ContinuationError {
+    match err {
+        PicError::Continuation { error, .. } => error,
+        other => panic!("expected a continuation error, got {other:?}"),
+    }
+}
+
+#[test]
+fn intercepted_pca_is_not_continuable_by_another_workload() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .with_audience(Audience::executor("did:web:storage.example"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+
+    let continue_as = |name: &str| {
+        let pca2 = Prover::new(set.identity(name), set.attestation(name))
+            .continue_(&pca1, inv(), req(), now)
+            .expect("hop");
+        Verifier::new(&set.registry, None)
+            .verify_full_chain(&[pca0.clone(), pca1.clone(), pca2], now)
+    };
+    continue_as("storage-service").expect("the named successor");
+    let err = continue_as("archive-service").unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.3-CONTINUATION");
+    assert_eq!(err.hop(), Some(2));
+    assert_eq!(
+        continuation_error(err),
+        ContinuationError::NotInAudience {
+            executor: "did:web:archive.example".to_string(),
+            role: "archive-service".to_string(),
+        }
+    );
+
+    // The audience is signed: the executor cannot widen it after the fact.
+    let mut widened = pca1.clone();
+    widened.continuation.audience = None;
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&widened, &pca0, now, false)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.1-INTEGRITY");
+}
+
+#[test]
+fn role_audience_admits_any_executor_attested_in_the_role() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0_with(
+        set.identity("alice"),
+        inv(),
+        None,
+        now,
+        &OriginOptions {
+            audience: Some(Audience::role("gateway")),
+            ..Default::default()
+        },
+    )
+    .expect("mint");
+    assert_eq!(pca0.continuation.audience, Some(Audience::role("gateway")));
+    Verifier::new(&set.registry, None)
+        .verify_origin(&pca0, now)
+        .expect("origin");
+
+    let hop = |name: &str| {
+        Prover::new(set.identity(name), set.attestation(name))
+            .continue_(&pca0, inv(), req(), now)
+            .expect("hop")
+    };
+    Verifier::new(&set.registry, None)
+        .verify_hop(&hop("gateway"), &pca0, now, false)
+        .expect("attested gateway");
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&hop("backup-service"), &pca0, now, false)
+        .unwrap_err();
+    assert!(matches!(
+        continuation_error(err),
+        ContinuationError::NotInAudience { .. }
+    ));
+}
+
+#[test]
+fn empty_audience_admits_no_one() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let alice = set.identity("alice");
+    let mut pca0 = mint_pca0(alice, inv(), None, now).expect("mint");
+    pca0.continuation.audience = Some(Audience::default());
+    resign(&mut pca0, alice);
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&pca1, &pca0, now, false)
+        .unwrap_err();
+    assert!(matches!(
+        continuation_error(err),
+        ContinuationError::NotInAudience { .. }
+    ));
+}
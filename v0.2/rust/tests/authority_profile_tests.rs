@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Authority profile tests: a lineage records the profile it was minted under
+//! on every hop, the Prover, Verifier and snapshot issuer check hops under the
+//! profile they are given, and a Verifier refuses lineages minted under a
+//! profile it does not implement, whether it joins them at PCA0, at an
+//! envelope or at a snapshot. The test profile is a ledger whose operations are
+//! exact strings: no pattern covers another.
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use common::{granting, request, resign};
+use pic::authority::profile_of;
+use pic::{
+    attenuates, conforms, derive_lineage_id, fixtureset, issue_snapshot, mint_pca0, mint_pca0_with,
+    wrap_envelope, AuthorityProfile, ContractAttributes, CoordinateError, ExecutionContract,
+    Invariants, OriginError, OriginOptions, Pca, PicError, Prover, ReferenceProfile, Request,
+    SnapshotError, Verifier, Violation, REFERENCE_AUTHORITY_PROFILE,
+};
+
+const LEDGER: &str = "urn:example:authority:ledger-v1";
+
+/// Operations are exact ledger entries; contracts and quantities follow the
+/// reference profile.
+struct Ledger;
+
+impl AuthorityProfile for Ledger {
+    fn id(&self) -> &str {
+        LEDGER
+    }
+
+    fn attenuates(&self, current: &Invariants, predecessor: &Invariants) -> Result<(), Violation> {
+        match current
+            .operations
+            .iter()
+            .find(|op| !predecessor.operations.contains(op))
+        {
+            Some(op) => Err(Violation::OperationAdded {
+                operation: op.clone(),
+            }),
+            None => attenuates(current, predecessor),
+        }
+    }
+
+    fn conforms(
+        &self,
+        att: &ContractAttributes,
+        contract: &ExecutionContract,
+    ) -> Result<(), Violation> {
+        conforms(att, contract)
+    }
+
+    fn authorize(&self, inv: &Invariants, req: &Request) -> Result<(), Violation> {
+        let requested = format!("{}:{}", req.operation, req.target);
+        if inv.operations.contains(&requested) {
+            return Ok(());
+        }
+        Err(Violation::NotAuthorized {
+            requested,
+            granted: inv.operations.clone(),
+        })
+    }
+}
+
+fn authority_error(err: PicError) -> OriginError {
+    match err {
+        PicError::Origin(e @ OriginError::UnknownAuthorityProfile { .. }) => e,
+        other => panic!("expected an authority profile error, got {other:?}"),
+    }
+}
+
+/// A two-hop lineage minted and continued under the ledger profile.
+fn ledger_chain(set: &fixtureset::Set, now: DateTime<Utc>) -> [Pca; 2] {
+    let opts = OriginOptions {
+        authority: &Ledger,
+        ..Default::default()
+    };
+    let pca0 = mint_pca0_with(
+        set.identity("alice"),
+        granting(&["read:/user/a.txt"]),
+        None,
+        now,
+        &opts,
+    )
+    .expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .with_authority(&Ledger)
+        .continue_(
+            &pca0,
+            granting(&["read:/user/a.txt"]),
+            request("read", "/user/a.txt"),
+            now,
+        )
+        .expect("ledger hop");
+    [pca0, pca1]
+}
+
+#[test]
+fn reference_lineages_record_no_profile_and_are_refused_elsewhere() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(
+        set.identity("alice"),
+        granting(&["read:/user/*"]),
+        None,
+        now,
+    )
+    .expect("mint");
+    assert_eq!(pca0.authority_profile, "");
+    assert_eq!(profile_of(&pca0), REFERENCE_AUTHORITY_PROFILE);
+    Verifier::new(&set.registry, None)
+        .verify_origin(&pca0, now)
+        .expect("reference verifier");
+
+    let err = Verifier::new(&set.registry, None)
+        .with_authority(&Ledger)
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.2-ORIGIN");
+    assert_eq!(
+        authority_error(err),
+        OriginError::UnknownAuthorityProfile {
+            profile: REFERENCE_AUTHORITY_PROFILE.to_string(),
+            implemented: LEDGER.to_string(),
+        }
+    );
+}
+
+#[test]
+fn a_profile_lineage_is_checked_and_accepted_only_under_its_profile() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let opts = OriginOptions {
+        authority: &Ledger,
+        ..Default::default()
+    };
+    let pca0 = mint_pca0_with(
+        set.identity("alice"),
+        granting(&["read:/user/*", "read:/user/a.txt"]),
+        None,
+        now,
+        &opts,
+    )
+    .expect("mint");
+    assert_eq!(pca0.authority_profile, LEDGER);
+
+    // The ledger does not read `*`: narrowing to a pattern it lacks is expansion.
+    let gateway =
+        Prover::new(set.identity("gateway"), set.attestation("gateway")).with_authority(&Ledger);
+    let err = gateway
+        .continue_(
+            &pca0,
+            granting(&["read:/user/b.txt"]),
+            request("read", "/user/a.txt"),
+            now,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        PicError::SelfCheck(Violation::OperationAdded {
+            operation: "read:/user/b.txt".to_string(),
+        })
+    );
+    let pca1 = gateway
+        .continue_(
+            &pca0,
+            granting(&["read:/user/a.txt"]),
+            request("read", "/user/a.txt"),
+            now,
+        )
+        .expect("ledger hop");
+    let chain = [pca0, pca1];
+
+    let tip = Verifier::new(&set.registry, None)
+        .with_authority(&Ledger)
+        .verify_full_chain(&chain, now)
+        .expect("ledger verifier");
+    Ledger
+        .authorize(&tip, &request("read", "/user/a.txt"))
+        .expect("authorized");
+
+    let err = Verifier::new(&set.registry, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    assert_eq!(
+        authority_error(err),
+        OriginError::UnknownAuthorityProfile {
+            profile: LEDGER.to_string(),
+            implemented: REFERENCE_AUTHORITY_PROFILE.to_string(),
+        }
+    );
+
+    let issuer = set.identity("snapshot-issuer");
+    assert!(issue_snapshot(
+        issuer,
+        &set.registry,
+        &chain,
+        1,
+        now,
+        &ReferenceProfile::DEFAULT
+    )
+    .is_err());
+    let snap =
+        issue_snapshot(issuer, &set.registry, &chain, 1, now, &Ledger).expect("ledger snapshot");
+    assert_eq!(snap.through_pca_hash, chain[1].digest());
+}
+
+#[test]
+fn the_recorded_profile_is_bound_to_the_lineage() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let alice = set.identity("alice");
+    let opts = OriginOptions {
+        authority: &Ledger,
+        ..Default::default()
+    };
+    let pca0 =
+        mint_pca0_with(alice, granting(&["read:/user/a.txt"]), None, now, &opts).expect("mint");
+
+    // Relabelling the lineage as a reference one, even re-signed by its
+    // issuer, no longer matches the lineage it committed to.
+    let mut relabelled = pca0.clone();
+    relabelled.authority_profile.clear();
+    assert_ne!(derive_lineage_id(&relabelled), pca0.lineage_id);
+    resign(&mut relabelled, alice);
+    let err = Verifier::new(&set.registry, None)
+        .verify_origin(&relabelled, now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Origin(OriginError::LineageIdMismatch { .. })
+    ));
+}
+
+#[test]
+fn envelope_and_snapshot_hops_are_checked_under_the_lineage_profile() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let [pca0, pca1] = ledger_chain(set, now);
+    assert_eq!(pca1.authority_profile, LEDGER);
+    let env = wrap_envelope(set.identity("gateway"), &pca0, &pca1).expect("envelope");
+
+    let err = Verifier::new(&set.registry, None)
+        .verify_envelope(&env, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-REV-2.3-COORDINATES");
+    assert!(matches!(
+        err,
+        PicError::Coordinates {
+            error: CoordinateError::UnknownAuthorityProfile { .. },
+            ..
+        }
+    ));
+    Verifier::new(&set.registry, None)
+        .with_authority(&Ledger)
+        .verify_envelope(&env, now)
+        .expect("ledger envelope");
+
+    let issuer = set.identity("snapshot-issuer");
+    let chain = [pca0.clone(), pca1.clone()];
+    let snap =
+        issue_snapshot(issuer, &set.registry, &chain, 1, now, &Ledger).expect("ledger snapshot");
+    let err = Verifier::new(&set.registry, None)
+        .verify_from_snapshot(&snap, &chain[1..], now)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        PicError::Snapshot(SnapshotError::UnknownAuthorityProfile {
+            expected: REFERENCE_AUTHORITY_PROFILE.to_string(),
+            found: LEDGER.to_string(),
+        })
+    );
+    Verifier::new(&set.registry, None)
+        .with_authority(&Ledger)
+        .verify_from_snapshot(&snap, &chain[1..], now)
+        .expect("ledger tail");
+
+    // A hop cannot drop its lineage's profile, even one its executor signed.
+    let mut relabelled = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .with_authority(&Ledger)
+        .continue_(
+            &pca0,
+            granting(&["read:/user/a.txt"]),
+            request("read", "/user/a.txt"),
+            now,
+        )
+        .expect("ledger hop");
+    relabelled.authority_profile.clear();
+    resign(&mut relabelled, set.identity("gateway"));
+    let err = Verifier::new(&set.registry, None)
+        .with_authority(&Ledger)
+        .verify_full_chain(&[pca0, relabelled], now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Coordinates {
+            error: CoordinateError::AuthorityProfileChanged { .. },
+            ..
+        }
+    ));
+}
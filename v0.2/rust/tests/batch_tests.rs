@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Batch signature verification tests on the real shared fixtures: the batched
+//! full-chain path accepts exactly what the one-at-a-time path accepts, and a
+//! bad PCA or attestation signature is still reported at its hop.
+
+mod common;
+
+use chrono::Utc;
+use common::resign;
+use pic::scenario::World;
+use pic::{
+    sign_attestation, AttestationError, Identity, IntegrityError, PicError, Prover, SignatureError,
+    SignatureSuite, Verifier,
+};
+
+#[test]
+fn batched_accepts_what_sequential_accepts() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let chain = w.build_chain(16, now).expect("chain");
+    let seq = Verifier::new(reg, None)
+        .verify_full_chain(&chain, now)
+        .expect("sequential");
+    let batch = Verifier::new(reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .expect("batched");
+    assert_eq!(seq, batch);
+
+    // Attestations resolved from a cache are batched as well.
+    let (chain, cache) = w.build_chain_by_reference(16, now).expect("chain");
+    let mut v = Verifier::new(reg, None);
+    v.attestations = Some(&cache);
+    v.verify_full_chain_batched(&chain, now)
+        .expect("batched by reference");
+}
+
+#[test]
+fn bad_pca_signature_is_reported_at_its_hop() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let mut chain = w.build_chain(8, now).expect("chain");
+    // A well-formed signature by the right key, over other bytes.
+    let gateway = w.set.identity("gateway");
+    chain[5].proof.as_mut().unwrap().signature = gateway.sign(b"not this PCA");
+
+    let seq = Verifier::new(reg, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    let batch = Verifier::new(reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .unwrap_err();
+    assert_eq!(seq, batch);
+    assert!(matches!(
+        batch,
+        PicError::Integrity {
+            hop: Some(5),
+            error: IntegrityError::Signature(SignatureError::Invalid { .. })
+        }
+    ));
+}
+
+#[test]
+fn bad_attestation_signature_is_reported_at_its_hop() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let mut chain = w.build_chain(4, now).expect("chain");
+    // The executor of hop 1 inflates its own attestation and re-signs the PCA.
+    let por = chain[1].proof_of_relationship.as_mut().unwrap();
+    let att = por.executor_attestation.as_mut().unwrap();
+    att.attributes.role = "admin".to_string();
+    resign(&mut chain[1], w.set.identity("gateway"));
+
+    let seq = Verifier::new(reg, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    let batch = Verifier::new(reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .unwrap_err();
+    assert_eq!(seq, batch);
+    assert!(matches!(
+        batch,
+        PicError::Attestation {
+            hop: Some(1),
+            error: AttestationError::IssuerSignature(_)
+        }
+    ));
+}
+
+#[test]
+fn other_suites_are_checked_individually() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let mut reg = pic::Registry::new();
+    let alice = w.set.identity("alice");
+    let org = Identity::new("did:example:org");
+    let p256 = Identity::new_with("did:example:p256", SignatureSuite::EcdsaP256);
+    reg.add(alice);
+    reg.add(&org);
+    reg.add(&p256);
+    let mut chain = w.build_chain(0, now).expect("chain");
+    let att = sign_attestation(
+        pic::Attestation {
+            subject: p256.id.clone(),
+            issued_at: pic::rfc3339(now),
+            expires_at: pic::rfc3339(now + chrono::Duration::hours(1)),
+            ..Default::default()
+        },
+        &org,
+    )
+    .expect("sign attestation");
+    let tip = Prover::new(&p256, att)
+        .continue_(
+            &chain[0],
+            chain[0].invariants.clone(),
+            Default::default(),
+            now,
+        )
+        .expect("p256 hop");
+    chain.push(tip);
+    Verifier::new(&reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .expect("mixed batch");
+
+    chain[1].proof.as_mut().unwrap().signature = p256.sign(b"not this PCA");
+    let err = Verifier::new(&reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.1-INTEGRITY");
+}
+
+#[test]
+fn small_order_signatures_are_refused_batched_or_not() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let mut reg = pic::Registry::new();
+    let alice = w.set.identity("alice");
+    let org = Identity::new("did:example:org");
+    let weak = Identity::new("did:example:weak");
+    reg.add(alice);
+    reg.add(&org);
+    let mut chain = w.build_chain(0, now).expect("chain");
+    let att = sign_attestation(
+        pic::Attestation {
+            subject: weak.id.clone(),
+            issued_at: pic::rfc3339(now),
+            expires_at: pic::rfc3339(now + chrono::Duration::hours(1)),
+            ..Default::default()
+        },
+        &org,
+    )
+    .expect("sign attestation");
+    let tip = Prover::new(&weak, att)
+        .continue_(
+            &chain[0],
+            chain[0].invariants.clone(),
+            Default::default(),
+            now,
+        )
+        .expect("hop");
+    chain.push(tip);
+
+    // The identity point as the key, and R = identity, S = 0: the
+    // cofactorless equation holds for any message, the cofactored one too.
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let mut entry = pic::KeyEntry::new(&weak);
+    entry.key = pic::PublicKey::from_bytes(SignatureSuite::Ed25519, &identity).expect("point");
+    reg.add_key(entry);
+    let mut forged = [0u8; 64];
+    forged[0] = 1;
+    chain[1].proof.as_mut().unwrap().signature = pic::crypto::b64_encode(&forged);
+
+    assert!(reg.verify_raw(&weak.id, b"anything", &forged).is_err());
+    let seq = Verifier::new(&reg, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    let batch = Verifier::new(&reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .unwrap_err();
+    assert_eq!(seq, batch);
+    assert!(matches!(
+        batch,
+        PicError::Integrity {
+            hop: Some(1),
+            error: IntegrityError::Signature(SignatureError::Invalid { .. })
+        }
+    ));
+}
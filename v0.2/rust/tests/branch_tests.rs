@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Branch-creation tests: `Prover::branch` opens a derived child branch, the
+//! Verifier accepts exactly that transition, and a BRANCH-SUFFIX revocation
+//! strikes one sub-workflow while its siblings and the parent branch live on.
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use common::{inv, req, resign};
+use pic::{
+    derive_branch_id, fixtureset, mint_pca0, Checkpoint, ContinuationMode, CoordinateError, Pca,
+    PicError, Prover, RevocationStore, Verifier,
+};
+
+fn coordinate_error(err: PicError) -> CoordinateError {
+    match err {
+        PicError::Coordinates { error, .. } => error,
+        other => panic!("expected a coordinate error, got {other:?}"),
+    }
+}
+
+/// PCA0 → gateway (multi-use) → one branch per shard → one more hop each.
+fn fan_out(shards: &[&str], now: DateTime<Utc>) -> (Vec<Pca>, Vec<Vec<Pca>>) {
+    let set = fixtureset::load().expect("fixtures");
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .with_mode(ContinuationMode::MultiUse(shards.len() as u32))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("gateway");
+    let trunk = vec![pca0, pca1];
+    let branches = shards
+        .iter()
+        .map(|shard| {
+            let prover = Prover::new(set.identity(shard), set.attestation(shard));
+            let pca2 = prover.branch(&trunk[1], inv(), req(), now).expect("branch");
+            let pca3 = prover.continue_(&pca2, inv(), req(), now).expect("hop");
+            [trunk.clone(), vec![pca2, pca3]].concat()
+        })
+        .collect();
+    (trunk, branches)
+}
+
+#[test]
+fn branches_are_derived_and_kept_downstream() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let (trunk, branches) = fan_out(&["backup-service", "archive-service"], now);
+    let (a, b) = (&branches[0], &branches[1]);
+
+    let por = a[2].proof_of_relationship.as_ref().unwrap();
+    assert_eq!(
+        a[2].branch_id,
+        derive_branch_id(&trunk[1].branch_id, &trunk[1].digest(), &por.branch_nonce)
+    );
+    assert_ne!(a[2].branch_id, trunk[1].branch_id);
+    assert_ne!(a[2].branch_id, b[2].branch_id);
+    assert_eq!(a[3].branch_id, a[2].branch_id);
+    assert!(a[3]
+        .proof_of_relationship
+        .as_ref()
+        .unwrap()
+        .branch_nonce
+        .is_empty());
+
+    for chain in &branches {
+        Verifier::new(&set.registry, None)
+            .verify_full_chain(chain, now)
+            .expect("branched chain");
+    }
+}
+
+#[test]
+fn branch_suffix_revokes_one_sub_workflow() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let (trunk, branches) = fan_out(&["backup-service", "archive-service"], now);
+    let (a, b) = (&branches[0], &branches[1]);
+
+    let mut store = RevocationStore::new();
+    store.branch_suffix(
+        &a[2].lineage_id,
+        &a[2].branch_id,
+        2,
+        "did:web:alice.example",
+    );
+    let err = Verifier::new(&set.registry, Some(&store))
+        .verify_full_chain(a, now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Revocation {
+            hop: Some(2),
+            checkpoint: Checkpoint::Hop,
+            ..
+        }
+    ));
+    for survivor in [b, &trunk] {
+        Verifier::new(&set.registry, Some(&store))
+            .verify_full_chain(survivor, now)
+            .expect("sibling and parent branch stay valid");
+    }
+}
+
+#[test]
+fn only_the_derived_transition_changes_the_branch() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let (trunk, branches) = fan_out(&["backup-service"], now);
+    let backup = set.identity("backup-service");
+    let verify = |cur: &Pca| {
+        Verifier::new(&set.registry, None)
+            .verify_hop(cur, &trunk[1], now, false)
+            .unwrap_err()
+    };
+
+    // A branchId that nothing derives.
+    let mut forged = branches[0][2].clone();
+    forged.branch_id = "sha256:00".to_string();
+    resign(&mut forged, backup);
+    assert!(matches!(
+        coordinate_error(verify(&forged)),
+        CoordinateError::BranchIdNotDerived { .. }
+    ));
+
+    // A changed branchId without a nonce.
+    forged
+        .proof_of_relationship
+        .as_mut()
+        .unwrap()
+        .branch_nonce
+        .clear();
+    resign(&mut forged, backup);
+    assert!(matches!(
+        coordinate_error(verify(&forged)),
+        CoordinateError::BranchIdChanged { .. }
+    ));
+
+    // A nonce on an ordinary continuation.
+    let mut stray = branches[0][2].clone();
+    stray.branch_id = trunk[1].branch_id.clone();
+    resign(&mut stray, backup);
+    let err = verify(&stray);
+    assert_eq!(err.code(), "PIC-REV-2.3-COORDINATES");
+    assert_eq!(
+        coordinate_error(err),
+        CoordinateError::BranchNonceWithoutBranch
+    );
+}
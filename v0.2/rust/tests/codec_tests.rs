@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Wire-encoding tests on the real shared fixtures: CBOR/COSE round trips keep
+//! digests intact, the encoding is deterministic, the Verifier accepts either
+//! encoding (mixed within one chain), and a broken seal fails closed.
+
+use chrono::Utc;
+use pic::codec::{decode, decode_cose, encode_cose, from_cbor, to_cbor};
+use pic::scenario::World;
+use pic::{
+    issue_snapshot, wrap_envelope, CodecError, Encoding, Envelope, Pca, PicError, ReferenceProfile,
+    Revocation, SignatureError, Snapshot, Verifier, STRATEGY_LINEAGE_SUFFIX,
+};
+
+#[test]
+fn cose_round_trip_preserves_digests() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let chain = w.build_chain(3, now).expect("chain");
+
+    for p in &chain {
+        let bytes = encode_cose(p, gateway).expect("seal");
+        assert_eq!(Encoding::detect(&bytes), Some(Encoding::Cose));
+        let back: Pca = decode_cose(reg, &bytes, now).expect("decode pca");
+        assert_eq!(back.digest(), p.digest(), "PCA digest changed in transit");
+    }
+
+    let env = wrap_envelope(gateway, &chain[0], &chain[1]).expect("wrap");
+    let back: Envelope =
+        decode(reg, &encode_cose(&env, gateway).expect("seal"), now).expect("decode envelope");
+    assert_eq!(pic::digest_of(&back), pic::digest_of(&env));
+
+    let snap = issue_snapshot(
+        w.set.identity("snapshot-issuer"),
+        reg,
+        &chain,
+        2,
+        now,
+        &ReferenceProfile::DEFAULT,
+    )
+    .expect("snapshot");
+    let back: Snapshot =
+        decode(reg, &encode_cose(&snap, gateway).expect("seal"), now).expect("decode snapshot");
+    assert_eq!(pic::digest_of(&back), pic::digest_of(&snap));
+
+    let att = w.set.attestation("gateway");
+    let back: pic::Attestation =
+        decode(reg, &encode_cose(&att, gateway).expect("seal"), now).expect("decode attestation");
+    assert_eq!(back.signing_bytes(), att.signing_bytes());
+
+    let rev = Revocation {
+        strategy: STRATEGY_LINEAGE_SUFFIX.to_string(),
+        lineage_id: chain[0].lineage_id.clone(),
+        from_counter: 2,
+        issuer: "did:web:alice.example".to_string(),
+        ..Default::default()
+    };
+    let back: Revocation =
+        decode(reg, &encode_cose(&rev, gateway).expect("seal"), now).expect("decode revocation");
+    assert_eq!(back, rev);
+}
+
+#[test]
+fn cbor_is_deterministic_and_compact() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let chain = w.build_chain(1, now).expect("chain");
+    let pca = &chain[1];
+
+    let a = to_cbor(pca);
+    let b = to_cbor(&from_cbor::<Pca>(&a).expect("from_cbor"));
+    assert_eq!(a, b, "re-encoding a decoded PCA changed its bytes");
+    assert!(
+        a.len() * 3 < serde_json::to_vec(pca).unwrap().len() * 2,
+        "CBOR payload is not meaningfully smaller than JSON"
+    );
+
+    // A text key where the profile assigns an integer label is valid CBOR but
+    // not the deterministic encoding.
+    let mut loose = Vec::new();
+    ciborium::into_writer(
+        &ciborium::value::Value::Map(vec![(
+            ciborium::value::Value::Text("strategy".into()),
+            ciborium::value::Value::Text("GRANT".into()),
+        )]),
+        &mut loose,
+    )
+    .unwrap();
+    assert_eq!(
+        from_cbor::<Revocation>(&loose).unwrap_err(),
+        CodecError::NonDeterministic
+    );
+}
+
+#[test]
+fn verifier_accepts_either_encoding() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let chain = w.build_chain(4, now).expect("chain");
+
+    // Alternate JSON and COSE within one chain.
+    let wire: Vec<Vec<u8>> = chain
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            if i % 2 == 0 {
+                encode_cose(p, gateway).expect("seal")
+            } else {
+                serde_json::to_vec(p).unwrap()
+            }
+        })
+        .collect();
+    let inv = Verifier::new(reg, None)
+        .verify_full_chain_bytes(&wire, now)
+        .expect("mixed-encoding chain rejected");
+    assert_eq!(inv.operations, chain[4].invariants.operations);
+
+    // The single-use challenge is consumed whatever encoding carried it.
+    let env = wrap_envelope(gateway, &chain[0], &chain[1]).expect("wrap");
+    let mut v = Verifier::new(reg, None);
+    v.verify_envelope_bytes(&encode_cose(&env, gateway).expect("seal"), now)
+        .expect("COSE envelope rejected");
+    let err = v
+        .verify_envelope_bytes(&serde_json::to_vec(&env).unwrap(), now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.3-CONTINUATION");
+}
+
+#[test]
+fn broken_seal_fails_closed() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let chain = w.build_chain(1, now).expect("chain");
+    let bytes = encode_cose(&chain[1], gateway).expect("seal");
+
+    // Flip one byte of the payload: the seal no longer verifies.
+    let mut tampered = bytes.clone();
+    let at = tampered.len() - 100;
+    tampered[at] ^= 0x01;
+    let err = decode::<Pca>(reg, &tampered, now).unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-6.4-ENCODING");
+    assert!(matches!(
+        err,
+        PicError::Codec(CodecError::Seal(SignatureError::Invalid { .. }))
+    ));
+
+    // A PCA is not an envelope, even under a valid seal.
+    assert!(matches!(
+        decode::<Envelope>(reg, &bytes, now).unwrap_err(),
+        PicError::Codec(CodecError::ContentType { .. })
+    ));
+
+    // An unknown sealer is refused.
+    let stranger = pic::Identity::new("did:example:stranger");
+    assert!(matches!(
+        decode::<Pca>(reg, &encode_cose(&chain[1], &stranger).expect("seal"), now).unwrap_err(),
+        PicError::Codec(CodecError::Seal(SignatureError::UnknownMethod { .. }))
+    ));
+
+    assert!(matches!(
+        decode::<Pca>(reg, b"not a document", now).unwrap_err(),
+        PicError::Codec(CodecError::UnknownEncoding)
+    ));
+}
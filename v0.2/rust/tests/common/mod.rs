@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Fixture factories shared by the integration tests. Each test crate uses
+//! only some of them.
+
+#![allow(dead_code)]
+
+use pic::{Identity, Invariants, Pca, Proof, Request};
+
+/// The invariants most test lineages are minted with: `read:/user/*`.
+pub fn inv() -> Invariants {
+    granting(&["read:/user/*"])
+}
+
+/// Invariants granting exactly `ops`.
+pub fn granting(ops: &[&str]) -> Invariants {
+    Invariants {
+        operations: ops.iter().map(|s| s.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+/// A `read` of `/user/file`, which [`inv`] authorizes.
+pub fn req() -> Request {
+    request("read", "/user/file")
+}
+
+/// [`req`] in the security domain `domain`.
+pub fn req_in(domain: &str) -> Request {
+    Request {
+        security_domain: domain.to_string(),
+        ..req()
+    }
+}
+
+/// A request for `operation` on `target`.
+pub fn request(operation: &str, target: &str) -> Request {
+    Request {
+        operation: operation.to_string(),
+        target: target.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Re-signs a PCA after the test has altered it, as a malicious executor can.
+pub fn resign(p: &mut Pca, signer: &Identity) {
+    resign_as(p, signer, signer.proof_type());
+}
+
+/// [`resign`] with the proof naming `proof_type`, whatever the key's suite.
+pub fn resign_as(p: &mut Pca, signer: &Identity, proof_type: &str) {
+    p.proof = None;
+    let signature = signer.sign(&p.signing_bytes());
+    p.proof = Some(Proof {
+        type_: proof_type.to_string(),
+        verification_method: signer.verification_method.clone(),
+        signature,
+    });
+}
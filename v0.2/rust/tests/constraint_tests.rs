@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Constraint tests: numeric bounds, value sets and time-of-day windows on
+//! `Invariants` are kept or tightened hop by hop, travel inside the signed
+//! PCA in either encoding, and bound the quantities a request declares.
+
+mod common;
+
+use chrono::Utc;
+use common::resign;
+use pic::codec::{decode_cose, encode_cose};
+use pic::{
+    attenuates, authorize, fixtureset, mint_pca0, Constraint, ExecutedRequest, Invariants, Limits,
+    Pca, PicError, Prover, Quantity, Request, TimeOfDay, Verifier, Violation,
+};
+
+fn transfer(max: u64) -> Invariants {
+    Invariants {
+        operations: vec!["transfer:/accounts/*".to_string()],
+        ..Default::default()
+    }
+    .with_constraint("amountEur", Constraint::Max(max))
+    .with_constraint("currency", Constraint::one_of(&["EUR", "USD"]))
+    .with_constraint(
+        "hours",
+        Constraint::TimeOfDay(TimeOfDay::new("08:00", "18:00")),
+    )
+}
+
+fn executed(amount: u64, currency: &str, at: &str) -> ExecutedRequest {
+    ExecutedRequest::new("transfer", "/accounts/acme")
+        .with_quantity("amountEur", Quantity::Number(amount))
+        .with_quantity("currency", Quantity::Text(currency.to_string()))
+        .with_quantity("hours", Quantity::Text(at.to_string()))
+}
+
+#[test]
+fn each_bound_is_kept_or_tightened() {
+    let pred = transfer(500);
+    attenuates(&pred, &pred).expect("kept");
+    let tighter = transfer(100)
+        .with_constraint("currency", Constraint::one_of(&["EUR"]))
+        .with_constraint(
+            "hours",
+            Constraint::TimeOfDay(TimeOfDay::new("09:00", "12:00")),
+        )
+        .with_constraint("records", Constraint::Max(100));
+    attenuates(&tighter, &pred).expect("tightened, plus a new bound");
+
+    let relaxed = [
+        (transfer(501), "amountEur"),
+        (
+            transfer(500).with_constraint("currency", Constraint::one_of(&["EUR", "GBP"])),
+            "currency",
+        ),
+        (
+            transfer(500).with_constraint(
+                "hours",
+                Constraint::TimeOfDay(TimeOfDay::new("07:59", "18:00")),
+            ),
+            "hours",
+        ),
+        (
+            transfer(500).with_constraint("currency", Constraint::Max(1)),
+            "currency",
+        ),
+    ];
+    for (cur, field) in relaxed {
+        match attenuates(&cur, &pred) {
+            Err(Violation::ConstraintRelaxed { name, .. }) => assert_eq!(name, field),
+            other => panic!("{field}: expected a relaxed constraint, got {other:?}"),
+        }
+    }
+
+    let mut dropped = transfer(500);
+    dropped.constraints.remove("amountEur");
+    assert_eq!(
+        attenuates(&dropped, &pred),
+        Err(Violation::ConstraintDropped {
+            name: "amountEur".to_string(),
+        })
+    );
+}
+
+#[test]
+fn time_windows_may_wrap_past_midnight() {
+    let night = |from, to| {
+        Invariants::default()
+            .with_constraint("hours", Constraint::TimeOfDay(TimeOfDay::new(from, to)))
+    };
+    attenuates(&night("23:00", "01:00"), &night("22:00", "02:00")).expect("inside");
+    attenuates(&night("22:30", "23:30"), &night("22:00", "02:00")).expect("before midnight");
+    assert!(attenuates(&night("21:00", "01:00"), &night("22:00", "02:00")).is_err());
+    assert!(attenuates(&night("8:00", "09:00"), &night("08:00", "10:00")).is_err());
+
+    let req = |at: &str| Request {
+        operation: "run".to_string(),
+        quantities: [("hours".to_string(), Quantity::Text(at.to_string()))].into(),
+        ..Default::default()
+    };
+    let mut inv = night("22:00", "02:00");
+    inv.operations = vec!["run".to_string()];
+    authorize(&inv, &req("23:59")).expect("before midnight");
+    authorize(&inv, &req("00:30")).expect("after midnight");
+    assert!(authorize(&inv, &req("02:00")).is_err());
+    assert!(authorize(&inv, &req("25:00")).is_err());
+}
+
+#[test]
+fn requests_must_declare_quantities_within_bounds() {
+    let inv = transfer(500);
+    authorize(&inv, &executed(500, "EUR", "09:30").request("bank")).expect("at the bound");
+    assert_eq!(
+        authorize(&inv, &executed(501, "EUR", "09:30").request("bank")),
+        Err(Violation::QuantityExceeded {
+            name: "amountEur".to_string(),
+            declared: "501".to_string(),
+            constraint: "max 500".to_string(),
+        })
+    );
+    assert!(matches!(
+        authorize(&inv, &executed(10, "GBP", "09:30").request("bank")),
+        Err(Violation::QuantityExceeded { name, .. }) if name == "currency"
+    ));
+    assert!(matches!(
+        authorize(&inv, &executed(10, "EUR", "19:00").request("bank")),
+        Err(Violation::QuantityExceeded { name, .. }) if name == "hours"
+    ));
+
+    let mut undeclared = executed(10, "EUR", "09:30");
+    undeclared.quantities.remove("amountEur");
+    let err = authorize(&inv, &undeclared.request("bank")).unwrap_err();
+    assert!(matches!(err, Violation::QuantityUndeclared { .. }));
+    assert_eq!(
+        err.to_string(),
+        "not authorized: quantity \"amountEur\" (max 500) not declared"
+    );
+
+    // A text where a number is bounded is not within the bound.
+    let mut wrong_type = executed(10, "EUR", "09:30");
+    wrong_type
+        .quantities
+        .insert("amountEur".to_string(), Quantity::Text("10".to_string()));
+    assert!(authorize(&inv, &wrong_type.request("bank")).is_err());
+}
+
+#[test]
+fn constraints_are_signed_and_narrowed_along_the_chain() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), transfer(500), None, now).expect("mint");
+    let gateway = set.identity("gateway");
+    let sent = executed(100, "EUR", "09:30");
+    let pca1 = Prover::new(gateway, set.attestation("gateway"))
+        .continue_(&pca0, transfer(100), sent.request("bank"), now)
+        .expect("narrowed hop");
+    let tip = Verifier::new(&set.registry, None)
+        .verify_full_chain(&[pca0.clone(), pca1.clone()], now)
+        .expect("chain");
+    assert_eq!(tip.constraints["amountEur"], Constraint::Max(100));
+
+    let signed = &pca1.proof_of_relationship.as_ref().unwrap().request;
+    sent.check(signed).expect("executed-vs-signed");
+    authorize(&tip, signed).expect("authorized");
+    let inflated = executed(400, "EUR", "09:30");
+    assert!(inflated.check(signed).is_err());
+    assert!(authorize(&tip, &inflated.request("bank")).is_err());
+
+    // The bounds are covered by the PCA signature, in both encodings.
+    let back = Pca::from_untrusted_bytes(&serde_json::to_vec(&pca1).unwrap(), &Limits::default())
+        .expect("strict decode");
+    assert_eq!(back.digest(), pca1.digest());
+    let back: Pca = decode_cose(
+        &set.registry,
+        &encode_cose(&pca1, gateway).expect("seal"),
+        now,
+    )
+    .expect("cose");
+    assert_eq!(back.invariants, pca1.invariants);
+
+    let mut widened = pca1.clone();
+    widened
+        .invariants
+        .constraints
+        .insert("amountEur".to_string(), Constraint::Max(10_000));
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&widened, &pca0, now, false)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.1-INTEGRITY");
+    resign(&mut widened, gateway);
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&widened, &pca0, now, false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::NonExpansion {
+            violation: Violation::ConstraintRelaxed { .. },
+            ..
+        }
+    ));
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Continuation mode tests: a multi-use challenge admits exactly `maxUses`
+//! live successors, history re-validation consumes nothing, and unknown modes
+//! or inconsistent `maxUses` are rejected wherever they appear.
+
+mod common;
+
+use chrono::Utc;
+use common::{inv, req, resign};
+use pic::{
+    fixtureset, mint_pca0, mint_pca0_with, wrap_envelope, ContinuationError, ContinuationMode,
+    OriginError, OriginOptions, PicError, Prover, Verifier,
+};
+
+fn continuation_error(err: PicError) -> ContinuationError {
+    match err {
+        PicError::Continuation { error, .. } => error,
+        other => panic!("expected a continuation error, got {other:?}"),
+    }
+}
+
+#[test]
+fn multi_use_challenge_admits_max_uses_successors() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let gateway = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .with_mode(ContinuationMode::MultiUse(3));
+    let pca1 = gateway.continue_(&pca0, inv(), req(), now).expect("hop");
+    assert_eq!(
+        (pca1.continuation.mode.as_str(), pca1.continuation.max_uses),
+        ("multi-use", 3)
+    );
+
+    let shards = [
+        "backup-service",
+        "summary-service",
+        "archive-service",
+        "storage-service",
+    ];
+    let mut v = Verifier::new(&set.registry, None);
+    let mut accepted = Vec::new();
+    for shard in shards {
+        let pca2 = Prover::new(set.identity(shard), set.attestation(shard))
+            .continue_(&pca1, inv(), req(), now)
+            .expect("shard hop");
+        let env = wrap_envelope(set.identity("gateway"), &pca1, &pca2).expect("envelope");
+        accepted.push(v.verify_envelope(&env, now).map(|_| pca2));
+    }
+    let err = accepted.pop().unwrap().unwrap_err();
+    assert!(accepted.iter().all(Result::is_ok));
+    assert_eq!(
+        continuation_error(err),
+        ContinuationError::Exhausted {
+            challenge: pca1.continuation.challenge.clone(),
+            max_uses: 3,
+        }
+    );
+
+    // Re-validating the accepted branches consumes nothing.
+    for pca2 in accepted.into_iter().map(Result::unwrap) {
+        v.verify_full_chain(&[pca0.clone(), pca1.clone(), pca2], now)
+            .expect("history");
+    }
+}
+
+#[test]
+fn single_use_remains_the_default() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    assert_eq!(
+        ContinuationMode::of(&pca1.continuation),
+        Ok(ContinuationMode::SingleUse)
+    );
+    let env = wrap_envelope(set.identity("alice"), &pca0, &pca1).expect("envelope");
+    let mut v = Verifier::new(&set.registry, None);
+    v.verify_envelope(&env, now).expect("first use");
+    assert!(matches!(
+        continuation_error(v.verify_envelope(&env, now).unwrap_err()),
+        ContinuationError::Replayed { .. }
+    ));
+}
+
+#[test]
+fn unknown_modes_are_rejected() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let alice = set.identity("alice");
+    let gateway = set.identity("gateway");
+
+    // A multi-use origin fans out like any other predecessor.
+    let pca0 = mint_pca0_with(
+        alice,
+        inv(),
+        None,
+        now,
+        &OriginOptions {
+            mode: ContinuationMode::MultiUse(2),
+            ..Default::default()
+        },
+    )
+    .expect("multi-use origin");
+    Verifier::new(&set.registry, None)
+        .verify_origin(&pca0, now)
+        .expect("origin");
+
+    let mut unlimited = pca0.clone();
+    unlimited.continuation.mode = "unlimited".to_string();
+    resign(&mut unlimited, alice);
+    assert_eq!(
+        Verifier::new(&set.registry, None).verify_origin(&unlimited, now),
+        Err(PicError::Origin(OriginError::Continuation(
+            ContinuationError::UnknownMode {
+                mode: "unlimited".to_string()
+            }
+        )))
+    );
+
+    // An emitted challenge in an unknown mode, and a predecessor's.
+    let mut pca1 = Prover::new(gateway, set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    pca1.continuation.mode = "unlimited".to_string();
+    resign(&mut pca1, gateway);
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&pca1, &pca0, now, false)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.3-CONTINUATION");
+    assert!(matches!(
+        continuation_error(err),
+        ContinuationError::UnknownMode { .. }
+    ));
+
+    let mut inconsistent = pca0.clone();
+    inconsistent.continuation.mode = "single-use".to_string();
+    resign(&mut inconsistent, alice);
+    assert_eq!(
+        Verifier::new(&set.registry, None).verify_origin(&inconsistent, now),
+        Err(PicError::Origin(OriginError::Continuation(
+            ContinuationError::InvalidMaxUses {
+                mode: "single-use".to_string(),
+                max_uses: 2,
+            }
+        )))
+    );
+
+    // A Prover refuses to emit a challenge no Verifier would accept.
+    let err = Prover::new(gateway, set.attestation("gateway"))
+        .with_mode(ContinuationMode::MultiUse(0))
+        .continue_(&pca0, inv(), req(), now)
+        .unwrap_err();
+    assert!(matches!(
+        continuation_error(err),
+        ContinuationError::InvalidMaxUses { max_uses: 0, .. }
+    ));
+}
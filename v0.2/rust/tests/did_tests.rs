@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! DID resolution tests: did:key and did:jwk identifiers of every suite
+//! resolve to their key, the fixture chain verifies against a registry built
+//! from the public `did.json` documents alone, resolutions and failures are
+//! cached, and resolution failures surface as verification errors.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{inv, req_in};
+use pic::did::{did_jwk, did_key};
+use pic::{
+    fixtureset, mint_pca0, sign_attestation, Attestation, DidKey, DidResolver, DidWeb, Identity,
+    KeyEntry, OriginError, PicError, Prover, Registry, ResolveError, Resolvers, SignatureError,
+    SignatureSuite, Verifier,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A fresh key of `suite` whose identifier is derived from its public key,
+/// signing under the verification method the DID resolves to.
+fn self_certifying(suite: SignatureSuite, did_of: fn(&pic::PublicKey) -> String) -> Identity {
+    let mut id = Identity::new_with("did:example:placeholder", suite);
+    id.id = did_of(&id.public_key());
+    id.verification_method = match id.id.strip_prefix("did:key:") {
+        Some(mb) => format!("{}#{mb}", id.id),
+        None => format!("{}#0", id.id),
+    };
+    id
+}
+
+#[test]
+fn did_key_and_did_jwk_resolve_every_suite() {
+    let resolvers = Resolvers::new();
+    for suite in SignatureSuite::ALL {
+        for did_of in [did_key, did_jwk] {
+            let id = self_certifying(*suite, did_of);
+            let keys = resolvers.resolve(&id.id).expect("resolve");
+            assert_eq!(keys.len(), 1);
+            assert_eq!(keys[0].controller, id.id);
+            assert_eq!(keys[0].verification_method, id.verification_method);
+            assert_eq!(keys[0].key, id.public_key());
+        }
+    }
+    // The did:key of a known Ed25519 test vector.
+    let ed = Identity::load("did:example:x", "did:example:x#key-1", &[0u8; 32]).expect("load");
+    assert!(did_key(&ed.public_key()).starts_with("did:key:z6Mk"));
+
+    for bad in [
+        "did:key:z6MkBAD",
+        "did:key:abc",
+        "did:jwk:e30",
+        "did:example:x",
+    ] {
+        assert!(resolvers.resolve(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn did_key_origin_verifies_without_registration() {
+    let now = Utc::now();
+    let reg = Registry::with_resolver(Resolvers::new());
+    for suite in SignatureSuite::ALL {
+        let alice = self_certifying(*suite, did_key);
+        let pca0 = mint_pca0(&alice, inv(), None, now).expect("mint");
+        Verifier::new(&reg, None)
+            .verify_origin(&pca0, now)
+            .expect("did:key origin");
+    }
+}
+
+#[test]
+fn fixture_chain_verifies_from_public_documents() {
+    let now = Utc::now();
+    let set = fixtureset::load().expect("fixtures");
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req_in("tenant-1"), now)
+        .expect("hop");
+    let chain = vec![pca0, pca1];
+
+    let reg = fixtureset::public_registry().expect("public registry");
+    Verifier::new(&reg, None)
+        .verify_full_chain(&chain, now)
+        .expect("chain from did.json");
+    Verifier::new(&reg, None)
+        .verify_full_chain_batched(&chain, now)
+        .expect("batched chain from did.json");
+    let keys = reg.current_keys("did:web:alice.example");
+    assert_eq!(keys[0].verification_method, "did:web:alice.example#key-1");
+
+    // An identity nobody published fails as a verification error.
+    let stranger = Identity::new("did:web:stranger.example");
+    let pca0 = mint_pca0(&stranger, inv(), None, now).expect("mint");
+    let err = Verifier::new(&reg, None)
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Origin(OriginError::Signature(SignatureError::Resolution(
+            ResolveError::NotFound { .. }
+        )))
+    ));
+}
+
+#[test]
+fn did_web_refuses_paths_outside_its_root() {
+    let root = fixtureset_identities();
+    let web = DidWeb::new(&root).map_host("alice.example", root.join("alice"));
+    assert!(web.resolve("did:web:alice.example").is_ok());
+    for did in [
+        "did:web:..",
+        "did:web:alice.example:..:gateway",
+        "did:web:a%2Fb",
+    ] {
+        assert!(
+            matches!(web.resolve(did), Err(ResolveError::Invalid { .. })),
+            "{did}"
+        );
+    }
+    // A document served for the wrong DID is refused.
+    let web = DidWeb::new(&root).map_host("mallory.example", root.join("alice"));
+    assert!(matches!(
+        web.resolve("did:web:mallory.example"),
+        Err(ResolveError::Invalid { .. })
+    ));
+}
+
+fn fixtureset_identities() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/identities")
+}
+
+/// A did:key resolver that counts its lookups.
+struct Counting(Arc<AtomicUsize>);
+
+impl DidResolver for Counting {
+    fn resolve(&self, did: &str) -> Result<Vec<KeyEntry>, ResolveError> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        DidKey.resolve(did)
+    }
+}
+
+#[test]
+fn resolutions_and_failures_are_cached() {
+    let now = Utc::now();
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let reg = Registry::with_resolver(Counting(Arc::clone(&lookups)));
+
+    let org = self_certifying(SignatureSuite::Ed25519, did_key);
+    let att = sign_attestation(
+        Attestation {
+            subject: "did:example:gateway".to_string(),
+            issued_at: pic::rfc3339(now - Duration::hours(1)),
+            expires_at: pic::rfc3339(now + Duration::hours(1)),
+            ..Default::default()
+        },
+        &org,
+    )
+    .expect("sign attestation");
+    for _ in 0..3 {
+        let proof = att.proof.as_ref().expect("proof");
+        reg.verify(&att.issuer, &att.signing_bytes(), proof)
+            .expect("attestation");
+    }
+    assert_eq!(lookups.load(Ordering::SeqCst), 1);
+
+    // An unknown DID reaches the resolver once, however often it is named.
+    let failures: Vec<_> = (0..3)
+        .map(|_| reg.verify_raw("did:key:zNope", b"msg", b"sig").unwrap_err())
+        .collect();
+    assert!(matches!(failures[0], SignatureError::Resolution(_)));
+    assert!(failures.iter().all(|e| *e == failures[0]));
+    assert!(reg.current_keys("did:key:zNope").is_empty());
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+
+    // Without a failure TTL every lookup asks again.
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let reg = Registry::with_resolver(Counting(Arc::clone(&lookups)))
+        .with_failure_ttl(std::time::Duration::ZERO);
+    for _ in 0..2 {
+        assert!(reg.current_keys("did:key:zNope").is_empty());
+    }
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+}
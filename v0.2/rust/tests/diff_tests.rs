@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Invariant diff tests: every operation and contract field of a hop is
+//! classified, every violation is reported, the first being the one
+//! `attenuates` returns (on every pair of a corpus of operation, contract and
+//! constraint cases), and a configured profile diffs under its own
+//! execution-model lattice.
+
+use pic::{
+    attenuates, diff, fixtureset, Constraint, ContractChange, ContractDiff, ExecutionContract,
+    InvariantDiff, Invariants, OperationChange, OperationDiff, ReferenceProfile, TimeOfDay,
+    Violation,
+};
+
+fn inv(ops: &[&str], contract: ExecutionContract) -> Invariants {
+    Invariants {
+        operations: ops.iter().map(|s| s.to_string()).collect(),
+        execution_contract: contract,
+        ..Default::default()
+    }
+}
+
+fn model(m: &str) -> ExecutionContract {
+    ExecutionContract {
+        execution_model: m.to_string(),
+        ..Default::default()
+    }
+}
+
+fn op(operation: &str, change: OperationChange, predecessor: &[&str]) -> OperationDiff {
+    OperationDiff {
+        operation: operation.to_string(),
+        change,
+        predecessor: predecessor.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+/// Asserts the diff agrees with `attenuates` on the verdict and first reason.
+fn diff_checked(current: &Invariants, predecessor: &Invariants) -> InvariantDiff {
+    let d = diff(current, predecessor);
+    assert_eq!(
+        attenuates(current, predecessor).err(),
+        d.violations.first().cloned()
+    );
+    assert_eq!(d.attenuates(), attenuates(current, predecessor).is_ok());
+    d
+}
+
+#[test]
+fn operations_are_classified() {
+    let pred = inv(
+        &["read:/user/*", "backup", "share-files"],
+        Default::default(),
+    );
+    let cur = inv(&["read:/user/a.txt", "backup"], Default::default());
+    let d = diff_checked(&cur, &pred);
+    assert_eq!(
+        d.operations,
+        vec![
+            op(
+                "read:/user/a.txt",
+                OperationChange::Narrowed,
+                &["read:/user/*"]
+            ),
+            op("backup", OperationChange::Preserved, &[]),
+            op("share-files", OperationChange::Removed, &[]),
+        ]
+    );
+    assert!(d.attenuates());
+    assert!(!d.is_unchanged());
+    assert_eq!(d.operations_with(OperationChange::Removed), ["share-files"]);
+    assert_eq!(
+        d.to_string(),
+        "narrowed [read:/user/a.txt]; removed [share-files]"
+    );
+
+    let d = diff_checked(&pred, &pred);
+    assert!(d.is_unchanged());
+    assert_eq!(d.to_string(), "unchanged");
+
+    let cur = inv(&["read:/user/**", "delete:/user/a.txt"], Default::default());
+    let d = diff_checked(&cur, &inv(&["read:/user/*"], Default::default()));
+    assert_eq!(
+        d.operations,
+        vec![
+            op(
+                "read:/user/**",
+                OperationChange::Expanded,
+                &["read:/user/*"]
+            ),
+            op("delete:/user/a.txt", OperationChange::Added, &[]),
+        ]
+    );
+    assert_eq!(
+        d.violations,
+        vec![
+            Violation::OperationAdded {
+                operation: "read:/user/**".to_string(),
+            },
+            Violation::OperationAdded {
+                operation: "delete:/user/a.txt".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn contract_fields_are_classified() {
+    let pred = inv(
+        &["backup"],
+        ExecutionContract {
+            role: "backup-operator".to_string(),
+            compliance: vec!["gdpr".to_string()],
+            execution_model: "agentic".to_string(),
+            ..Default::default()
+        },
+    );
+    let cur = inv(
+        &["backup"],
+        ExecutionContract {
+            role: "backup-operator".to_string(),
+            compliance: vec!["gdpr".to_string(), "soc2".to_string()],
+            execution_model: "deterministic".to_string(),
+            allowed_regions: vec!["eu-1".to_string()],
+            ..Default::default()
+        },
+    );
+    let d = diff_checked(&cur, &pred);
+    let changes: Vec<(&str, ContractChange)> = d
+        .contract
+        .iter()
+        .map(|c| (c.field.as_str(), c.change))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("role", ContractChange::Preserved),
+            ("compliance", ContractChange::Strengthened),
+            ("executionModel", ContractChange::Strengthened),
+            ("allowedRegions", ContractChange::Strengthened),
+        ]
+    );
+    assert_eq!(
+        d.contract[2],
+        ContractDiff {
+            field: "executionModel".to_string(),
+            change: ContractChange::Strengthened,
+            current: "deterministic".to_string(),
+            predecessor: "agentic".to_string(),
+        }
+    );
+    assert!(d
+        .to_string()
+        .contains("executionModel strengthened (agentic → deterministic)"));
+    assert!(d
+        .to_string()
+        .contains("allowedRegions strengthened (unset → [eu-1])"));
+
+    // Relaxing the contract back is reported field by field.
+    let d = diff_checked(&pred, &cur);
+    assert_eq!(
+        d.contract
+            .iter()
+            .filter(|c| c.change == ContractChange::Relaxed)
+            .map(|c| c.field.as_str())
+            .collect::<Vec<_>>(),
+        ["compliance", "executionModel", "allowedRegions"]
+    );
+    assert_eq!(d.violations.len(), 3);
+}
+
+#[test]
+fn every_violation_is_reported_in_check_order() {
+    let pred = inv(&["write:/user/*"], model("deterministic"))
+        .with_constraint("amountEur", Constraint::Max(500))
+        .with_constraint("currency", Constraint::one_of(&["EUR"]));
+    let cur =
+        inv(&["write:/**"], model("agentic")).with_constraint("amountEur", Constraint::Max(1000));
+    let d = diff_checked(&cur, &pred);
+    assert!(matches!(
+        d.violations.as_slice(),
+        [
+            Violation::OperationAdded { .. },
+            Violation::ExecutionModelRelaxed { .. },
+            Violation::ConstraintRelaxed { .. },
+            Violation::ConstraintDropped { .. },
+        ]
+    ));
+    let constraints: Vec<(&str, ContractChange)> = d
+        .contract
+        .iter()
+        .filter(|c| c.field.starts_with("constraints."))
+        .map(|c| (c.field.as_str(), c.change))
+        .collect();
+    assert_eq!(
+        constraints,
+        [
+            ("constraints.amountEur", ContractChange::Relaxed),
+            ("constraints.currency", ContractChange::Relaxed),
+        ]
+    );
+
+    let json = serde_json::to_value(&d).expect("encode");
+    assert_eq!(json["violations"][0], d.violations[0].to_string());
+    assert_eq!(json["operations"][0]["change"], "expanded");
+
+    // A constraint added by the successor only strengthens.
+    let d = diff_checked(
+        &pred.clone().with_constraint("hour", Constraint::Max(3)),
+        &pred,
+    );
+    assert_eq!(
+        d.contract.last().map(|c| c.change),
+        Some(ContractChange::Strengthened)
+    );
+}
+
+#[test]
+fn a_configured_profile_diffs_under_its_lattice() {
+    let set = fixtureset::load().expect("fixtures");
+    let profile = ReferenceProfile::with_models(set.execution_models.clone());
+    let cur = inv(&["backup"], model("sandboxed-agentic"));
+    let pred = inv(&["backup"], model("autonomous"));
+
+    let d = diff(&cur, &pred);
+    assert_eq!(
+        d.violations,
+        vec![Violation::UnknownExecutionModel {
+            model: "sandboxed-agentic".to_string(),
+        }]
+    );
+    let d = profile.diff(&cur, &pred);
+    assert!(d.attenuates());
+    assert_eq!(d.contract[0].change, ContractChange::Strengthened);
+
+    let d = profile.diff(&inv(&["backup"], model("human-in-the-loop")), &cur);
+    assert!(matches!(
+        d.violations.as_slice(),
+        [Violation::ExecutionModelRelaxed { .. }]
+    ));
+}
+
+#[test]
+fn diff_agrees_with_attenuates_on_every_pair() {
+    let mut cases = Vec::new();
+    // The operation cases of operation_tests, one pattern each and in pairs.
+    let ops = [
+        "read:/user/*",
+        "read:/user/a.txt",
+        "read:/user/docs/*",
+        "read:/user/a*",
+        "read:/user/**",
+        "read:/user/**/*.csv",
+        "read:/user/*.csv",
+        "read:/user",
+        "*:/user/*",
+        "write:/user/a.txt",
+        "read-all",
+        "read",
+        "read*",
+        "*",
+    ];
+    for (i, a) in ops.iter().enumerate() {
+        cases.push(inv(&[a], Default::default()));
+        cases.push(inv(&[a, ops[(i + 3) % ops.len()]], Default::default()));
+    }
+    // The residency and contract cases of residency_tests and this file.
+    let list = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    for contract in [
+        ExecutionContract {
+            allowed_regions: list(&["eu-1", "eu-2"]),
+            ..Default::default()
+        },
+        ExecutionContract {
+            allowed_regions: list(&["eu-2"]),
+            allowed_environments: list(&["production"]),
+            ..Default::default()
+        },
+        ExecutionContract {
+            allowed_regions: list(&["eu-1", "us-1"]),
+            ..Default::default()
+        },
+        ExecutionContract {
+            allowed_environments: list(&["staging"]),
+            ..Default::default()
+        },
+        ExecutionContract {
+            role: "analyst".to_string(),
+            compliance: list(&["GDPR"]),
+            ..model("deterministic")
+        },
+        model("agentic"),
+        model("unknown"),
+    ] {
+        cases.push(inv(&["write:/user/*"], contract));
+    }
+    // The constraint cases of constraint_tests.
+    let night = |from, to| Constraint::TimeOfDay(TimeOfDay::new(from, to));
+    for (name, constraint) in [
+        ("amountEur", Constraint::Max(500)),
+        ("amountEur", Constraint::Max(501)),
+        ("currency", Constraint::one_of(&["EUR", "USD"])),
+        ("currency", Constraint::one_of(&["EUR", "GBP"])),
+        ("currency", Constraint::Max(1)),
+        ("hours", night("22:00", "02:00")),
+        ("hours", night("23:00", "01:00")),
+        ("hours", night("21:00", "01:00")),
+    ] {
+        cases.push(inv(&["write:/user/*"], Default::default()).with_constraint(name, constraint));
+    }
+
+    for current in &cases {
+        for predecessor in &cases {
+            diff_checked(current, predecessor);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Execution-model lattice tests: the fixture lattice is a partial order with
+//! incomparable models and meets, malformed lattices are refused, and the
+//! reference profile checks contracts under the lattice it is given, refusing
+//! models that lattice does not declare.
+
+use chrono::Utc;
+use pic::{
+    attenuates, conforms, fixtureset, mint_pca0, AuthorityProfile, ContractAttributes,
+    ExecutionContract, Invariants, LatticeError, ModelLattice, PicError, Prover, ReferenceProfile,
+    Request, Verifier, Violation,
+};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+fn lattice(within: &[(&str, &[&str])]) -> Result<ModelLattice, LatticeError> {
+    ModelLattice::new(
+        within
+            .iter()
+            .map(|(m, above)| (m.to_string(), above.iter().map(|a| a.to_string()).collect()))
+            .collect::<BTreeMap<String, BTreeSet<String>>>(),
+    )
+}
+
+fn contract(model: &str) -> ExecutionContract {
+    ExecutionContract {
+        execution_model: model.to_string(),
+        ..Default::default()
+    }
+}
+
+fn attested(model: &str) -> ContractAttributes {
+    ContractAttributes {
+        execution_model: model.to_string(),
+        ..Default::default()
+    }
+}
+
+fn inv(model: &str) -> Invariants {
+    Invariants {
+        operations: vec!["read:/user/*".to_string()],
+        execution_contract: contract(model),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn the_fixture_lattice_is_a_partial_order() {
+    let models = &fixtureset::load().expect("fixtures").execution_models;
+    assert_eq!(
+        models.compare("deterministic", "agentic"),
+        Ok(Some(Ordering::Less))
+    );
+    assert_eq!(
+        models.compare("autonomous", "human-in-the-loop"),
+        Ok(Some(Ordering::Greater))
+    );
+    assert_eq!(
+        models.compare("human-in-the-loop", "sandboxed-agentic"),
+        Ok(None)
+    );
+    assert_eq!(
+        models.meet("human-in-the-loop", "sandboxed-agentic"),
+        Ok(Some("deterministic"))
+    );
+    assert_eq!(
+        models.meet("agentic", "sandboxed-agentic"),
+        Ok(Some("sandboxed-agentic"))
+    );
+    assert_eq!(models.meet("", "agentic"), Ok(Some("agentic")));
+    assert_eq!(models.restricts("autonomous", ""), Ok(true));
+    assert_eq!(models.restricts("", "autonomous"), Ok(false));
+    assert_eq!(
+        models.restricts("quantum", "agentic"),
+        Err(LatticeError::Unknown {
+            model: "quantum".to_string(),
+        })
+    );
+
+    // The fixture lattice extends the reference order.
+    let reference = ModelLattice::reference();
+    for m in reference.models() {
+        assert!(models.contains(m));
+    }
+    assert_eq!(
+        reference.compare("deterministic", "agentic"),
+        Ok(Some(Ordering::Less))
+    );
+    assert!(!reference.contains("autonomous"));
+}
+
+#[test]
+fn malformed_lattices_are_refused() {
+    assert_eq!(
+        lattice(&[("deterministic", &["agentic"])]),
+        Err(LatticeError::Undeclared {
+            model: "agentic".to_string(),
+            within: "deterministic".to_string(),
+        })
+    );
+    assert!(matches!(
+        lattice(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]),
+        Err(LatticeError::Cycle { .. })
+    ));
+    assert_eq!(lattice(&[("", &[])]), Err(LatticeError::EmptyModel));
+
+    // Two maximal lower bounds: no meet.
+    let diamond = lattice(&[
+        ("x", &["a", "b"]),
+        ("y", &["a", "b"]),
+        ("a", &[]),
+        ("b", &[]),
+    ])
+    .expect("lattice");
+    assert_eq!(diamond.meet("a", "b"), Ok(None));
+
+    let err = serde_json::from_str::<ModelLattice>(r#"{"models": {"a": ["b"]}}"#).unwrap_err();
+    assert!(err.to_string().contains("undeclared model \"b\""), "{err}");
+    let json = serde_json::to_string(ModelLattice::reference()).expect("encode");
+    assert_eq!(
+        json,
+        r#"{"models":{"agentic":[],"deterministic":["agentic"]}}"#
+    );
+}
+
+#[test]
+fn contracts_are_checked_under_the_profile_lattice() {
+    // The reference lattice does not know "autonomous": no longer unconstrained.
+    assert_eq!(
+        conforms(&attested("autonomous"), &contract("agentic")),
+        Err(Violation::UnknownExecutionModel {
+            model: "autonomous".to_string(),
+        })
+    );
+    assert!(matches!(
+        attenuates(&inv("autonomous"), &inv("")),
+        Err(Violation::UnknownExecutionModel { .. })
+    ));
+    conforms(&attested("deterministic"), &contract("")).expect("unset contract");
+    assert!(matches!(
+        conforms(&attested(""), &contract("agentic")),
+        Err(Violation::ExecutionModelNotPermitted { .. })
+    ));
+
+    let set = fixtureset::load().expect("fixtures");
+    let profile = ReferenceProfile::with_models(set.execution_models.clone());
+    profile
+        .conforms(&attested("human-in-the-loop"), &contract("agentic"))
+        .expect("more restrictive");
+    assert_eq!(
+        profile.conforms(
+            &attested("human-in-the-loop"),
+            &contract("sandboxed-agentic")
+        ),
+        Err(Violation::ExecutionModelNotPermitted {
+            attested: "human-in-the-loop".to_string(),
+            permitted: "sandboxed-agentic".to_string(),
+        })
+    );
+    profile
+        .attenuates(&inv("sandboxed-agentic"), &inv("autonomous"))
+        .expect("narrowed");
+    assert!(matches!(
+        profile.attenuates(&inv("human-in-the-loop"), &inv("sandboxed-agentic")),
+        Err(Violation::ExecutionModelRelaxed { .. })
+    ));
+}
+
+#[test]
+fn prover_and_verifier_share_the_configured_lattice() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let profile = ReferenceProfile::with_models(set.execution_models.clone());
+    let pca0 = mint_pca0(set.identity("alice"), inv("human-in-the-loop"), None, now).expect("mint");
+    let req = Request {
+        operation: "read".to_string(),
+        target: "/user/a.txt".to_string(),
+        ..Default::default()
+    };
+
+    let gateway = Prover::new(set.identity("gateway"), set.attestation("gateway"));
+    let err = gateway
+        .continue_(&pca0, inv("deterministic"), req.clone(), now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::SelfCheck(Violation::UnknownExecutionModel { .. })
+    ));
+    let pca1 = gateway
+        .with_authority(&profile)
+        .continue_(&pca0, inv("deterministic"), req, now)
+        .expect("deterministic within human-in-the-loop");
+    let chain = [pca0, pca1];
+
+    Verifier::new(&set.registry, None)
+        .with_authority(&profile)
+        .verify_full_chain(&chain, now)
+        .expect("configured lattice");
+    let err = Verifier::new(&set.registry, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.5-CONFORMANCE");
+}
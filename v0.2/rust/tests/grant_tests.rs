@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Grant tests: a PCA0 minted under a signed grant must be issued by its
+//! grantee, within its validity, with invariants that attenuate it; a
+//! Verifier holding the grants refuses unknown or forged ones; and only the
+//! grantor can revoke a grant.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::granting;
+use pic::{
+    fixtureset, mint_pca0, rfc3339, sign_grant, sign_revocation, Grant, GrantError, GrantRegistry,
+    OriginError, PicError, Revocation, RevocationStore, Verifier,
+};
+
+const BACKUP: &str = "urn:pic:grant:user-backup";
+
+fn grant_error(err: PicError) -> GrantError {
+    match err {
+        PicError::Origin(OriginError::Grant(e)) => e,
+        other => panic!("expected a grant error, got {other:?}"),
+    }
+}
+
+#[test]
+fn mint_requires_an_attenuating_grant_held_by_the_issuer() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let grant = set.grant(BACKUP);
+    let pca0 = mint_pca0(
+        set.identity("alice"),
+        granting(&["backup"]),
+        Some(grant),
+        now,
+    )
+    .expect("attenuated origin");
+    assert_eq!(pca0.grant_id, BACKUP);
+
+    let err = mint_pca0(
+        set.identity("gateway"),
+        granting(&["backup"]),
+        Some(grant),
+        now,
+    )
+    .unwrap_err();
+    assert_eq!(
+        grant_error(err),
+        GrantError::GranteeMismatch {
+            grantee: "did:web:alice.example".to_string(),
+            issuer: "did:web:gateway.example".to_string(),
+        }
+    );
+
+    let err = mint_pca0(
+        set.identity("alice"),
+        granting(&["backup", "delete"]),
+        Some(grant),
+        now,
+    )
+    .unwrap_err();
+    assert!(matches!(grant_error(err), GrantError::NotAttenuated(_)));
+
+    let expired = sign_grant(
+        Grant {
+            id: "urn:pic:grant:expired".to_string(),
+            grantee: "did:web:alice.example".to_string(),
+            invariants: granting(&["backup"]),
+            issued_at: rfc3339(now - Duration::days(2)),
+            expires_at: rfc3339(now - Duration::days(1)),
+            ..Default::default()
+        },
+        set.identity("org-authority"),
+    )
+    .expect("sign");
+    let err = mint_pca0(
+        set.identity("alice"),
+        granting(&["backup"]),
+        Some(&expired),
+        now,
+    )
+    .unwrap_err();
+    assert!(matches!(grant_error(err), GrantError::Validity(_)));
+
+    let unsigned = Grant {
+        proof: None,
+        ..grant.clone()
+    };
+    let err = mint_pca0(
+        set.identity("alice"),
+        granting(&["backup"]),
+        Some(&unsigned),
+        now,
+    )
+    .unwrap_err();
+    assert_eq!(grant_error(err), GrantError::MissingSignature);
+}
+
+#[test]
+fn verifier_refuses_unknown_and_forged_grants() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let alice = set.identity("alice");
+    let pca0 = mint_pca0(alice, granting(&["backup"]), Some(set.grant(BACKUP)), now).expect("mint");
+    Verifier::new(&set.registry, None)
+        .with_grants(&set.grants)
+        .verify_origin(&pca0, now)
+        .expect("trusted grant");
+
+    let err = Verifier::new(&set.registry, None)
+        .with_grants(&GrantRegistry::new())
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.2-ORIGIN");
+    assert_eq!(
+        grant_error(err),
+        GrantError::Unknown {
+            grant_id: BACKUP.to_string(),
+        }
+    );
+
+    // Alice widens her own grant and signs it in org-authority's name.
+    let mut forged = set.grant(BACKUP).clone();
+    forged.invariants = granting(&["read-all", "backup", "delete"]);
+    let forged = Grant {
+        grantor: forged.grantor.clone(),
+        ..sign_grant(forged, alice).expect("sign")
+    };
+    let pca0 = mint_pca0(alice, granting(&["delete"]), Some(&forged), now).expect("mint");
+    let grants: GrantRegistry = [forged].into_iter().collect();
+    let err = Verifier::new(&set.registry, None)
+        .with_grants(&grants)
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    assert!(matches!(grant_error(err), GrantError::Signature(_)));
+}
+
+#[test]
+fn only_the_grantor_revokes_a_grant() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(
+        set.identity("alice"),
+        granting(&["backup"]),
+        Some(set.grant(BACKUP)),
+        now,
+    )
+    .expect("mint");
+    let mut store = RevocationStore::new();
+
+    let by_grantee =
+        sign_revocation(Revocation::grant(BACKUP), set.identity("alice")).expect("sign");
+    let err = store
+        .revoke_grant(by_grantee, &set.grants, &set.registry, now)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        GrantError::NotGrantor {
+            grant_id: BACKUP.to_string(),
+            grantor: "did:web:org-authority.example".to_string(),
+            issuer: "did:web:alice.example".to_string(),
+        }
+    );
+
+    // Claiming the grantor without its key fails on the signature.
+    let mut claimed =
+        sign_revocation(Revocation::grant(BACKUP), set.identity("alice")).expect("sign");
+    claimed.issuer = "did:web:org-authority.example".to_string();
+    let err = store
+        .revoke_grant(claimed, &set.grants, &set.registry, now)
+        .unwrap_err();
+    assert!(matches!(err, GrantError::Signature(_)));
+    Verifier::new(&set.registry, Some(&store))
+        .verify_origin(&pca0, now)
+        .expect("not yet revoked");
+
+    let by_grantor =
+        sign_revocation(Revocation::grant(BACKUP), set.identity("org-authority")).expect("sign");
+    store
+        .revoke_grant(by_grantor, &set.grants, &set.registry, now)
+        .expect("grantor revocation");
+    let err = Verifier::new(&set.registry, Some(&store))
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-REV-3.1-REVOKED");
+}
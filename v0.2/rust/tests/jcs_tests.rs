@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! RFC 8785 canonicalization against the shared vectors in
+//! `v0.2/fixtures/canonical/jcs.json`: number formatting, member order, string
+//! escaping, the digests of documents carrying Unicode resource paths and
+//! tenant names, and the refusal of numbers beyond ±2^53.
+
+use chrono::Utc;
+use pic::codec::{from_cbor, to_cbor};
+use pic::{
+    canonical_json, digest_of, from_untrusted_bytes, jcs, mint_pca0, CodecError, Constraint,
+    Identity, Invariants, Limits, PicError, Request,
+};
+use serde_json::Value;
+use std::path::Path;
+
+fn vectors() -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("fixtures")
+        .join("canonical")
+        .join("jcs.json");
+    let raw = std::fs::read(&path).unwrap_or_else(|e| panic!("read {}: {e}", path.display()));
+    serde_json::from_slice(&raw).expect("parse jcs vectors")
+}
+
+#[test]
+fn numbers_match_rfc8785_appendix_b() {
+    let v = vectors();
+    for n in v["numbers"].as_array().expect("numbers") {
+        let bits = u64::from_str_radix(n["ieee"].as_str().unwrap(), 16).unwrap();
+        let want = n["canonical"].as_str().unwrap();
+        assert_eq!(
+            jcs::format_number(f64::from_bits(bits)).as_deref(),
+            Some(want),
+            "ieee {}",
+            n["ieee"]
+        );
+    }
+    assert_eq!(jcs::format_number(f64::NAN), None);
+    assert_eq!(jcs::format_number(f64::INFINITY), None);
+}
+
+#[test]
+fn documents_canonicalize_and_digest_as_published() {
+    let v = vectors();
+    for d in v["documents"].as_array().expect("documents") {
+        let name = d["name"].as_str().unwrap();
+        let got = String::from_utf8(canonical_json(&d["input"])).unwrap();
+        assert_eq!(got, d["canonical"].as_str().unwrap(), "{name}");
+        assert_eq!(
+            digest_of(&d["input"]),
+            d["digest"].as_str().unwrap(),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn typed_documents_share_the_vectors() {
+    let v = vectors();
+    let doc = v["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["name"] == "cjk-tenant")
+        .expect("cjk-tenant vector");
+    let req: Request = serde_json::from_value(doc["input"].clone()).expect("request");
+    assert_eq!(req.security_domain, "テナント-42");
+    assert_eq!(digest_of(&req), doc["digest"].as_str().unwrap());
+}
+
+#[test]
+fn member_order_of_the_input_is_irrelevant() {
+    let a: Value = serde_json::from_str(r#"{"b":1,"a":{"y":[1,2],"x":"é"}}"#).unwrap();
+    let b: Value = serde_json::from_str(r#"{"a":{"x":"é","y":[1,2]},"b":1}"#).unwrap();
+    assert_eq!(canonical_json(&a), canonical_json(&b));
+    assert_eq!(
+        String::from_utf8(jcs::to_vec(&a)).unwrap(),
+        r#"{"a":{"x":"é","y":[1,2]},"b":1}"#
+    );
+}
+
+#[test]
+fn numbers_beyond_2_53_are_refused() {
+    let v = vectors();
+    for d in v["rejected"].as_array().expect("rejected") {
+        let name = d["name"].as_str().unwrap();
+        let json = d["json"].as_str().unwrap();
+        assert!(
+            matches!(
+                from_untrusted_bytes::<Value>(json.as_bytes(), &Limits::default()),
+                Err(PicError::Codec(CodecError::InexactNumber { .. }))
+            ),
+            "{name}"
+        );
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert!(jcs::exact_numbers(&value).is_err(), "{name}");
+    }
+
+    // 2^53 + 1 would sign as 2^53, so neither signing nor CBOR admits it.
+    let max = |n| Invariants::default().with_constraint("amountEur", Constraint::Max(n));
+    let exact = jcs::MAX_EXACT_INTEGER;
+    assert_eq!(canonical_json(&max(exact + 1)), canonical_json(&max(exact)));
+    let alice = Identity::new("did:example:alice");
+    let now = Utc::now();
+    mint_pca0(&alice, max(exact), None, now).expect("2^53 is exact");
+    let err = mint_pca0(&alice, max(exact + 1), None, now).unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-6.4-ENCODING");
+    assert!(matches!(
+        from_cbor::<Invariants>(&to_cbor(&max(exact + 1))),
+        Err(CodecError::InexactNumber { .. })
+    ));
+    assert_eq!(
+        from_cbor::<Invariants>(&to_cbor(&max(exact))).ok(),
+        Some(max(exact))
+    );
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Key lifecycle tests: versioned keys per controller, selection of the key
+//! valid at a document's `issuedAt`, bare-DID resolution to the keys valid at
+//! an instant, COSE seals checked at the decoding instant, and compromised
+//! keys.
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use common::{inv, req_in};
+use pic::codec::{decode_cose, encode_cose};
+use pic::{
+    mint_pca0, sign_attestation, Attestation, CodecError, Identity, IntegrityError, OriginError,
+    Pca, PicError, Prover, Registry, SignatureError, Verifier,
+};
+
+/// Key version `n` (a fresh key) of `id`'s controller.
+fn next_version(id: &Identity, n: u32) -> Identity {
+    let mut next = Identity::new(&id.id);
+    next.verification_method = format!("{}#key-{n}", id.id);
+    next
+}
+
+struct Cast {
+    reg: Registry,
+    alice: Identity,
+    org: Identity,
+    gateway: Identity,
+}
+
+impl Cast {
+    fn new() -> Cast {
+        let alice = Identity::new("did:example:alice");
+        let org = Identity::new("did:example:org");
+        let gateway = Identity::new("did:example:gateway");
+        let mut reg = Registry::new();
+        reg.add(&alice);
+        reg.add(&org);
+        reg.add(&gateway);
+        Cast {
+            reg,
+            alice,
+            org,
+            gateway,
+        }
+    }
+
+    /// PCA0 by `origin` at `t`, continued by `executor` at `t`.
+    fn chain(&self, origin: &Identity, executor: &Identity, t: DateTime<Utc>) -> Vec<Pca> {
+        let att = sign_attestation(
+            Attestation {
+                subject: executor.id.clone(),
+                issued_at: pic::rfc3339(t - Duration::hours(1)),
+                expires_at: pic::rfc3339(t + Duration::hours(24)),
+                ..Default::default()
+            },
+            &self.org,
+        )
+        .expect("sign attestation");
+        let pca0 = mint_pca0(origin, inv(), None, t).expect("mint");
+        let pca1 = Prover::new(executor, att)
+            .continue_(&pca0, inv(), req_in("tenant-1"), t)
+            .expect("hop");
+        vec![pca0, pca1]
+    }
+}
+
+#[test]
+fn rotation_keeps_earlier_chains_verifiable() {
+    let t0 = Utc::now() - Duration::hours(1);
+    let rotated = t0 + Duration::minutes(1);
+    let mut cast = Cast::new();
+    let before = cast.chain(&cast.alice, &cast.gateway, t0);
+
+    let alice2 = next_version(&cast.alice, 2);
+    let gateway2 = next_version(&cast.gateway, 2);
+    cast.reg.rotate(&alice2, rotated).expect("rotate alice");
+    cast.reg.rotate(&gateway2, rotated).expect("rotate gateway");
+    assert!(cast.reg.rotate(&alice2, rotated).is_err());
+
+    // The old chain still verifies, under the keys valid when it was signed.
+    let check = t0 + Duration::minutes(2);
+    Verifier::new(&cast.reg, None)
+        .verify_full_chain(&before, check)
+        .expect("pre-rotation chain");
+    Verifier::new(&cast.reg, None)
+        .verify_full_chain_batched(&before, check)
+        .expect("pre-rotation chain, batched");
+
+    // New documents verify under the new versions, not the retired ones.
+    let t1 = rotated + Duration::seconds(30);
+    let after = cast.chain(&alice2, &gateway2, t1);
+    Verifier::new(&cast.reg, None)
+        .verify_full_chain(&after, check)
+        .expect("post-rotation chain");
+    let stale = cast.chain(&cast.alice, &cast.gateway, t1);
+    let err = Verifier::new(&cast.reg, None)
+        .verify_origin(&stale[0], check)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Origin(OriginError::Signature(SignatureError::KeyNotValid { .. }))
+    ));
+}
+
+#[test]
+fn bare_did_resolves_to_the_keys_valid_then() {
+    let now = Utc::now();
+    let rotated = now - Duration::minutes(10);
+    let mut cast = Cast::new();
+    let alice2 = next_version(&cast.alice, 2);
+    cast.reg.rotate(&alice2, rotated).expect("rotate");
+
+    let vms = |keys: Vec<std::sync::Arc<pic::KeyEntry>>| -> Vec<String> {
+        keys.iter().map(|e| e.verification_method.clone()).collect()
+    };
+    assert_eq!(
+        vms(cast.reg.current_keys("did:example:alice")),
+        ["did:example:alice#key-2"]
+    );
+    assert_eq!(
+        vms(cast
+            .reg
+            .keys_at("did:example:alice", rotated - Duration::seconds(1))),
+        ["did:example:alice#key-1"]
+    );
+    // A retired version is still known, with its window.
+    let old = cast.reg.entry("did:example:alice#key-1").expect("entry");
+    assert_eq!(old.not_after, Some(rotated));
+    assert!(cast.reg.current_keys("did:example:alice#key-1").is_empty());
+
+    // During an overlap both versions resolve.
+    let mut overlap = pic::KeyEntry::new(&next_version(&cast.alice, 3));
+    overlap.not_before = Some(now - Duration::minutes(1));
+    cast.reg.add_key(overlap);
+    assert_eq!(cast.reg.current_keys("did:example:alice").len(), 2);
+}
+
+#[test]
+fn cose_seals_check_against_the_key_valid_at_decoding() {
+    let t0 = Utc::now() - Duration::hours(1);
+    let rotated = t0 + Duration::minutes(1);
+    let mut cast = Cast::new();
+    let pcas = cast.chain(&cast.alice, &cast.gateway, t0);
+    let gateway2 = next_version(&cast.gateway, 2);
+    let old_seal = encode_cose(&pcas[1], &cast.gateway).expect("seal");
+    let new_seal = encode_cose(&pcas[1], &gateway2).expect("seal");
+    cast.reg.rotate(&gateway2, rotated).expect("rotate");
+
+    // Replaying at a fixed instant gives the same answer after the rotation.
+    let before = t0 + Duration::seconds(30);
+    let after = rotated + Duration::seconds(30);
+    let decode = |bytes: &[u8], at| decode_cose::<Pca>(&cast.reg, bytes, at);
+    assert_eq!(
+        decode(&old_seal, before).expect("old key, before").digest(),
+        pcas[1].digest()
+    );
+    decode(&new_seal, after).expect("new key, after");
+    for (bytes, at) in [(&old_seal, after), (&new_seal, before)] {
+        assert!(matches!(
+            decode(bytes, at).unwrap_err(),
+            PicError::Codec(CodecError::Seal(SignatureError::KeyNotValid { .. }))
+        ));
+    }
+}
+
+#[test]
+fn compromised_key_rejects_later_signatures() {
+    let t0 = Utc::now() - Duration::hours(1);
+    let compromised = t0 + Duration::minutes(1);
+    let mut cast = Cast::new();
+    let before = cast.chain(&cast.alice, &cast.gateway, t0);
+    let after = cast.chain(&cast.alice, &cast.gateway, compromised);
+    let vm = cast.gateway.verification_method.clone();
+    cast.reg.mark_compromised(&vm, compromised).expect("mark");
+    assert!(matches!(
+        cast.reg.mark_compromised("did:example:nobody#key-1", t0),
+        Err(PicError::Key(_))
+    ));
+
+    let check = t0 + Duration::minutes(2);
+    Verifier::new(&cast.reg, None)
+        .verify_full_chain(&before, check)
+        .expect("signed before the compromise");
+    let seq = Verifier::new(&cast.reg, None)
+        .verify_full_chain(&after, check)
+        .unwrap_err();
+    let batch = Verifier::new(&cast.reg, None)
+        .verify_full_chain_batched(&after, check)
+        .unwrap_err();
+    assert_eq!(seq, batch);
+    assert!(matches!(
+        seq,
+        PicError::Integrity {
+            hop: Some(1),
+            error: IntegrityError::Signature(SignatureError::KeyCompromised { .. })
+        }
+    ));
+    assert!(cast.reg.current_keys("did:example:gateway").is_empty());
+}
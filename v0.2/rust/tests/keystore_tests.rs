@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Keystore tests: identities round-trip through the encrypted file, the
+//! passphrase and every entry are checked, rotation keeps retired versions
+//! verifiable, and the fixture keystore holds the fixture cast.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::inv;
+use pic::keystore::{identity_from_jwk, identity_to_jwk};
+use pic::{
+    fixtureset, mint_pca0, Identity, KdfParams, Keystore, KeystoreError, PicError, SignatureSuite,
+    Verifier,
+};
+use std::path::PathBuf;
+
+/// Cheap KDF parameters; the cost of the real ones is not under test.
+const FAST: KdfParams = KdfParams {
+    memory_kib: 64,
+    iterations: 1,
+    parallelism: 1,
+};
+
+/// A fresh keystore path, removed on drop.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str) -> Scratch {
+        let path =
+            std::env::temp_dir().join(format!("pic-keystore-{}-{name}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Scratch(path)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// The base64url secret of `id`, read back from its private JWK.
+fn secret(id: &Identity) -> String {
+    let jwk: serde_json::Value = serde_json::from_str(&identity_to_jwk(id)).expect("jwk");
+    jwk["d"].as_str().expect("d").to_string()
+}
+
+#[test]
+fn identities_round_trip_under_the_passphrase() {
+    let scratch = Scratch::new("round-trip");
+    let alice = Identity::new("did:example:alice");
+    let org = Identity::new_with("did:example:org", SignatureSuite::EcdsaP256);
+    let mut ks = Keystore::create_with(&scratch.0, "correct horse", FAST).expect("create");
+    ks.import("alice", &alice).expect("import alice");
+    ks.import("org", &org).expect("import org");
+    assert!(Keystore::create(&scratch.0, "again").is_err());
+
+    // Nothing secret is written in the clear.
+    let raw = std::fs::read_to_string(&scratch.0).expect("read");
+    assert!(!raw.contains(&secret(&alice)) && !raw.contains(&secret(&org)));
+
+    let ks = Keystore::open(&scratch.0, "correct horse").expect("open");
+    let listed = ks.list().expect("list");
+    assert_eq!(
+        listed.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        ["alice", "org"]
+    );
+    assert_eq!(listed[1].keys[0].key, org.public_key());
+    for (name, original) in [("alice", &alice), ("org", &org)] {
+        let exported = ks.export(name).expect("export");
+        assert_eq!(exported.id, original.id);
+        assert_eq!(exported.verification_method, original.verification_method);
+        assert_eq!(secret(&exported), secret(original));
+        let jwk = ks.export_jwk(name).expect("export jwk");
+        assert_eq!(
+            secret(&identity_from_jwk(jwk.as_bytes()).expect("jwk")),
+            secret(original)
+        );
+    }
+
+    assert_eq!(
+        Keystore::open(&scratch.0, "wrong horse").err(),
+        Some(PicError::Keystore(KeystoreError::WrongPassphrase))
+    );
+    let mut ks = ks;
+    ks.import("alice", &alice).expect("re-import is a no-op");
+    assert!(matches!(
+        ks.import("alice", &Identity::new("did:example:alice")),
+        Err(PicError::Keystore(KeystoreError::Conflict { .. }))
+    ));
+    assert!(matches!(
+        ks.export("mallory"),
+        Err(PicError::Keystore(KeystoreError::UnknownIdentity { .. }))
+    ));
+}
+
+#[test]
+fn swapped_entries_do_not_decrypt() {
+    let scratch = Scratch::new("swapped");
+    let mut ks = Keystore::create_with(&scratch.0, "pass", FAST).expect("create");
+    ks.import("alice", &Identity::new("did:example:alice"))
+        .expect("import");
+    ks.import("bob", &Identity::new("did:example:bob"))
+        .expect("import");
+
+    // Move bob's sealed secret under alice.
+    let mut file: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&scratch.0).expect("read")).expect("json");
+    let bob = file["identities"]["bob"]["versions"][0]["secret"].clone();
+    file["identities"]["alice"]["versions"][0]["secret"] = bob;
+    std::fs::write(&scratch.0, file.to_string()).expect("write");
+
+    let ks = Keystore::open(&scratch.0, "pass").expect("open");
+    assert!(ks.export("bob").is_ok());
+    let err = ks.export("alice").err().expect("swapped secret");
+    assert_eq!(err.code(), "PIC-KEYSTORE");
+    assert!(matches!(
+        err,
+        PicError::Keystore(KeystoreError::Corrupt { .. })
+    ));
+    assert!(ks.load().is_err());
+}
+
+#[test]
+fn rotation_keeps_retired_versions_verifiable() {
+    let scratch = Scratch::new("rotation");
+    let t0 = Utc::now() - Duration::hours(1);
+    let rotated = t0 + Duration::minutes(1);
+    let alice = Identity::new("did:example:alice");
+    let mut ks = Keystore::create_with(&scratch.0, "pass", FAST).expect("create");
+    ks.import("alice", &alice).expect("import");
+    let before = mint_pca0(&alice, inv(), None, t0).expect("mint");
+
+    let next = ks.rotate("alice", rotated).expect("rotate");
+    assert_eq!(next.verification_method, "did:example:alice#key-2");
+    let after = mint_pca0(&next, inv(), None, rotated + Duration::seconds(1)).expect("mint");
+    let stale = mint_pca0(&alice, inv(), None, rotated + Duration::seconds(1)).expect("mint");
+
+    let loaded = Keystore::open(&scratch.0, "pass")
+        .and_then(|k| k.load())
+        .expect("load");
+    assert_eq!(secret(&loaded.identities["alice"]), secret(&next));
+    let keys = &Keystore::open(&scratch.0, "pass")
+        .and_then(|k| k.list())
+        .expect("list")[0]
+        .keys;
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0].not_after, Some(rotated));
+    assert_eq!(keys[1].not_before, Some(rotated));
+
+    let now = rotated + Duration::minutes(1);
+    let v = Verifier::new(&loaded.registry, None);
+    v.verify_origin(&before, now)
+        .expect("signed before rotation");
+    v.verify_origin(&after, now).expect("signed after rotation");
+    assert!(v.verify_origin(&stale, now).is_err());
+}
+
+#[test]
+fn tampered_retired_versions_are_refused() {
+    let scratch = Scratch::new("retired");
+    let rotated = Utc::now() - Duration::hours(1);
+    let mut ks = Keystore::create_with(&scratch.0, "pass", FAST).expect("create");
+    ks.import("alice", &Identity::new("did:example:alice"))
+        .expect("import");
+    ks.rotate("alice", rotated).expect("rotate");
+    let honest = std::fs::read(&scratch.0).expect("read");
+    let mallory = Identity::new("did:example:alice");
+
+    // Swap in an attacker key and reopen the retired window, or only move
+    // the window: either way the retired version no longer decrypts.
+    let tampers: [&dyn Fn(&mut serde_json::Value); 2] = [
+        &|v| {
+            v["publicKey"] = mallory.encode_public().into();
+            v.as_object_mut().expect("version").remove("notAfter");
+        },
+        &|v| v["notAfter"] = pic::rfc3339(Utc::now() + Duration::days(1)).into(),
+    ];
+    for tamper in tampers {
+        let mut file: serde_json::Value = serde_json::from_slice(&honest).expect("json");
+        tamper(&mut file["identities"]["alice"]["versions"][0]);
+        std::fs::write(&scratch.0, file.to_string()).expect("write");
+        let ks = Keystore::open(&scratch.0, "pass").expect("open");
+        assert!(ks.export("alice").is_ok(), "the current version is intact");
+        assert!(matches!(
+            ks.load().err(),
+            Some(PicError::Keystore(KeystoreError::Corrupt { .. }))
+        ));
+    }
+}
+
+#[test]
+fn fixture_keystore_holds_the_fixture_cast() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures");
+    let ks = Keystore::open(
+        dir.join(fixtureset::KEYSTORE_FILE),
+        fixtureset::FIXTURE_PASSPHRASE,
+    )
+    .expect("open fixture keystore");
+    let set = fixtureset::load().expect("fixtures");
+    let listed = ks.list().expect("list");
+    assert_eq!(listed.len(), set.identities.len());
+    for stored in listed {
+        let jwk = std::fs::read(
+            dir.join("identities")
+                .join(&stored.name)
+                .join("private.jwk"),
+        )
+        .expect("private.jwk");
+        let plain = identity_from_jwk(&jwk).expect("jwk");
+        assert_eq!(stored.id, plain.id);
+        assert_eq!(stored.keys[0].key, plain.public_key());
+        assert_eq!(secret(set.identity(&stored.name)), secret(&plain));
+    }
+}
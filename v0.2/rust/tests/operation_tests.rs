@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Operation grammar tests: segment globs, `**`, exact segments and the
+//! trailing-`*` prefix; attenuation by pattern subsumption (narrowing accepted,
+//! widening refused, end to end through the Prover and Verifier); and
+//! authorization under the same grammar.
+
+mod common;
+
+use chrono::Utc;
+use common::{granting, request};
+use pic::operation::covers;
+use pic::{
+    attenuates, authorize, fixtureset, mint_pca0, Operation, PicError, Prover, Verifier, Violation,
+};
+
+#[test]
+fn subsumption_follows_the_segment_grammar() {
+    let narrower = [
+        ("read:/user/*", "read:/user/a.txt"),
+        ("read:/user/*", "read:/user/docs/a.txt"),
+        ("read:/user/*", "read:/user/docs/*"),
+        ("read:/user/a*", "read:/user/abc/d.txt"),
+        ("read:/user/**", "read:/user/*"),
+        ("read:/user/**", "read:/user/docs/*"),
+        ("read:/user/**", "read:/user/*/report.csv"),
+        ("read:/user/**", "read:/user/**/*.csv"),
+        ("read:/user/**", "read:/user"),
+        ("read:/user/*", "read:/user/*.csv"),
+        ("read:/user/*.csv", "read:/user/q*.csv"),
+        ("read:/**/*.csv", "read:/user/docs/a.csv"),
+        ("*:/user/*", "write:/user/a.txt"),
+        ("read:/a/**/**", "read:/a/**"),
+        ("read-all", "read-all"),
+        ("*", "read:/x"),
+        ("*", "read-all"),
+        ("*", "*:/user/**"),
+        ("re*", "read:/x"),
+        ("read*", "read:/user/a"),
+        ("read*", "read"),
+        ("read*", "readme"),
+        ("read*", "read:/user/*"),
+        ("read*", "read:/user/**"),
+        ("read*", "readme*"),
+    ];
+    for (granted, op) in narrower {
+        assert!(covers(granted, op), "{granted} should cover {op}");
+    }
+    let wider_or_disjoint = [
+        ("read:/user/*", "read:/user/**"),
+        ("read:/user/*", "read:/user"),
+        ("read:/user/*.csv", "read:/user/docs/a.csv"),
+        ("read:/user/*/a.txt", "read:/user/docs/tmp/a.txt"),
+        ("read:/user/docs/*", "read:/user/*"),
+        ("read:/user/*.csv", "read:/user/*"),
+        ("read:/user/a*", "read:/user/*a"),
+        ("read:/user/**", "write:/user/a.txt"),
+        ("read:/user/**", "read:user/a.txt"),
+        ("read:/user/**", "read"),
+        ("read", "read:/user/a.txt"),
+        ("read:/user/*", "*:/user/a.txt"),
+        ("read*", "write:/user/a"),
+        ("read*", "*:/user/a"),
+        ("read*", "re*"),
+        ("*:/**", "read*"),
+        ("*:**", "read-all"),
+    ];
+    for (granted, op) in wider_or_disjoint {
+        assert!(!covers(granted, op), "{granted} should not cover {op}");
+    }
+
+    let op = Operation::parse("read:/user/**/*.csv");
+    assert_eq!(op.to_string(), "read:/user/**/*.csv");
+    assert_eq!(Operation::parse("read-all").path, None);
+    assert_eq!(Operation::parse("read*").to_string(), "read*");
+}
+
+#[test]
+fn narrowing_attenuates_and_widening_is_expansion() {
+    attenuates(
+        &granting(&["read:/user/docs/*"]),
+        &granting(&["read:/user/*"]),
+    )
+    .expect("narrowed");
+    attenuates(
+        &granting(&["read:/user/docs/*"]),
+        &granting(&["read:/user/**"]),
+    )
+    .expect("narrowed");
+    attenuates(&granting(&["read:/user/a", "backup"]), &granting(&["*"])).expect("narrowed");
+    attenuates(&granting(&["read:/user/*"]), &granting(&["read*"])).expect("narrowed");
+    attenuates(
+        &granting(&["read:/user/docs/*", "write:/user/tmp/*"]),
+        &granting(&["read:/user/**", "write:/user/*/*"]),
+    )
+    .expect("each covered by one");
+    assert_eq!(
+        attenuates(&granting(&["read:/user/**"]), &granting(&["read:/user/*"])),
+        Err(Violation::OperationAdded {
+            operation: "read:/user/**".to_string(),
+        })
+    );
+    // Conservative: coverage by a union of patterns is not recognised.
+    assert!(attenuates(
+        &granting(&["read:/a/**"]),
+        &granting(&["read:/a", "read:/a/*/**"])
+    )
+    .is_err());
+}
+
+#[test]
+fn prover_and_verifier_accept_a_narrowed_successor() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(
+        set.identity("alice"),
+        granting(&["read:/user/**"]),
+        None,
+        now,
+    )
+    .expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(
+            &pca0,
+            granting(&["read:/user/docs/*"]),
+            request("read", "/user/docs/a.txt"),
+            now,
+        )
+        .expect("narrowed hop");
+    let tip = Verifier::new(&set.registry, None)
+        .verify_full_chain(&[pca0.clone(), pca1.clone()], now)
+        .expect("chain");
+    authorize(&tip, &request("read", "/user/docs/a.txt")).expect("in scope");
+    assert!(matches!(
+        authorize(&tip, &request("read", "/user/secrets/key")),
+        Err(Violation::NotAuthorized { .. })
+    ));
+
+    let err = Prover::new(
+        set.identity("storage-service"),
+        set.attestation("storage-service"),
+    )
+    .continue_(
+        &pca1,
+        granting(&["read:/user/**"]),
+        request("read", "/user/docs/a.txt"),
+        now,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::SelfCheck(Violation::OperationAdded { .. })
+    ));
+}
+
+#[test]
+fn authorization_reads_the_request_literally() {
+    let granted = granting(&["read:/user/*", "backup"]);
+    authorize(&granted, &request("read", "/user/a.txt")).expect("one segment");
+    authorize(&granted, &request("read", "/user/docs/a.txt")).expect("trailing prefix");
+    authorize(&granted, &request("backup", "")).expect("bare verb");
+    for (operation, target) in [
+        ("read", "/user"),
+        ("read", "/users/a.txt"),
+        ("backup", "/user/dataset"),
+        ("write", "/user/a.txt"),
+    ] {
+        assert!(
+            authorize(&granted, &request(operation, target)).is_err(),
+            "{operation}:{target}"
+        );
+    }
+    // A `*` in a request is a character, not a wildcard.
+    assert!(authorize(&granting(&["read:/user/a*"]), &request("read", "/user/a*")).is_ok());
+    assert!(authorize(
+        &granting(&["read:/user/a.txt"]),
+        &request("read", "/user/*")
+    )
+    .is_err());
+
+    // A bare verb ending in `*` is a prefix of the whole operation, as in Go.
+    authorize(&granting(&["*"]), &request("read", "/x")).expect("any operation");
+    authorize(&granting(&["read*"]), &request("read", "/user/a")).expect("verb prefix");
+    assert!(authorize(&granting(&["read*"]), &request("write", "/user/a")).is_err());
+}
@@ -5,13 +5,15 @@
 
 //! Ported adversarial tests for the `pic` library: non-expansion rejected, tamper
 //! detected, predecessor binding, single-use replay, snapshot matches full-chain,
-//! revocation LINEAGE-SUFFIX cutoff, plus the canonical-JSON interop contract.
+//! revocation LINEAGE-SUFFIX cutoff, the typed error model, plus the
+//! canonical-JSON interop contract.
 
 use chrono::{DateTime, Duration, Utc};
 use pic::{
     canonical_json, derive_lineage_id, issue_snapshot, mint_pca0, root_branch_id, sign_attestation,
-    wrap_envelope, Attestation, ContractAttributes, ExecutionContract, Identity, Invariants, Pca,
-    Prover, Registry, Request, RevocationStore, Verifier,
+    wrap_envelope, Attestation, Checkpoint, ContinuationError, ContractAttributes,
+    ExecutionContract, Identity, IntegrityError, Invariants, Pca, PicError, Prover,
+    ReferenceProfile, Registry, Request, RevocationStore, Verifier, Violation,
 };
 
 fn test_invariants() -> Invariants {
@@ -22,6 +24,7 @@ fn test_invariants() -> Invariants {
             execution_model: "deterministic".to_string(),
             ..Default::default()
         },
+        ..Default::default()
     }
 }
 
@@ -46,7 +49,8 @@ fn new_executor(
             ..Default::default()
         },
         org,
-    );
+    )
+    .expect("sign attestation");
     (ex, att)
 }
 
@@ -61,7 +65,7 @@ fn build_chain(hops: usize, now: DateTime<Utc>) -> (Registry, Vec<Pca>, Identity
     reg.add(&org);
     reg.add(&snap);
 
-    let pca0 = mint_pca0(&alice, test_invariants(), "", now);
+    let pca0 = mint_pca0(&alice, test_invariants(), None, now).expect("mint");
     let mut chain = vec![pca0];
     let req = Request {
         operation: "read".to_string(),
@@ -117,12 +121,13 @@ fn non_expansion_rejected() {
     let org = Identity::new("did:example:org");
     reg.add(&alice);
     reg.add(&org);
-    let pca0 = mint_pca0(&alice, test_invariants(), "", now);
+    let pca0 = mint_pca0(&alice, test_invariants(), None, now).expect("mint");
     let (bob, att) = new_executor(&mut reg, &org, "did:example:bob", now);
 
     let expanded = Invariants {
         operations: vec!["read:/user/*".to_string(), "read:/sys/*".to_string()],
         execution_contract: test_invariants().execution_contract,
+        ..Default::default()
     };
     let req = Request {
         operation: "read".to_string(),
@@ -191,8 +196,15 @@ fn snapshot_matches_full_chain() {
         .verify_full_chain(&chain, now)
         .expect("full chain");
     let through_index = chain.len() - 1 - 4;
-    let snap =
-        issue_snapshot(&snap_issuer, &reg, &chain, through_index, now).expect("IssueSnapshot");
+    let snap = issue_snapshot(
+        &snap_issuer,
+        &reg,
+        &chain,
+        through_index,
+        now,
+        &ReferenceProfile::DEFAULT,
+    )
+    .expect("IssueSnapshot");
     let snap_inv = Verifier::new(&reg, None)
         .verify_from_snapshot(&snap, &chain[through_index..], now)
         .expect("VerifyFromSnapshot");
@@ -212,7 +224,15 @@ fn snapshot_refuses_invalid_chain() {
         .operations
         .push("read:/sys/*".to_string()); // break it
     assert!(
-        issue_snapshot(&snap_issuer, &reg, &chain, 3, now).is_err(),
+        issue_snapshot(
+            &snap_issuer,
+            &reg,
+            &chain,
+            3,
+            now,
+            &ReferenceProfile::DEFAULT
+        )
+        .is_err(),
         "snapshot issued over an invalid chain"
     );
 }
@@ -252,7 +272,7 @@ fn challenge_single_use() {
     let (mut reg, chain, _) = build_chain(1, now);
     let forwarder = Identity::new("did:example:forwarder");
     reg.add(&forwarder);
-    let env = wrap_envelope(&forwarder, &chain[0], &chain[1]);
+    let env = wrap_envelope(&forwarder, &chain[0], &chain[1]).expect("wrap");
     let mut v = Verifier::new(&reg, None);
     assert!(
         v.verify_envelope(&env, now).is_ok(),
@@ -264,6 +284,114 @@ fn challenge_single_use() {
     );
 }
 
+#[test]
+fn replay_is_a_typed_continuation_error() {
+    let now = Utc::now();
+    let (mut reg, chain, _) = build_chain(1, now);
+    let forwarder = Identity::new("did:example:forwarder");
+    reg.add(&forwarder);
+    let env = wrap_envelope(&forwarder, &chain[0], &chain[1]).expect("wrap");
+    let mut v = Verifier::new(&reg, None);
+    v.verify_envelope(&env, now).expect("first acceptance");
+    let err = v.verify_envelope(&env, now).unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.3-CONTINUATION");
+    match &err {
+        PicError::Continuation {
+            error: ContinuationError::Replayed { challenge },
+            ..
+        } => assert_eq!(challenge, &chain[0].continuation.challenge),
+        other => panic!("expected a replay, got {other:?}"),
+    }
+    assert_eq!(
+        err.to_string(),
+        "hop continuation: single-use challenge already consumed (replay)"
+    );
+}
+
+#[test]
+fn full_chain_error_reports_hop_and_values() {
+    let now = Utc::now();
+    let (mut reg, mut chain, _) = build_chain(2, now);
+    let org = Identity::new("did:example:org-2");
+    reg.add(&org);
+    let (mallory, att) = new_executor(&mut reg, &org, "did:example:mallory", now);
+    let expanded = Invariants {
+        operations: vec!["read:/user/*".to_string(), "read:/sys/*".to_string()],
+        execution_contract: test_invariants().execution_contract,
+        ..Default::default()
+    };
+    let req = Request {
+        operation: "read".to_string(),
+        target: "/sys/secret".to_string(),
+        ..Default::default()
+    };
+    let mal = Prover::new(&mallory, att)
+        .continue_malicious(&chain[2], expanded, req, now)
+        .expect("malicious build");
+    chain.push(mal);
+
+    let err = Verifier::new(&reg, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.6-NON-EXPANSION");
+    assert_eq!(err.hop(), Some(3));
+    assert_eq!(
+        err,
+        PicError::NonExpansion {
+            hop: Some(3),
+            violation: Violation::OperationAdded {
+                operation: "read:/sys/*".to_string()
+            },
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "hop 3: hop non-expansion: non-expansion: operation \"read:/sys/*\" not present in predecessor"
+    );
+
+    // Tampering is an integrity failure at the tampered position.
+    chain.truncate(3);
+    chain[2]
+        .invariants
+        .operations
+        .push("read:/sys/*".to_string());
+    let err = Verifier::new(&reg, None)
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Integrity {
+            hop: Some(2),
+            error: IntegrityError::Signature(_)
+        }
+    ));
+}
+
+#[test]
+fn revocation_error_names_cutoff() {
+    let now = Utc::now();
+    let (reg, chain, _) = build_chain(4, now);
+    let mut store = RevocationStore::new();
+    store.lineage_suffix(&chain[0].lineage_id, 2, "did:example:alice");
+
+    let err = Verifier::new(&reg, Some(&store))
+        .verify_full_chain(&chain, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-REV-3.1-REVOKED");
+    assert_eq!(err.hop(), Some(2));
+    match err {
+        PicError::Revocation {
+            checkpoint: Checkpoint::Hop,
+            revoked,
+            ..
+        } => {
+            assert_eq!(revoked.counter, 2);
+            assert_eq!(revoked.revocation.from_counter, 2);
+        }
+        other => panic!("expected a hop revocation, got {other:?}"),
+    }
+}
+
 /// The interop contract: the canonical (signed) bytes of the archive-service
 /// attestation MUST equal the exact expected string, byte for byte.
 #[test]
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Request-binding tests: the Prover signs digests of the request it
+//! executes, the receiver recomputes them from what arrived, and any
+//! divergence (operation, target, a bound parameter, the body) or a missing
+//! digest is its own error kind.
+
+use chrono::Utc;
+use pic::{
+    fixtureset, mint_pca0, ExecutedRequest, Invariants, PicError, Prover, Request,
+    RequestBindingError, Verifier,
+};
+
+fn inv() -> Invariants {
+    Invariants {
+        operations: vec!["write:/user/*".to_string()],
+        ..Default::default()
+    }
+}
+
+fn executed() -> ExecutedRequest {
+    ExecutedRequest::new("write", "/user/report.csv")
+        .with_param("mode", "append")
+        .with_param("tenant", "42")
+        .with_body(b"id,total\n1,10\n".to_vec())
+}
+
+fn field(err: RequestBindingError) -> &'static str {
+    match err {
+        RequestBindingError::Mismatch { field, .. } | RequestBindingError::Unbound { field } => {
+            field
+        }
+    }
+}
+
+#[test]
+fn signed_binding_matches_the_received_request() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), executed().request("tenant-42"), now)
+        .expect("hop");
+    Verifier::new(&set.registry, None)
+        .verify_full_chain(&[pca0, pca1.clone()], now)
+        .expect("chain");
+
+    let signed = &pca1.proof_of_relationship.as_ref().unwrap().request;
+    assert!(signed.request_digest.starts_with("sha256:"));
+    assert_ne!(signed.request_digest, signed.payload_digest);
+
+    // Parameters bind by name, whatever order they arrive in.
+    let received = ExecutedRequest::new("write", "/user/report.csv")
+        .with_param("tenant", "42")
+        .with_param("mode", "append")
+        .with_body(b"id,total\n1,10\n".to_vec());
+    assert_eq!(received.check(signed), Ok(()));
+}
+
+#[test]
+fn every_divergence_is_a_request_binding_error() {
+    let signed = executed().request("tenant-42");
+    let cases = [
+        (
+            ExecutedRequest {
+                operation: "read".to_string(),
+                ..executed()
+            },
+            "operation",
+        ),
+        (
+            ExecutedRequest {
+                target: "/user/other.csv".to_string(),
+                ..executed()
+            },
+            "target",
+        ),
+        (executed().with_param("mode", "truncate"), "requestDigest"),
+        (executed().with_param("extra", "1"), "requestDigest"),
+        (
+            executed().with_body(b"id,total\n1,99\n".to_vec()),
+            "payloadDigest",
+        ),
+    ];
+    for (received, expected) in cases {
+        let err = received.check(&signed).unwrap_err();
+        assert!(matches!(err, RequestBindingError::Mismatch { .. }));
+        assert_eq!(field(err), expected);
+    }
+
+    let err =
+        PicError::RequestBinding(executed().with_body(Vec::new()).check(&signed).unwrap_err());
+    assert_eq!(err.code(), "PIC-PV-3.3.8-REQUEST-BINDING");
+}
+
+#[test]
+fn unbound_requests_are_refused() {
+    let legacy = Request {
+        operation: "write".to_string(),
+        target: "/user/report.csv".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        executed().check(&legacy),
+        Err(RequestBindingError::Unbound {
+            field: "requestDigest"
+        })
+    );
+    let mut no_payload = executed().request("tenant-42");
+    no_payload.payload_digest.clear();
+    assert_eq!(
+        executed().check(&no_payload),
+        Err(RequestBindingError::Unbound {
+            field: "payloadDigest"
+        })
+    );
+}
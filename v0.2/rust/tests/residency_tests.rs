@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Data-residency tests: `allowedRegions` / `allowedEnvironments` on the
+//! execution contract are checked against the attested region and
+//! environment, and may only narrow along a lineage. The fixture cast places
+//! `storage-us` in `us-1` and `storage-staging` in `staging`.
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use common::request;
+use pic::{
+    attenuates, conforms, fixtureset, mint_pca0, ExecutionContract, Invariants, Pca, PicError,
+    Prover, Verifier, Violation,
+};
+
+fn eu_production() -> ExecutionContract {
+    ExecutionContract {
+        allowed_regions: vec!["eu-1".to_string()],
+        allowed_environments: vec!["production".to_string()],
+        ..Default::default()
+    }
+}
+
+fn inv(contract: ExecutionContract) -> Invariants {
+    Invariants {
+        operations: vec!["write:/user/*".to_string()],
+        execution_contract: contract,
+        ..Default::default()
+    }
+}
+
+fn eu_lineage(set: &fixtureset::Set, now: DateTime<Utc>) -> Pca {
+    mint_pca0(set.identity("alice"), inv(eu_production()), None, now).expect("mint")
+}
+
+#[test]
+fn eu_lineage_is_not_continued_in_us() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = eu_lineage(set, now);
+
+    let pca1 = Prover::new(
+        set.identity("storage-service"),
+        set.attestation("storage-service"),
+    )
+    .continue_(
+        &pca0,
+        inv(eu_production()),
+        request("write", "/user/backup.tar"),
+        now,
+    )
+    .expect("eu-1 hop");
+    Verifier::new(&set.registry, None)
+        .verify_full_chain(&[pca0.clone(), pca1], now)
+        .expect("eu-1 chain");
+
+    let us = Prover::new(set.identity("storage-us"), set.attestation("storage-us"));
+    let err = us
+        .continue_(
+            &pca0,
+            inv(eu_production()),
+            request("write", "/user/backup.tar"),
+            now,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::SelfCheck(Violation::NotAllowed {
+            field: "region",
+            ..
+        })
+    ));
+
+    // An executor that skips its own check is refused by the Verifier.
+    let pca1 = us
+        .continue_malicious(
+            &pca0,
+            inv(eu_production()),
+            request("write", "/user/backup.tar"),
+            now,
+        )
+        .expect("unchecked hop");
+    let err = Verifier::new(&set.registry, None)
+        .verify_full_chain(&[pca0, pca1], now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.5-CONFORMANCE");
+    assert_eq!(err.hop(), Some(1));
+    let PicError::Conformance { violation, .. } = err else {
+        panic!("expected a conformance error, got {err:?}");
+    };
+    assert_eq!(
+        violation,
+        Violation::NotAllowed {
+            field: "region",
+            attested: "us-1".to_string(),
+            allowed: vec!["eu-1".to_string()],
+        }
+    );
+}
+
+#[test]
+fn environments_are_checked_like_regions() {
+    let set = fixtureset::load().expect("fixtures");
+    let staging = set.attestation("storage-staging");
+    assert_eq!(
+        conforms(&staging.attributes, &eu_production()),
+        Err(Violation::NotAllowed {
+            field: "environment",
+            attested: "staging".to_string(),
+            allowed: vec!["production".to_string()],
+        })
+    );
+    let any_environment = ExecutionContract {
+        allowed_regions: vec!["eu-1".to_string()],
+        ..Default::default()
+    };
+    conforms(&staging.attributes, &any_environment).expect("environment unconstrained");
+    conforms(
+        &set.attestation("storage-us").attributes,
+        &ExecutionContract::default(),
+    )
+    .expect("no residency contract");
+}
+
+#[test]
+fn allow_lists_only_narrow() {
+    let pred = inv(ExecutionContract {
+        allowed_regions: vec!["eu-1".to_string(), "eu-2".to_string()],
+        ..Default::default()
+    });
+    let narrowed = inv(ExecutionContract {
+        allowed_regions: vec!["eu-2".to_string()],
+        allowed_environments: vec!["production".to_string()],
+        ..Default::default()
+    });
+    attenuates(&narrowed, &pred).expect("narrowed, plus a new list");
+
+    let widened = inv(ExecutionContract {
+        allowed_regions: vec!["eu-1".to_string(), "us-1".to_string()],
+        ..Default::default()
+    });
+    assert!(matches!(
+        attenuates(&widened, &pred),
+        Err(Violation::AllowedRelaxed {
+            field: "allowedRegions",
+            ..
+        })
+    ));
+    // Dropping the list lifts the restriction altogether.
+    assert!(matches!(
+        attenuates(&inv(ExecutionContract::default()), &pred),
+        Err(Violation::AllowedRelaxed { .. })
+    ));
+
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = eu_lineage(set, now);
+    let err = Prover::new(
+        set.identity("storage-service"),
+        set.attestation("storage-service"),
+    )
+    .continue_(
+        &pca0,
+        inv(ExecutionContract::default()),
+        request("write", "/user/backup.tar"),
+        now,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::SelfCheck(Violation::AllowedRelaxed { .. })
+    ));
+}
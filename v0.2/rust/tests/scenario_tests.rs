@@ -110,11 +110,65 @@ fn accept_rejects_unauthorized_origin() {
     // A receiving hop that does not accept the enforcement origin must reject
     // even a fully valid outer chain (a valid signature is not authorization).
     let err = pic::accept_guarded_crossing(
-        &w.set.registry,
-        None,
+        &mut pic::Verifier::new(&w.set.registry, None),
         &["did:web:someone-else.example".to_string()],
         &res.permit.outer_chain,
         now,
     );
     assert!(err.is_err(), "accepted an unauthorized sandbox origin");
 }
+
+#[test]
+fn accept_checks_under_the_receivers_verifier() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let res = w.guarded(now).expect("guarded");
+    let outer = &res.permit.outer_chain;
+    let origins = w.accepted_origins();
+
+    // Past the outer tip's expiry, only a Verifier tolerating the skew accepts.
+    let tip_expires = pic::parse_rfc3339(&outer.last().unwrap().expires_at).expect("time");
+    let late = tip_expires + chrono::Duration::seconds(30);
+    let mut strict = Verifier::new(&w.set.registry, None);
+    assert!(pic::accept_guarded_crossing(&mut strict, &origins, outer, late).is_err());
+    let mut skewed = Verifier::new(&w.set.registry, None)
+        .with_temporal(pic::TemporalPolicy::default().with_skew(chrono::Duration::minutes(1)));
+    pic::accept_guarded_crossing(&mut skewed, &origins, outer, late).expect("within skew");
+
+    // A receiver under another authority profile refuses reference lineages.
+    let mut foreign = Verifier::new(&w.set.registry, None).with_authority(&Other);
+    let err = pic::accept_guarded_crossing(&mut foreign, &origins, outer, now).unwrap_err();
+    assert!(matches!(
+        err,
+        pic::PicError::GuardedAcceptance(pic::AcceptanceError::OuterInvalid(_))
+    ));
+}
+
+/// The reference checks under another profile identifier.
+struct Other;
+
+impl pic::AuthorityProfile for Other {
+    fn id(&self) -> &str {
+        "urn:example:authority:other"
+    }
+
+    fn attenuates(
+        &self,
+        current: &pic::Invariants,
+        predecessor: &pic::Invariants,
+    ) -> Result<(), pic::Violation> {
+        pic::attenuates(current, predecessor)
+    }
+
+    fn conforms(
+        &self,
+        att: &pic::ContractAttributes,
+        contract: &pic::ExecutionContract,
+    ) -> Result<(), pic::Violation> {
+        pic::conforms(att, contract)
+    }
+
+    fn authorize(&self, inv: &pic::Invariants, req: &pic::Request) -> Result<(), pic::Violation> {
+        pic::authorize(inv, req)
+    }
+}
@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Signer tests: minting, proving, wrapping and snapshot issuance through a
+//! `dyn Signer`, synchronously and asynchronously, in memory and end to end
+//! against the `mocksigner` signing service over a Unix socket. Every sync
+//! entry point has an async one that produces the same PCA.
+
+mod common;
+
+use chrono::Utc;
+use common::{inv, req_in};
+use futures_executor::block_on;
+use pic::codec::{encode_cose, encode_cose_async};
+use pic::scenario::World;
+use pic::{
+    issue_snapshot, issue_snapshot_async, mint_pca0, mint_pca0_async, mint_pca0_with,
+    wrap_envelope, wrap_envelope_async, Audience, ContinuationMode, OriginOptions, Pca, Prover,
+    ReferenceProfile, SeededEntropy, Signer, TemporalProfile, Verifier,
+};
+
+/// Asserts two PCAs encode identically.
+fn same(a: &Pca, b: &Pca) {
+    assert_eq!(
+        serde_json::to_value(a).expect("encode"),
+        serde_json::to_value(b).expect("encode")
+    );
+}
+
+/// Mints, proves two hops (one of them asynchronously), wraps the tip, seals
+/// it as COSE and snapshots the chain with the given signers, then verifies
+/// every artifact.
+fn sign_everything(
+    w: &World,
+    alice: &dyn Signer,
+    gateway: &dyn Signer,
+    storage: &dyn Signer,
+    snapshots: &dyn Signer,
+) {
+    let now = Utc::now();
+    let reg = &w.set.registry;
+    let pca0 = block_on(mint_pca0_async(
+        alice,
+        inv(),
+        None,
+        now,
+        &OriginOptions::default(),
+    ))
+    .expect("mint async");
+    let pca1 = Prover::new(gateway, w.set.attestation("gateway"))
+        .continue_(&pca0, inv(), req_in("tenant-42"), now)
+        .expect("hop 1");
+    let pca2 = block_on(
+        Prover::new(storage, w.set.attestation("storage-service")).continue_async(
+            &pca1,
+            inv(),
+            req_in("tenant-42"),
+            now,
+        ),
+    )
+    .expect("hop 2 async");
+    let chain = vec![pca0, pca1, pca2];
+    Verifier::new(reg, None)
+        .verify_full_chain(&chain, now)
+        .expect("chain");
+
+    let env = wrap_envelope(gateway, &chain[0], &chain[1]).expect("wrap");
+    Verifier::new(reg, None)
+        .verify_envelope(&env, now)
+        .expect("envelope");
+    let env = block_on(wrap_envelope_async(storage, &chain[1], &chain[2], &[])).expect("wrap");
+    Verifier::new(reg, None)
+        .verify_envelope(&env, now)
+        .expect("async envelope");
+    for wire in [
+        encode_cose(&env, storage).expect("seal"),
+        block_on(encode_cose_async(&env, storage)).expect("async seal"),
+    ] {
+        Verifier::new(reg, None)
+            .verify_envelope_bytes(&wire, now)
+            .expect("sealed envelope");
+    }
+
+    for snap in [
+        issue_snapshot(snapshots, reg, &chain, 1, now, &ReferenceProfile::DEFAULT)
+            .expect("snapshot"),
+        block_on(issue_snapshot_async(
+            snapshots,
+            reg,
+            &chain,
+            1,
+            now,
+            &ReferenceProfile::DEFAULT,
+        ))
+        .expect("async snapshot"),
+    ] {
+        Verifier::new(reg, None)
+            .verify_from_snapshot(&snap, &chain[1..], now)
+            .expect("from snapshot");
+    }
+
+    // A sync origin verifies the same way.
+    let pca0 = mint_pca0(alice, inv(), None, now).expect("mint");
+    Verifier::new(reg, None)
+        .verify_origin(&pca0, now)
+        .expect("origin");
+}
+
+#[test]
+fn in_memory_signer_signs_sync_and_async() {
+    let w = World::new().expect("world");
+    sign_everything(
+        &w,
+        w.set.identity("alice"),
+        w.set.identity("gateway"),
+        w.set.identity("storage-service"),
+        w.set.identity("snapshot-issuer"),
+    );
+}
+
+#[test]
+fn sync_and_async_entry_points_produce_the_same_pcas() {
+    let w = World::new().expect("world");
+    let now = Utc::now();
+    let alice = w.set.identity("alice");
+    let gateway = w.set.identity("gateway");
+
+    let opts = |entropy| OriginOptions {
+        entropy,
+        mode: ContinuationMode::MultiUse(3),
+        temporal_profile: Some(TemporalProfile {
+            max_hop_window_seconds: 60,
+            ..Default::default()
+        }),
+        audience: Some(Audience::executor(&gateway.id)),
+        ..Default::default()
+    };
+    let (e1, e2) = (SeededEntropy::new(b"parity"), SeededEntropy::new(b"parity"));
+    let pca0 = mint_pca0_with(alice, inv(), None, now, &opts(&e1)).expect("mint");
+    let async_pca0 =
+        block_on(mint_pca0_async(alice, inv(), None, now, &opts(&e2))).expect("mint async");
+    same(&pca0, &async_pca0);
+    assert!(pca0.temporal_profile.is_some() && pca0.continuation.audience.is_some());
+
+    let prover = |entropy| Prover::new(gateway, w.set.attestation("gateway")).with_entropy(entropy);
+    let (e1, e2) = (SeededEntropy::new(b"hop"), SeededEntropy::new(b"hop"));
+    let pca1 = prover(&e1)
+        .continue_(&pca0, inv(), req_in("tenant-42"), now)
+        .expect("hop");
+    let async_pca1 =
+        block_on(prover(&e2).continue_async(&pca0, inv(), req_in("tenant-42"), now)).expect("hop");
+    same(&pca1, &async_pca1);
+    let (e1, e2) = (SeededEntropy::new(b"branch"), SeededEntropy::new(b"branch"));
+    let branch = prover(&e1)
+        .branch(&pca0, inv(), req_in("tenant-42"), now)
+        .expect("branch");
+    let async_branch =
+        block_on(prover(&e2).branch_async(&pca0, inv(), req_in("tenant-42"), now)).expect("branch");
+    same(&branch, &async_branch);
+    assert_ne!(branch.branch_id, pca0.branch_id);
+    Verifier::new(&w.set.registry, None)
+        .verify_full_chain(&[pca0, branch], now)
+        .expect("branched chain");
+
+    // The guardrail's outer PCA, re-proved sync and async from the same origin.
+    let permit = w.guarded(now).expect("guarded").permit;
+    let outer = &permit.outer_chain[1];
+    let ml = outer.multi_lineage.clone().expect("multiLineage");
+    let req = outer
+        .proof_of_relationship
+        .as_ref()
+        .expect("PoR")
+        .request
+        .clone();
+    let guardrail = |entropy| {
+        Prover::new(w.set.identity("guardrail"), w.set.attestation("guardrail"))
+            .with_entropy(entropy)
+    };
+    let (e1, e2) = (
+        SeededEntropy::new(b"enforce"),
+        SeededEntropy::new(b"enforce"),
+    );
+    let enforced = guardrail(&e1)
+        .continue_enforce(
+            &permit.outer_chain[0],
+            outer.invariants.clone(),
+            req.clone(),
+            ml.clone(),
+            now,
+        )
+        .expect("enforce");
+    let async_enforced = block_on(guardrail(&e2).continue_enforce_async(
+        &permit.outer_chain[0],
+        outer.invariants.clone(),
+        req,
+        ml,
+        now,
+    ))
+    .expect("enforce async");
+    same(&enforced, &async_enforced);
+}
+
+#[cfg(unix)]
+mod remote {
+    use super::*;
+    use pic::{PicError, RemoteSigner, SignerError};
+    use std::io::{BufRead, BufReader};
+    use std::path::PathBuf;
+    use std::process::{Child, Command, Stdio};
+
+    /// A running `mocksigner` process, killed on drop.
+    struct MockService {
+        child: Child,
+        socket: PathBuf,
+    }
+
+    impl MockService {
+        fn start(name: &str) -> MockService {
+            let socket = std::env::temp_dir()
+                .join(format!("pic-mocksigner-{}-{name}.sock", std::process::id()));
+            let mut child = Command::new(env!("CARGO_BIN_EXE_mocksigner"))
+                .arg(&socket)
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("spawn mocksigner");
+            let mut line = String::new();
+            BufReader::new(child.stdout.take().unwrap())
+                .read_line(&mut line)
+                .expect("mocksigner output");
+            assert_eq!(line.trim(), "ready");
+            MockService { child, socket }
+        }
+
+        fn signer(&self, w: &World, name: &str) -> RemoteSigner {
+            RemoteSigner::connect(&self.socket, &w.set.identity(name).id).expect("connect")
+        }
+    }
+
+    impl Drop for MockService {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            let _ = std::fs::remove_file(&self.socket);
+        }
+    }
+
+    #[test]
+    fn remote_signer_drives_a_chain_end_to_end() {
+        let w = World::new().expect("world");
+        let service = MockService::start("chain");
+        let alice = service.signer(&w, "alice");
+        assert_eq!(alice.id(), w.set.identity("alice").id);
+        assert_eq!(alice.public_key(), w.set.identity("alice").public_key());
+        sign_everything(
+            &w,
+            &alice,
+            &service.signer(&w, "gateway"),
+            &service.signer(&w, "storage-service"),
+            &service.signer(&w, "snapshot-issuer"),
+        );
+
+        // Concurrent asynchronous requests are answered independently.
+        let msgs: Vec<Vec<u8>> = (0..8)
+            .map(|i| format!("message {i}").into_bytes())
+            .collect();
+        let pending: Vec<_> = msgs.iter().map(|m| alice.sign_async(m)).collect();
+        for (msg, fut) in msgs.iter().zip(pending) {
+            let raw = block_on(fut).expect("async signature");
+            w.set
+                .registry
+                .verify_raw(alice.verification_method(), msg, &raw)
+                .expect("verifies");
+        }
+    }
+
+    #[test]
+    fn remote_failures_are_signer_errors() {
+        let w = World::new().expect("world");
+        let service = MockService::start("failures");
+        let err = RemoteSigner::connect(&service.socket, "did:web:nobody.example").unwrap_err();
+        assert!(matches!(err, PicError::Signer(SignerError::Refused { .. })));
+
+        let alice = service.signer(&w, "alice");
+        drop(service);
+        let err = mint_pca0(&alice, inv(), None, Utc::now()).unwrap_err();
+        assert_eq!(err.code(), "PIC-SIGNER");
+        assert!(matches!(
+            err,
+            PicError::Signer(SignerError::Unavailable { .. })
+        ));
+        let err = block_on(mint_pca0_async(
+            &alice,
+            inv(),
+            None,
+            Utc::now(),
+            &OriginOptions::default(),
+        ))
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            PicError::Signer(SignerError::Unavailable { .. })
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Signature-suite tests: chains mixing Ed25519 and ECDSA P-256 (and Ed448
+//! with the `ed448` feature) verify in either wire encoding, the proof type
+//! must match the suite of the resolved key, and unsupported suites and
+//! malleated ECDSA signatures fail closed.
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use common::{inv, req_in, resign_as};
+use pic::codec::{decode_cose, encode_cose};
+use pic::{
+    mint_pca0, sign_attestation, wrap_envelope, Attestation, CodecError, ContractAttributes,
+    Identity, IntegrityError, Pca, PicError, Prover, Registry, SignatureError, SignatureSuite,
+    Verifier,
+};
+
+fn attestation(subject: &Identity, org: &Identity, now: DateTime<Utc>) -> Attestation {
+    sign_attestation(
+        Attestation {
+            subject: subject.id.clone(),
+            attributes: ContractAttributes::default(),
+            issued_at: pic::rfc3339(now - Duration::hours(1)),
+            expires_at: pic::rfc3339(now + Duration::hours(24)),
+            ..Default::default()
+        },
+        org,
+    )
+    .expect("sign attestation")
+}
+
+/// An Ed25519 origin continued by one executor per suite in `hops`, the
+/// attestations issued by a P-256 organization.
+fn mixed_chain(hops: &[SignatureSuite], now: DateTime<Utc>) -> (Registry, Vec<Pca>, Vec<Identity>) {
+    let mut reg = Registry::new();
+    let alice = Identity::new("did:example:alice");
+    let org = Identity::new_with("did:example:org", SignatureSuite::EcdsaP256);
+    reg.add(&alice);
+    reg.add(&org);
+    let mut chain = vec![mint_pca0(&alice, inv(), None, now).expect("mint")];
+    let mut executors = Vec::new();
+    for (i, suite) in hops.iter().enumerate() {
+        let ex = Identity::new_with(&format!("did:example:hop-{i}"), *suite);
+        reg.add(&ex);
+        let p = Prover::new(&ex, attestation(&ex, &org, now))
+            .continue_(chain.last().unwrap(), inv(), req_in("tenant-1"), now)
+            .expect("continue");
+        chain.push(p);
+        executors.push(ex);
+    }
+    (reg, chain, executors)
+}
+
+/// Replaces the proof of `p` with a signature by `signer` under `proof_type`.
+#[test]
+fn mixed_suite_chain_verifies() {
+    let now = Utc::now();
+    let suites = [
+        SignatureSuite::EcdsaP256,
+        SignatureSuite::Ed25519,
+        SignatureSuite::EcdsaP256,
+    ];
+    let (reg, chain, executors) = mixed_chain(&suites, now);
+    assert_eq!(
+        chain[1].proof.as_ref().unwrap().type_,
+        "EcdsaSecp256r1Signature2019"
+    );
+    assert_eq!(chain[2].proof.as_ref().unwrap().type_, pic::SIGNATURE_TYPE);
+    Verifier::new(&reg, None)
+        .verify_full_chain(&chain, now)
+        .expect("mixed chain");
+
+    // A P-256 forwarder over an Ed25519 origin, as JSON and as COSE_Sign1.
+    let gateway = &executors[0];
+    let env = wrap_envelope(gateway, &chain[0], &chain[1]).expect("wrap");
+    Verifier::new(&reg, None)
+        .verify_envelope(&env, now)
+        .expect("json envelope");
+    let wire = encode_cose(&env, gateway).expect("seal");
+    Verifier::new(&reg, None)
+        .verify_envelope_bytes(&wire, now)
+        .expect("cose envelope");
+}
+
+#[test]
+fn suites_round_trip() {
+    let msg = b"PIC signature suite round trip";
+    for suite in SignatureSuite::ALL {
+        let id = Identity::new_with("did:example:k", *suite);
+        let mut reg = Registry::new();
+        reg.add(&id);
+        let proof = pic::Proof {
+            type_: id.proof_type().to_string(),
+            verification_method: id.verification_method.clone(),
+            signature: id.sign(msg),
+        };
+        reg.verify(&id.id, msg, &proof).expect("verify");
+        assert!(matches!(
+            reg.verify(&id.id, b"other", &proof),
+            Err(SignatureError::Invalid { .. })
+        ));
+
+        // Loading the exported secret reproduces the key.
+        let jwk = pic::keystore::identity_to_jwk(&id);
+        let again = pic::keystore::identity_from_jwk(jwk.as_bytes()).expect("load");
+        assert_eq!(again.suite(), *suite);
+        assert_eq!(again.public_bytes(), id.public_bytes());
+        assert_eq!(
+            pic::PublicKey::from_bytes(*suite, &id.public_bytes()),
+            Some(id.public_key())
+        );
+    }
+}
+
+#[test]
+fn proof_type_must_match_key_suite() {
+    let now = Utc::now();
+    let (reg, mut chain, executors) = mixed_chain(&[SignatureSuite::EcdsaP256], now);
+
+    // Unknown, and not compiled in, suite names fail closed.
+    for unsupported in ["RsaSignature2018", "Ed448Signature2022", ""] {
+        if SignatureSuite::from_proof_type(unsupported).is_ok() {
+            continue;
+        }
+        let mut p = chain[1].clone();
+        p.proof.as_mut().unwrap().type_ = unsupported.to_string();
+        let err = Verifier::new(&reg, None)
+            .verify_hop(&p, &chain[0], now, false)
+            .unwrap_err();
+        assert_eq!(err.code(), "PIC-PV-3.3.1-INTEGRITY");
+        assert!(matches!(
+            err,
+            PicError::Integrity {
+                error: IntegrityError::Signature(SignatureError::UnsupportedSuite { .. }),
+                ..
+            }
+        ));
+    }
+
+    // A P-256 signature labelled as Ed25519 is not tried under another suite.
+    resign_as(&mut chain[1], &executors[0], pic::SIGNATURE_TYPE);
+    let err = Verifier::new(&reg, None)
+        .verify_hop(&chain[1], &chain[0], now, false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Integrity {
+            error: IntegrityError::Signature(SignatureError::SuiteMismatch { .. }),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn high_s_ecdsa_signature_is_rejected() {
+    let now = Utc::now();
+    let (reg, mut chain, _) = mixed_chain(&[SignatureSuite::EcdsaP256], now);
+    let proof = chain[1].proof.as_mut().unwrap();
+    let raw = pic::crypto::b64_decode(&proof.signature).unwrap();
+    let sig = p256::ecdsa::Signature::from_slice(&raw).unwrap();
+    let (r, s) = sig.split_scalars();
+    let flipped = p256::ecdsa::Signature::from_scalars(r, -s).unwrap();
+    proof.signature = pic::crypto::b64_encode(&flipped.to_bytes());
+    let err = Verifier::new(&reg, None)
+        .verify_hop(&chain[1], &chain[0], now, false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Integrity {
+            error: IntegrityError::Signature(SignatureError::Malformed { .. }),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn cose_algorithm_must_match_key_suite() {
+    let now = Utc::now();
+    let (mut reg, chain, executors) = mixed_chain(&[SignatureSuite::EcdsaP256], now);
+    // An Ed25519 key registered under the P-256 sealer's kid: the ES256 seal
+    // is refused before any signature check.
+    let impostor = Identity::new("did:example:hop-0");
+    let wire = encode_cose(&chain[1], &executors[0]).expect("seal");
+    reg.add(&impostor);
+    let err = decode_cose::<Pca>(&reg, &wire, now).unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Codec(CodecError::UnsupportedAlgorithm { .. })
+    ));
+}
+
+#[cfg(feature = "ed448")]
+#[test]
+fn ed448_executor_continues_the_chain() {
+    let now = Utc::now();
+    let (reg, chain, executors) =
+        mixed_chain(&[SignatureSuite::Ed448, SignatureSuite::EcdsaP256], now);
+    assert_eq!(chain[1].proof.as_ref().unwrap().type_, "Ed448Signature2022");
+    Verifier::new(&reg, None)
+        .verify_full_chain(&chain, now)
+        .expect("ed448 chain");
+    let wire = encode_cose(&chain[1], &executors[0]).expect("seal");
+    assert_eq!(
+        decode_cose::<Pca>(&reg, &wire, now).expect("cose").digest(),
+        chain[1].digest()
+    );
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Temporal profile tests: PCA0 signs the hop window, challenge lifetime and
+//! lineage lifetime of its lineage, the Prover mints successors within them,
+//! and the Verifier lets a successor tighten the profile but never relax it.
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use common::{inv, req, resign};
+use pic::{
+    fixtureset, issue_snapshot, mint_pca0, mint_pca0_with, parse_rfc3339, ContinuationError,
+    OriginOptions, Pca, PicError, Prover, ReferenceProfile, TemporalBound, TemporalError,
+    TemporalProfile, Verifier,
+};
+
+fn window(from: &str, to: &str) -> Duration {
+    parse_rfc3339(to).unwrap() - parse_rfc3339(from).unwrap()
+}
+
+fn origin(set: &fixtureset::Set, profile: TemporalProfile, now: DateTime<Utc>) -> Pca {
+    mint_pca0_with(
+        set.identity("alice"),
+        inv(),
+        None,
+        now,
+        &OriginOptions {
+            temporal_profile: Some(profile),
+            ..Default::default()
+        },
+    )
+    .expect("mint")
+}
+
+#[test]
+fn batch_profile_allows_multi_hour_hops() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let batch = TemporalProfile {
+        max_hop_window_seconds: 4 * 60 * 60,
+        max_challenge_lifetime_seconds: 4 * 60 * 60,
+        lineage_lifetime_seconds: 12 * 60 * 60,
+    };
+    let pca0 = origin(set, batch, now);
+    assert_eq!(
+        window(&pca0.issued_at, &pca0.expires_at),
+        Duration::hours(12)
+    );
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    assert_eq!(pca1.temporal_profile, Some(batch));
+    assert_eq!(
+        window(&pca1.issued_at, &pca1.expires_at),
+        Duration::hours(4)
+    );
+    assert_eq!(
+        window(&pca1.issued_at, &pca1.continuation.expires_at),
+        Duration::hours(4)
+    );
+
+    let later = now + Duration::hours(3);
+    Verifier::new(&set.registry, None)
+        .verify_full_chain(&[pca0.clone(), pca1.clone()], later)
+        .expect("a three-hour-old hop under the batch profile");
+    let snapshot = issue_snapshot(
+        set.identity("alice"),
+        &set.registry,
+        &[pca0.clone(), pca1],
+        1,
+        now,
+        &ReferenceProfile::DEFAULT,
+    )
+    .expect("snapshot");
+    assert_eq!(snapshot.expires_at, pca0.expires_at);
+}
+
+#[test]
+fn interactive_profile_closes_challenges_after_thirty_seconds() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let interactive = TemporalProfile {
+        max_hop_window_seconds: 60,
+        max_challenge_lifetime_seconds: 30,
+        lineage_lifetime_seconds: 10 * 60,
+    };
+    let pca0 = origin(set, interactive, now);
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now + Duration::seconds(31))
+        .expect("hop");
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&pca1, &pca0, now + Duration::seconds(31), false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Continuation {
+            error: ContinuationError::Expired { by, .. },
+            ..
+        } if by == Duration::seconds(1)
+    ));
+}
+
+#[test]
+fn successors_may_tighten_but_never_relax_the_profile() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let gateway = set.identity("gateway");
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+
+    // A Prover tightens bound by bound; a looser bound leaves the lineage's.
+    let tighter = TemporalProfile {
+        max_hop_window_seconds: 60,
+        max_challenge_lifetime_seconds: 3600,
+        ..Default::default()
+    };
+    let pca1 = Prover::new(gateway, set.attestation("gateway"))
+        .with_temporal_profile(tighter)
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    let expected = TemporalProfile {
+        max_hop_window_seconds: 60,
+        ..Default::default()
+    };
+    assert_eq!(pca1.temporal_profile, Some(expected));
+    assert_eq!(
+        window(&pca1.issued_at, &pca1.expires_at),
+        Duration::minutes(1)
+    );
+    Verifier::new(&set.registry, None)
+        .verify_hop(&pca1, &pca0, now, false)
+        .expect("tightened hop");
+
+    // A successor that signs a looser profile than its predecessor's.
+    let mut relaxed = Prover::new(gateway, set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    relaxed.temporal_profile = Some(TemporalProfile {
+        lineage_lifetime_seconds: 48 * 60 * 60,
+        ..Default::default()
+    });
+    resign(&mut relaxed, gateway);
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&relaxed, &pca0, now, false)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.7-TEMPORAL");
+    let PicError::Temporal { error, .. } = err else {
+        panic!("expected a temporal error, got {err:?}");
+    };
+    assert_eq!(
+        error,
+        TemporalError::ProfileRelaxed {
+            bound: TemporalBound::LineageLifetime,
+            max: Duration::hours(24),
+            by: Duration::hours(24),
+        }
+    );
+
+    // Dropping a tightened profile falls back to the looser default.
+    let pca2 = Prover::new(
+        set.identity("storage-service"),
+        set.attestation("storage-service"),
+    )
+    .continue_(&pca1, inv(), req(), now)
+    .expect("hop");
+    let mut dropped = pca2.clone();
+    dropped.temporal_profile = None;
+    resign(&mut dropped, set.identity("storage-service"));
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&dropped, &pca1, now, false)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Temporal {
+            error: TemporalError::ProfileRelaxed {
+                bound: TemporalBound::HopWindow,
+                ..
+            },
+            ..
+        }
+    ));
+}
@@ -0,0 +1,288 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Temporal policy tests: clock skew is tolerated up to the configured bound,
+//! hop windows, challenge lifetimes and lineage lifetimes are clamped by the
+//! minter or Prover and bounded by the Verifier, live acceptance reads the
+//! Verifier's clock, and every failure names the bound it crossed and by how
+//! much.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{inv, req};
+use pic::{
+    fixtureset, mint_pca0, mint_pca0_with, wrap_envelope, ContinuationError, FixedClock,
+    OriginError, OriginOptions, Pca, PicError, Prover, TemporalBound, TemporalError,
+    TemporalPolicy, TemporalProfile, ValidityError, Verifier,
+};
+
+fn temporal(err: PicError) -> TemporalError {
+    match err {
+        PicError::Temporal { error, .. } => error,
+        other => panic!("expected a temporal error, got {other:?}"),
+    }
+}
+
+#[test]
+fn successor_from_a_clock_ahead_is_accepted_within_the_skew() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let ahead = now + Duration::milliseconds(300);
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), ahead)
+        .expect("hop");
+
+    let err = Verifier::new(&set.registry, None)
+        .verify_hop(&pca1, &pca0, now, false)
+        .unwrap_err();
+    let err = temporal(err);
+    assert_eq!(err.bound(), Some(TemporalBound::ForwardSkew));
+    assert!(matches!(
+        err,
+        TemporalError::Validity(ValidityError::NotYetValid { by, .. })
+            if by == Duration::milliseconds(300)
+    ));
+    assert_eq!(
+        err.to_string(),
+        "not yet valid: issued 300ms beyond the 0ms forward skew"
+    );
+
+    let skewed = TemporalPolicy::default().with_skew(Duration::milliseconds(500));
+    Verifier::new(&set.registry, None)
+        .with_temporal(skewed)
+        .verify_hop(&pca1, &pca0, now, false)
+        .expect("within the forward skew");
+}
+
+#[test]
+fn expiry_is_extended_by_the_backward_skew_only() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now() - Duration::hours(1);
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    let late = now + Duration::minutes(5) + Duration::seconds(2);
+
+    let policy = TemporalPolicy {
+        backward_skew: Duration::seconds(1),
+        ..Default::default()
+    };
+    let err = Verifier::new(&set.registry, None)
+        .with_temporal(policy)
+        .verify_hop(&pca1, &pca0, late, false)
+        .unwrap_err();
+    // The predecessor's challenge expires with the hop, and is checked first.
+    match err {
+        PicError::Continuation {
+            error: ContinuationError::Expired { skew, by, .. },
+            ..
+        } => {
+            assert_eq!(skew, Duration::seconds(1));
+            assert_eq!(by, Duration::seconds(1));
+        }
+        other => panic!("expected an expired challenge, got {other:?}"),
+    }
+
+    Verifier::new(&set.registry, None)
+        .with_temporal(policy.with_skew(Duration::seconds(3)))
+        .verify_hop(&pca1, &pca0, late, false)
+        .expect("within the backward skew");
+}
+
+#[test]
+fn hop_window_is_clamped_by_the_prover_and_bounded_by_the_verifier() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let prover = |policy| {
+        Prover::new(set.identity("gateway"), set.attestation("gateway")).with_temporal(policy)
+    };
+    let tight = TemporalPolicy {
+        max_hop_window: Duration::minutes(1),
+        ..Default::default()
+    };
+    let clamped = prover(tight)
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    let window = |p: &Pca| {
+        pic::parse_rfc3339(&p.expires_at).unwrap() - pic::parse_rfc3339(&p.issued_at).unwrap()
+    };
+    assert_eq!(window(&clamped), Duration::minutes(1));
+
+    let wide = prover(TemporalPolicy::default())
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    let err = Verifier::new(&set.registry, None)
+        .with_temporal(tight)
+        .verify_hop(&wide, &pca0, now, false)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.7-TEMPORAL");
+    assert_eq!(
+        temporal(err),
+        TemporalError::Validity(ValidityError::WindowTooLong {
+            bound: TemporalBound::HopWindow,
+            max: Duration::minutes(1),
+            by: Duration::minutes(4),
+        })
+    );
+    Verifier::new(&set.registry, None)
+        .with_temporal(tight)
+        .verify_hop(&clamped, &pca0, now, false)
+        .expect("clamped hop");
+}
+
+#[test]
+fn lineage_lifetime_bounds_the_origin() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let policy = TemporalPolicy {
+        max_lineage_lifetime: Duration::hours(12),
+        ..Default::default()
+    };
+    let err = Verifier::new(&set.registry, None)
+        .with_temporal(policy)
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    let PicError::Origin(OriginError::Validity(err)) = err else {
+        panic!("expected an origin validity error, got {err:?}");
+    };
+    assert_eq!(err.bound(), Some(TemporalBound::LineageLifetime));
+    assert_eq!(
+        err.to_string(),
+        "lineage lifetime exceeded by 12h (max 12h)"
+    );
+}
+
+#[test]
+fn an_origin_minted_under_a_strict_policy_verifies_under_it() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let strict = TemporalPolicy {
+        max_lineage_lifetime: Duration::hours(12),
+        max_challenge_lifetime: Duration::minutes(1),
+        ..Default::default()
+    };
+    let opts = OriginOptions {
+        temporal: strict,
+        ..Default::default()
+    };
+    let pca0 = mint_pca0_with(set.identity("alice"), inv(), None, now, &opts).expect("mint");
+    let time = |s: &str| pic::parse_rfc3339(s).unwrap();
+    assert_eq!(
+        time(&pca0.expires_at) - time(&pca0.issued_at),
+        Duration::hours(12)
+    );
+    assert_eq!(
+        time(&pca0.continuation.expires_at) - time(&pca0.issued_at),
+        Duration::minutes(1)
+    );
+    Verifier::new(&set.registry, None)
+        .with_temporal(strict)
+        .verify_origin(&pca0, now)
+        .expect("clamped origin");
+
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .with_temporal(strict)
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    Verifier::new(&set.registry, None)
+        .with_temporal(strict)
+        .verify_full_chain(&[pca0, pca1], now)
+        .expect("clamped chain");
+}
+
+#[test]
+fn the_policy_caps_a_long_challenge_lifetime() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let opts = OriginOptions {
+        temporal_profile: Some(TemporalProfile {
+            max_challenge_lifetime_seconds: 4 * 60 * 60,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let pca0 = mint_pca0_with(set.identity("alice"), inv(), None, now, &opts).expect("mint");
+    let policy = TemporalPolicy {
+        max_challenge_lifetime: Duration::minutes(10),
+        ..Default::default()
+    };
+    let err = Verifier::new(&set.registry, None)
+        .with_temporal(policy)
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    let PicError::Origin(OriginError::Validity(err)) = err else {
+        panic!("expected an origin validity error, got {err:?}");
+    };
+    assert_eq!(
+        err,
+        ValidityError::WindowTooLong {
+            bound: TemporalBound::ChallengeLifetime,
+            max: Duration::minutes(10),
+            by: Duration::minutes(230),
+        }
+    );
+    Verifier::new(&set.registry, None)
+        .verify_origin(&pca0, now)
+        .expect("the profile alone allows it");
+}
+
+#[test]
+fn successor_issued_before_its_predecessor_reports_the_gap() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let behind = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now - Duration::milliseconds(200))
+        .expect("hop");
+    let err = temporal(
+        Verifier::new(&set.registry, None)
+            .verify_hop(&behind, &pca0, now, false)
+            .unwrap_err(),
+    );
+    assert!(matches!(
+        err,
+        TemporalError::IssuedBeforePredecessor { by, .. } if by == Duration::milliseconds(200)
+    ));
+    Verifier::new(&set.registry, None)
+        .with_temporal(TemporalPolicy::default().with_skew(Duration::milliseconds(250)))
+        .verify_hop(&behind, &pca0, now, false)
+        .expect("within the backward skew");
+}
+
+#[test]
+fn live_acceptance_reads_the_verifiers_clock() {
+    let set = fixtureset::load().expect("fixtures");
+    let now = Utc::now();
+    let clock = FixedClock::new(now);
+    let pca0 = mint_pca0(set.identity("alice"), inv(), None, now).expect("mint");
+    let pca1 = Prover::new(set.identity("gateway"), set.attestation("gateway"))
+        .continue_(&pca0, inv(), req(), now)
+        .expect("hop");
+    let env = wrap_envelope(set.identity("gateway"), &pca0, &pca1).expect("envelope");
+
+    clock.advance(Duration::minutes(6));
+    let err = Verifier::new(&set.registry, None)
+        .with_clock(&clock)
+        .accept_envelope(&env)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Continuation {
+            error: ContinuationError::Expired { by, .. },
+            ..
+        } if by == Duration::minutes(1)
+    ));
+
+    clock.set(now + Duration::seconds(1));
+    Verifier::new(&set.registry, None)
+        .with_clock(&clock)
+        .accept_envelope(&env)
+        .expect("accepted at the clock's instant");
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Hostile-input tests: malformed timestamps and counters become typed errors
+//! instead of panics, and untrusted bytes are held to the decoder's limits
+//! (size, depth, chain length, carried lineages, string length, unknown fields)
+//! in both wire encodings.
+
+mod common;
+
+use chrono::Utc;
+use common::resign;
+use pic::codec::encode_cose;
+use pic::scenario::World;
+use pic::{
+    wrap_envelope, CodecError, CoordinateError, Envelope, Limit, Limits, Pca, PicError, Prover,
+    TemporalError, ValidityError, Verifier,
+};
+
+fn limit_of(e: PicError) -> (Limit, usize, usize) {
+    match e {
+        PicError::Codec(CodecError::Limit { limit, max, found }) => (limit, max, found),
+        other => panic!("expected a limit error, got {other:?}"),
+    }
+}
+
+#[test]
+fn malformed_timestamps_are_errors_not_panics() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let mut chain = w.build_chain(1, now).expect("chain");
+
+    // A signed successor whose issuedAt is garbage reaches the temporal check.
+    chain[1].issued_at = "yesterday".to_string();
+    resign(&mut chain[1], gateway);
+    let env = wrap_envelope(gateway, &chain[0], &chain[1]).expect("wrap");
+    let bytes = serde_json::to_vec(&env).unwrap();
+    let env = Envelope::from_untrusted_bytes(&bytes, &Limits::default()).expect("decode");
+    let err = Verifier::new(reg, None)
+        .verify_envelope(&env, now)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-PV-3.3.7-TEMPORAL");
+    assert!(matches!(
+        err,
+        PicError::Temporal {
+            error: TemporalError::MalformedTime(ref t),
+            ..
+        } if t.value == "yesterday"
+    ));
+
+    // A PCA0 signed with a malformed expiry fails origin validation.
+    let mut pca0 = chain[0].clone();
+    pca0.expires_at = "2035-13-45T99:00:00Z".to_string();
+    resign(&mut pca0, w.set.identity("alice"));
+    let err = Verifier::new(reg, None)
+        .verify_origin(&pca0, now)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        PicError::Origin(pic::OriginError::Validity(ValidityError::MalformedTime(_)))
+    ));
+}
+
+#[test]
+fn counter_at_u64_max_is_rejected_without_overflow() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let gateway = w.set.identity("gateway");
+    let mut chain = w.build_chain(1, now).expect("chain");
+    chain[0].lineage_counter = u64::MAX;
+    let pred_digest = chain[0].digest();
+    if let Some(por) = chain[1].proof_of_relationship.as_mut() {
+        por.previous_pca_hash = pred_digest;
+    }
+    resign(&mut chain[1], gateway);
+    let err = Verifier::new(&w.set.registry, None)
+        .verify_hop(&chain[1], &chain[0], now, false)
+        .unwrap_err();
+    assert_eq!(err.code(), "PIC-REV-2.3-COORDINATES");
+    let exhausted = CoordinateError::CounterExhausted {
+        predecessor: u64::MAX,
+    };
+    assert_eq!(
+        err,
+        PicError::Coordinates {
+            hop: None,
+            error: exhausted.clone(),
+        }
+    );
+
+    // The Prover refuses to continue it rather than overflow.
+    let req = chain[1]
+        .proof_of_relationship
+        .as_ref()
+        .expect("PoR")
+        .request
+        .clone();
+    let err = Prover::new(gateway, w.set.attestation("gateway"))
+        .continue_(&chain[0], chain[1].invariants.clone(), req, now)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        PicError::Coordinates {
+            hop: None,
+            error: exhausted,
+        }
+    );
+}
+
+#[test]
+fn limits_bound_untrusted_json() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let chain = w.build_chain(1, now).expect("chain");
+    let bytes = serde_json::to_vec(&chain[1]).unwrap();
+    let limits = Limits::default();
+    Pca::from_untrusted_bytes(&bytes, &limits).expect("honest PCA rejected");
+
+    let small = Limits {
+        max_bytes: 512,
+        ..limits
+    };
+    let (limit, max, found) = limit_of(Pca::from_untrusted_bytes(&bytes, &small).unwrap_err());
+    assert_eq!((limit, max, found), (Limit::Bytes, 512, bytes.len()));
+
+    let deep = format!("{}{}", "[".repeat(40), "]".repeat(40));
+    let doc = format!(r#"{{"envelope":{{"forwardedBy":{deep}}}}}"#);
+    let (limit, ..) =
+        limit_of(Envelope::from_untrusted_bytes(doc.as_bytes(), &limits).unwrap_err());
+    assert_eq!(limit, Limit::Depth);
+
+    // Nesting far past the parser's own recursion limit is an error, not a
+    // stack overflow.
+    let abyss = "[".repeat(100_000);
+    assert!(matches!(
+        Envelope::from_untrusted_bytes(abyss.as_bytes(), &limits).unwrap_err(),
+        PicError::Codec(CodecError::Json { .. })
+    ));
+
+    let long = format!(r#"{{"envelope":{{"forwardedBy":"{}"}}}}"#, "x".repeat(5000));
+    let (limit, max, found) =
+        limit_of(Envelope::from_untrusted_bytes(long.as_bytes(), &limits).unwrap_err());
+    assert_eq!((limit, max, found), (Limit::StringLength, 4096, 5000));
+
+    let carried = vec![serde_json::json!({"label": "x", "chain": []}); 17];
+    let mut pca: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    pca["multiLineage"] = serde_json::json!({ "carriedLineages": carried });
+    let (limit, ..) = limit_of(
+        Pca::from_untrusted_bytes(&serde_json::to_vec(&pca).unwrap(), &limits).unwrap_err(),
+    );
+    assert_eq!(limit, Limit::CarriedLineages);
+
+    let long_chain = vec![serde_json::Value::Null; 129];
+    pca["multiLineage"] = serde_json::json!({ "carriedLineages": [{ "chain": long_chain }] });
+    let (limit, ..) = limit_of(
+        Pca::from_untrusted_bytes(&serde_json::to_vec(&pca).unwrap(), &limits).unwrap_err(),
+    );
+    assert_eq!(limit, Limit::ChainLength);
+}
+
+#[test]
+fn strict_mode_rejects_unknown_fields() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let chain = w.build_chain(1, now).expect("chain");
+    let mut pca: serde_json::Value = serde_json::to_value(&chain[1]).unwrap();
+    pca["proofOfRelationship"]["request"]["admin"] = serde_json::json!(true);
+    let bytes = serde_json::to_vec(&pca).unwrap();
+
+    let err = Pca::from_untrusted_bytes(&bytes, &Limits::default()).unwrap_err();
+    assert_eq!(
+        err,
+        PicError::Codec(CodecError::UnknownField {
+            path: "proofOfRelationship.request.admin".to_string()
+        })
+    );
+    let lax = Limits {
+        strict: false,
+        ..Limits::default()
+    };
+    let p = Pca::from_untrusted_bytes(&bytes, &lax).expect("lax decode");
+    assert_eq!(
+        p.digest(),
+        chain[1].digest(),
+        "unknown field reached the digest"
+    );
+}
+
+#[test]
+fn verifier_limits_apply_to_either_encoding() {
+    let now = Utc::now();
+    let w = World::new().expect("world");
+    let reg = &w.set.registry;
+    let gateway = w.set.identity("gateway");
+    let chain = w.build_chain(4, now).expect("chain");
+
+    let mut v = Verifier::new(reg, None);
+    v.limits.max_bytes = 1024;
+    let env = wrap_envelope(gateway, &chain[0], &chain[1]).expect("wrap");
+    let (limit, ..) = limit_of(
+        v.verify_envelope_bytes(&encode_cose(&env, gateway).expect("seal"), now)
+            .unwrap_err(),
+    );
+    assert_eq!(limit, Limit::Bytes);
+
+    let wire: Vec<Vec<u8>> = chain
+        .iter()
+        .map(|p| encode_cose(p, gateway).expect("seal"))
+        .collect();
+    let mut v = Verifier::new(reg, None);
+    v.limits.max_chain_len = 3;
+    let (limit, max, found) = limit_of(v.verify_full_chain_bytes(&wire, now).unwrap_err());
+    assert_eq!((limit, max, found), (Limit::ChainLength, 3, 5));
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Based on the Provenance Identity Continuity (PIC) Model created by Nicola Gallo.
+// Conforms to the PIC Specification published and maintained by Nitro Agility S.r.l.
+
+//! Deterministic-mode and test-vector tests: seeded entropy and a fixed clock
+//! reproduce artifacts byte for byte, the committed corpus is current and
+//! replays cleanly, and replay reports every divergence.
+
+use chrono::Duration;
+use pic::sources::{random_b64_from, ENTROPY_DOMAIN};
+use pic::vectors::{self, Corpus};
+use pic::{
+    fixtureset, mint_pca0_with, parse_rfc3339, Clock, Entropy, FixedClock, Invariants,
+    OriginOptions, SandboxedExecution, SeededEntropy,
+};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+fn committed() -> Corpus {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures/vectors/corpus.json");
+    serde_json::from_slice(&std::fs::read(path).expect("corpus")).expect("corpus json")
+}
+
+#[test]
+fn seeded_entropy_is_a_sha256_counter_stream() {
+    let block = |i: u64| -> Vec<u8> {
+        let mut h = Sha256::new();
+        h.update(ENTROPY_DOMAIN);
+        h.update(b"seed");
+        h.update(i.to_be_bytes());
+        h.finalize().to_vec()
+    };
+    let e = SeededEntropy::new(b"seed");
+    let mut first = [0u8; 20];
+    let mut second = [0u8; 20];
+    e.fill(&mut first);
+    e.fill(&mut second);
+    let stream = [block(0), block(1)].concat();
+    assert_eq!(first, stream[..20]);
+    assert_eq!(second, stream[20..40]);
+    assert_ne!(
+        random_b64_from(&SeededEntropy::new(b"a"), 32),
+        random_b64_from(&SeededEntropy::new(b"b"), 32)
+    );
+}
+
+#[test]
+fn seeded_sources_reproduce_artifacts() {
+    let set = fixtureset::load().expect("fixtures");
+    let clock = FixedClock::new(parse_rfc3339("2026-06-01T12:00:00Z").expect("time"));
+    let inv = Invariants {
+        operations: vec!["read:/user/*".to_string()],
+        ..Default::default()
+    };
+    let mint = || {
+        mint_pca0_with(
+            set.identity("alice"),
+            inv.clone(),
+            None,
+            clock.now(),
+            &OriginOptions {
+                entropy: &SeededEntropy::new(b"alice"),
+                ..Default::default()
+            },
+        )
+        .expect("mint")
+    };
+    assert_eq!(mint().digest(), mint().digest());
+    clock.advance(Duration::seconds(1));
+    let later = mint();
+    assert_eq!(
+        parse_rfc3339(&later.issued_at).expect("issuedAt"),
+        parse_rfc3339("2026-06-01T12:00:01Z").expect("time")
+    );
+
+    let originate = || {
+        SandboxedExecution::originate_with(
+            set.identity("enforcement-origin"),
+            clock.now(),
+            &SeededEntropy::new(b"outer"),
+        )
+        .expect("originate")
+    };
+    assert_eq!(originate().origin().digest(), originate().origin().digest());
+}
+
+#[test]
+fn committed_corpus_is_current_and_replays() {
+    let set = fixtureset::load().expect("fixtures");
+    let corpus = committed();
+    assert_eq!(
+        vectors::generate(set).expect("generate"),
+        corpus,
+        "regenerate with `cargo run --bin pic-vectors -- generate`"
+    );
+    assert_eq!(vectors::replay(&corpus, set), []);
+    let verdicts: Vec<_> = corpus
+        .vectors
+        .iter()
+        .map(|v| (v.name.as_str(), v.expected.verdict.code.as_str()))
+        .collect();
+    assert!(verdicts.contains(&("two-hops", "")));
+    assert!(verdicts.contains(&("tampered", "PIC-PV-3.3.1-INTEGRITY")));
+}
+
+#[test]
+fn replay_reports_every_divergence() {
+    let set = fixtureset::load().expect("fixtures");
+    let mut corpus = committed();
+    let two_hops = corpus
+        .vectors
+        .iter_mut()
+        .find(|v| v.name == "two-hops")
+        .expect("two-hops vector");
+    two_hops.expected.pcas[2].digest = "sha256:00".to_string();
+    two_hops.inputs.seed = "another seed".to_string();
+
+    let fields: Vec<_> = vectors::replay(&corpus, set)
+        .into_iter()
+        .map(|d| {
+            assert_eq!(d.vector, "two-hops");
+            d.field
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            "pcas[0].bytes",
+            "pcas[0].digest",
+            "pcas[1].bytes",
+            "pcas[1].digest",
+            "pcas[2].bytes",
+            "pcas[2].digest",
+            "lineageId",
+            "branchId"
+        ]
+    );
+
+    corpus.version += 1;
+    assert_eq!(vectors::replay(&corpus, set)[0].field, "version");
+}